@@ -0,0 +1,449 @@
+//! `synthonyx-audit`: verify, tail, search, and export audit logs written
+//! by `synthonyx-kit-audit`.
+//!
+//! Every command accepts either a single log file or a segment directory
+//! (one containing `manifest.json`). Reading always re-verifies the chain;
+//! a broken chain is reported with the file and line where it breaks and
+//! exits with status 1. `verify` scans past the first break and reports
+//! every defect.
+#![deny(missing_docs, unsafe_code, rust_2018_idioms)]
+
+mod output;
+mod timefmt;
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use synthonyx_kit_audit::{
+    AuditQuery, AuditReader, FindingKind, ForensicReport, SegmentManifest, forensic_scan_from,
+    verify_segments,
+};
+use synthonyx_kit_core::{AuditError, CorrelationId, OriginKind, Outcome, UnixNanos};
+
+use crate::output::{EntryWriter, Format};
+
+#[derive(Parser)]
+#[command(name = "synthonyx-audit", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scan the whole hash chain and report every break and the ranges that
+    /// can still be trusted.
+    Verify {
+        /// Log file or segment directory.
+        path: PathBuf,
+        /// Print the forensic report as JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the last entries, optionally following new ones.
+    Tail {
+        /// Log file or segment directory. A followed directory moves on to
+        /// each new segment as the writer rotates.
+        path: PathBuf,
+        /// Number of entries to print.
+        #[arg(short = 'n', long, default_value_t = 10)]
+        lines: usize,
+        /// Keep running and print entries as they are appended.
+        #[arg(short, long)]
+        follow: bool,
+        /// Poll interval for `--follow`, in milliseconds.
+        #[arg(long, default_value_t = 500)]
+        interval_ms: u64,
+        #[command(flatten)]
+        filter: Filter,
+        /// Output format. `json` is written as JSON lines with `--follow`,
+        /// since the array would never be closed.
+        #[arg(long, value_enum, default_value_t = Format::Jsonl)]
+        format: Format,
+    },
+    /// Print the entries matching the filters.
+    Search {
+        /// Log file or segment directory.
+        path: PathBuf,
+        #[command(flatten)]
+        filter: Filter,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Jsonl)]
+        format: Format,
+    },
+    /// Write the entries matching the filters to a file for auditors.
+    Export {
+        /// Log file or segment directory.
+        path: PathBuf,
+        #[command(flatten)]
+        filter: Filter,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+        /// Destination file.
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+/// Entry filters shared by `tail`, `search`, and `export`.
+#[derive(Args)]
+struct Filter {
+    /// Only entries with this correlation id (32 hex characters).
+    #[arg(long, value_parser = parse_correlation_id)]
+    correlation_id: Option<CorrelationId>,
+    /// Only entries linked to this data subject.
+    #[arg(long)]
+    subject: Option<String>,
+    /// Only entries written by this module.
+    #[arg(long)]
+    module: Option<String>,
+    /// Only entries for this action.
+    #[arg(long)]
+    action: Option<String>,
+    /// Only entries with this outcome.
+    #[arg(long, value_enum)]
+    outcome: Option<OutcomeArg>,
+    /// Only entries whose origin has this kind.
+    #[arg(long, value_enum)]
+    origin: Option<OriginArg>,
+    /// Only entries at or after this time (RFC 3339 UTC or epoch nanos).
+    #[arg(long, value_parser = timefmt::parse)]
+    since: Option<UnixNanos>,
+    /// Only entries before this time (RFC 3339 UTC or epoch nanos).
+    #[arg(long, value_parser = timefmt::parse)]
+    until: Option<UnixNanos>,
+}
+
+impl Filter {
+    fn query(&self) -> AuditQuery {
+        let mut q = AuditQuery::new();
+        if let Some(id) = self.correlation_id {
+            q = q.correlation_id(id);
+        }
+        if let Some(s) = &self.subject {
+            q = q.subject(s.clone());
+        }
+        if let Some(m) = &self.module {
+            q = q.module(m.clone());
+        }
+        if let Some(a) = &self.action {
+            q = q.action(a.clone());
+        }
+        if let Some(o) = self.outcome {
+            q = q.outcome(o.into());
+        }
+        if let Some(k) = self.origin {
+            q = q.origin_kind(k.into());
+        }
+        if let Some(ts) = self.since {
+            q = q.since(ts);
+        }
+        if let Some(ts) = self.until {
+            q = q.until(ts);
+        }
+        q
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutcomeArg {
+    Success,
+    Denied,
+    Error,
+}
+
+impl From<OutcomeArg> for Outcome {
+    fn from(o: OutcomeArg) -> Self {
+        match o {
+            OutcomeArg::Success => Outcome::Success,
+            OutcomeArg::Denied => Outcome::Denied,
+            OutcomeArg::Error => Outcome::Error,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OriginArg {
+    System,
+    Service,
+    User,
+    Anonymous,
+}
+
+impl From<OriginArg> for OriginKind {
+    fn from(k: OriginArg) -> Self {
+        match k {
+            OriginArg::System => OriginKind::System,
+            OriginArg::Service => OriginKind::Service,
+            OriginArg::User => OriginKind::User,
+            OriginArg::Anonymous => OriginKind::Anonymous,
+        }
+    }
+}
+
+fn parse_correlation_id(s: &str) -> Result<CorrelationId, String> {
+    let mut id = [0u8; 16];
+    hex::decode_to_slice(s, &mut id).map_err(|e| format!("{s}: {e}"))?;
+    Ok(CorrelationId(id))
+}
+
+/// A command failure: either the log failed verification (exit 1) or the
+/// command could not run (exit 2).
+enum Failure {
+    Broken(String),
+    Other(String),
+}
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Failure::Other(e.to_string())
+    }
+}
+
+impl From<AuditError> for Failure {
+    fn from(e: AuditError) -> Self {
+        match e {
+            AuditError::Io(e) => Failure::Other(e.to_string()),
+            other => Failure::Broken(other.to_string()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::Broken(msg)) => {
+            eprintln!("BROKEN {msg}");
+            ExitCode::from(1)
+        }
+        Err(Failure::Other(msg)) => {
+            eprintln!("error: {msg}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Failure> {
+    match command {
+        Command::Verify { path, json } => verify(&path, json),
+        Command::Tail {
+            path,
+            lines,
+            follow,
+            interval_ms,
+            filter,
+            format,
+        } => tail(&path, lines, follow, interval_ms, &filter, format),
+        Command::Search {
+            path,
+            filter,
+            format,
+        } => {
+            let reader = open(&path)?.with_query(filter.query());
+            copy(reader, EntryWriter::new(io::stdout().lock(), format)?)?;
+            Ok(())
+        }
+        Command::Export {
+            path,
+            filter,
+            format,
+            output,
+        } => {
+            let reader = open(&path)?.with_query(filter.query());
+            let out = BufWriter::new(File::create(&output)?);
+            let count = copy(reader, EntryWriter::new(out, format)?)?;
+            eprintln!("exported {count} entries to {}", output.display());
+            Ok(())
+        }
+    }
+}
+
+fn is_segment_dir(path: &Path) -> bool {
+    path.is_dir()
+}
+
+fn open(path: &Path) -> Result<AuditReader, Failure> {
+    let reader = if is_segment_dir(path) {
+        AuditReader::open_segments(path)?
+    } else {
+        AuditReader::open(path)?
+    };
+    Ok(reader)
+}
+
+/// Turn a reader error into a failure naming the line it occurred on.
+fn located(reader: &AuditReader, e: AuditError) -> Failure {
+    match (Failure::from(e), reader.position()) {
+        (Failure::Broken(msg), Some((file, line))) => {
+            Failure::Broken(format!("{}:{line}: {msg}", file.display()))
+        }
+        (failure, _) => failure,
+    }
+}
+
+fn copy<W: Write>(mut reader: AuditReader, mut out: EntryWriter<W>) -> Result<u64, Failure> {
+    while let Some(entry) = reader.next() {
+        let entry = entry.map_err(|e| located(&reader, e))?;
+        out.write(&entry)?;
+    }
+    Ok(out.finish()?)
+}
+
+/// Each file to scan with the hash its first entry must chain to: the
+/// file itself, or every segment in a directory in order.
+fn scan_plan(path: &Path) -> Result<Vec<(PathBuf, [u8; 32])>, Failure> {
+    if !is_segment_dir(path) {
+        return Ok(vec![(path.to_path_buf(), [0u8; 32])]);
+    }
+    let manifest = SegmentManifest::load(path)?;
+    let mut plan: Vec<_> = manifest
+        .segments
+        .iter()
+        .map(|s| (path.join(&s.file), s.start_hash))
+        .collect();
+    let active = manifest.active_path(path);
+    if active.exists() {
+        plan.push((active, manifest.head()));
+    }
+    Ok(plan)
+}
+
+fn describe(kind: &FindingKind) -> String {
+    match kind {
+        FindingKind::ChainBroken { expected, actual } => format!(
+            "chain broken: expected prev_hash {}, got {}",
+            hex::encode(expected),
+            hex::encode(actual)
+        ),
+        FindingKind::Gap { links_to_line: 0 } => "gap: chains to the start of the file".into(),
+        FindingKind::Gap { links_to_line } => format!("gap: chains to line {links_to_line}"),
+        FindingKind::Malformed { error } => format!("malformed line: {error}"),
+        FindingKind::TornTail { len } => format!("torn tail: {len} bytes after the last newline"),
+    }
+}
+
+fn verify(path: &Path, json: bool) -> Result<(), Failure> {
+    let mut reports: Vec<(PathBuf, ForensicReport)> = Vec::new();
+    for (file, start) in scan_plan(path)? {
+        let report = forensic_scan_from(&file, start)?;
+        reports.push((file, report));
+    }
+    let clean = reports.iter().all(|(_, r)| r.is_clean());
+    // The per-file scans locate defects; for a directory this also checks
+    // the manifest and that each segment continues the previous one.
+    let head = match (clean, is_segment_dir(path)) {
+        (true, true) => Some(verify_segments(path)?),
+        (true, false) => reports.last().map(|(_, r)| r.head),
+        (false, _) => None,
+    };
+
+    if json {
+        let files: Vec<_> = reports
+            .iter()
+            .map(|(file, report)| serde_json::json!({ "file": file, "report": report }))
+            .collect();
+        let doc = serde_json::json!({ "clean": clean, "files": files });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&doc).map_err(|e| Failure::Other(e.to_string()))?
+        );
+    } else {
+        for (file, report) in &reports {
+            for f in &report.findings {
+                eprintln!(
+                    "BROKEN {}:{}: {} (byte {})",
+                    file.display(),
+                    f.line,
+                    describe(&f.kind),
+                    f.offset
+                );
+            }
+            if !clean {
+                for r in &report.trusted {
+                    println!(
+                        "TRUSTED {}:{}-{}: {} entries, bytes {}..{}{}",
+                        file.display(),
+                        r.first_line,
+                        r.last_line,
+                        r.entries,
+                        r.start_offset,
+                        r.end_offset,
+                        if r.linked { "" } else { " (unlinked start)" }
+                    );
+                }
+            }
+        }
+    }
+
+    match head {
+        Some(head) => {
+            if !json {
+                let entries: u64 = reports.iter().map(|(_, r)| r.entries).sum();
+                println!(
+                    "OK {}: {entries} entries, head {}",
+                    path.display(),
+                    hex::encode(head)
+                );
+            }
+            Ok(())
+        }
+        None => {
+            let findings: usize = reports.iter().map(|(_, r)| r.findings.len()).sum();
+            Err(Failure::Broken(format!(
+                "{}: {findings} finding(s)",
+                path.display()
+            )))
+        }
+    }
+}
+
+fn tail(
+    path: &Path,
+    lines: usize,
+    follow: bool,
+    interval_ms: u64,
+    filter: &Filter,
+    format: Format,
+) -> Result<(), Failure> {
+    // A JSON array can never be closed while following; stream NDJSON.
+    let format = match format {
+        Format::Json if follow => Format::Jsonl,
+        other => other,
+    };
+    let mut reader = open(path)?.with_query(filter.query());
+    if follow {
+        reader = reader.follow();
+    }
+    let mut last = VecDeque::with_capacity(lines);
+    while let Some(entry) = reader.next() {
+        let entry = entry.map_err(|e| located(&reader, e))?;
+        if last.len() == lines {
+            last.pop_front();
+        }
+        if lines > 0 {
+            last.push_back(entry);
+        }
+    }
+    let mut out = EntryWriter::new(io::stdout().lock(), format)?;
+    for entry in &last {
+        out.write(entry)?;
+    }
+    if !follow {
+        out.finish()?;
+        return Ok(());
+    }
+    loop {
+        std::thread::sleep(Duration::from_millis(interval_ms));
+        while let Some(entry) = reader.next() {
+            let entry = entry.map_err(|e| located(&reader, e))?;
+            out.write(&entry)?;
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Entry output formats: JSON lines, a pretty JSON array, and CSV.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use synthonyx_kit_core::{AuditEntry, OriginKind, Outcome};
+
+use crate::timefmt;
+
+/// CSV column order. `fields` is the entry's field map as compact JSON;
+/// `sequence` and `writer` are empty for entries written without them.
+///
+/// Cells starting with `=`, `+`, `-`, `@`, tab or carriage return are
+/// prefixed with `'` so spreadsheets show them as text instead of running
+/// them as formulas: principals, subjects and field values are
+/// attacker-influenced (OWASP CSV injection guidance).
+const CSV_HEADER: &str = "timestamp,time,correlation_id,module,action,outcome,origin_kind,\
+                          principal,service,subject,fields,prev_hash,sequence,writer";
+
+/// How entries are written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub(crate) enum Format {
+    /// One `AuditEntry` JSON object per line.
+    Jsonl,
+    /// A single pretty-printed JSON array.
+    Json,
+    /// CSV with a header row, for spreadsheets.
+    Csv,
+}
+
+/// Streams entries in one [`Format`], emitting any header and footer.
+pub(crate) struct EntryWriter<W: Write> {
+    out: W,
+    format: Format,
+    count: u64,
+}
+
+impl<W: Write> EntryWriter<W> {
+    pub(crate) fn new(mut out: W, format: Format) -> io::Result<Self> {
+        match format {
+            Format::Json => out.write_all(b"[")?,
+            Format::Csv => writeln!(out, "{CSV_HEADER}")?,
+            Format::Jsonl => {}
+        }
+        Ok(Self {
+            out,
+            format,
+            count: 0,
+        })
+    }
+
+    pub(crate) fn write(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        match self.format {
+            Format::Jsonl => writeln!(self.out, "{}", serde_json::to_string(entry)?)?,
+            Format::Json => {
+                let sep = if self.count == 0 { "\n" } else { ",\n" };
+                let pretty = serde_json::to_string_pretty(entry)?;
+                self.out.write_all(sep.as_bytes())?;
+                for (i, line) in pretty.lines().enumerate() {
+                    if i > 0 {
+                        self.out.write_all(b"\n")?;
+                    }
+                    write!(self.out, "  {line}")?;
+                }
+            }
+            Format::Csv => writeln!(self.out, "{}", csv_row(entry)?)?,
+        }
+        self.count += 1;
+        // Keep `tail --follow` output live when piped.
+        self.out.flush()
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<u64> {
+        if self.format == Format::Json {
+            let close: &[u8] = if self.count == 0 { b"]\n" } else { b"\n]\n" };
+            self.out.write_all(close)?;
+        }
+        self.out.flush()?;
+        Ok(self.count)
+    }
+}
+
+fn csv_row(entry: &AuditEntry) -> io::Result<String> {
+    let fields = serde_json::to_string(&entry.fields)?;
+    let cells = [
+        entry.timestamp.0.to_string(),
+        timefmt::format(entry.timestamp),
+        entry.correlation_id.to_string(),
+        entry.module.to_string(),
+        entry.action.to_string(),
+        outcome_name(entry.outcome).to_string(),
+        origin_name(entry.origin.kind).to_string(),
+        entry.origin.principal.clone().unwrap_or_default(),
+        entry
+            .origin
+            .service
+            .as_deref()
+            .unwrap_or_default()
+            .to_string(),
+        entry.subject.clone().unwrap_or_default(),
+        fields,
+        hex::encode(entry.prev_hash),
+        entry.sequence.map(|n| n.to_string()).unwrap_or_default(),
+        entry.writer.clone().unwrap_or_default(),
+    ];
+    Ok(cells
+        .iter()
+        .map(|c| csv_cell(c))
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Neutralise a cell a spreadsheet would treat as a formula, then quote it
+/// per RFC 4180 when it contains a separator, quote, or line break.
+fn csv_cell(cell: &str) -> String {
+    let cell = if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{cell}"))
+    } else {
+        Cow::Borrowed(cell)
+    };
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.into_owned()
+    }
+}
+
+fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Success => "success",
+        Outcome::Denied => "denied",
+        Outcome::Error => "error",
+    }
+}
+
+fn origin_name(kind: OriginKind) -> &'static str {
+    match kind {
+        OriginKind::System => "system",
+        OriginKind::Service => "service",
+        OriginKind::User => "user",
+        OriginKind::Anonymous => "anonymous",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_cells_are_quoted_only_when_needed() {
+        assert_eq!(csv_cell("plain"), "plain");
+        assert_eq!(csv_cell("a,b"), "\"a,b\"");
+        assert_eq!(csv_cell("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_cell(""), "");
+        // Formula triggers are neutralised, quoted too where needed.
+        assert_eq!(csv_cell("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_cell("+1"), "'+1");
+        assert_eq!(csv_cell("-2"), "'-2");
+        assert_eq!(csv_cell("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_cell("\tcmd"), "'\tcmd");
+        assert_eq!(csv_cell("\rcmd"), "\"'\rcmd\"");
+        assert_eq!(csv_cell("a=b"), "a=b");
+    }
+}
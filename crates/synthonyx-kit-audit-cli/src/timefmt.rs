@@ -0,0 +1,108 @@
+//! RFC 3339 (UTC) formatting and parsing for [`UnixNanos`], without a
+//! calendar dependency.
+
+use synthonyx_kit_core::UnixNanos;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+const SECS_PER_DAY: u128 = 86_400;
+
+/// Format `ts` as `YYYY-MM-DDTHH:MM:SS.nnnnnnnnnZ`.
+pub(crate) fn format(ts: UnixNanos) -> String {
+    let secs = ts.0 / NANOS_PER_SEC;
+    let nanos = ts.0 % NANOS_PER_SEC;
+    let days = secs / SECS_PER_DAY;
+    let rem = secs % SECS_PER_DAY;
+    let (y, m, d) = civil_from_days(days as i64);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}.{nanos:09}Z",
+        rem / 3600,
+        (rem / 60) % 60,
+        rem % 60
+    )
+}
+
+/// Parse a CLI time bound: either integer nanoseconds since the UNIX epoch
+/// or an RFC 3339 UTC timestamp (`2024-05-01T12:00:00Z`, optionally with a
+/// fractional second).
+pub(crate) fn parse(s: &str) -> Result<UnixNanos, String> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse().map(UnixNanos).map_err(|e| format!("{s}: {e}"));
+    }
+    let bad = || format!("{s}: expected nanoseconds or RFC 3339 UTC time (YYYY-MM-DDTHH:MM:SSZ)");
+    let rest = s
+        .strip_suffix('Z')
+        .or_else(|| s.strip_suffix("+00:00"))
+        .ok_or_else(bad)?;
+    let (date, time) = rest.split_once(['T', ' ']).ok_or_else(bad)?;
+    let date: Vec<&str> = date.split('-').collect();
+    let (clock, frac) = time.split_once('.').unwrap_or((time, ""));
+    let clock: Vec<&str> = clock.split(':').collect();
+    if date.len() != 3 || clock.len() != 3 || frac.len() > 9 {
+        return Err(bad());
+    }
+    let num = |part: &str| part.parse::<u32>().map_err(|_| bad());
+    let (y, m, d) = (num(date[0])?, num(date[1])?, num(date[2])?);
+    let (hh, mm, ss) = (num(clock[0])?, num(clock[1])?, num(clock[2])?);
+    if y < 1970 || !(1..=12).contains(&m) || !(1..=31).contains(&d) || hh > 23 || mm > 59 || ss > 60
+    {
+        return Err(bad());
+    }
+    let frac_nanos = if frac.is_empty() {
+        0
+    } else {
+        u128::from(num(frac)?) * 10u128.pow(9 - frac.len() as u32)
+    };
+    let days = days_from_civil(i64::from(y), m, d) as u128;
+    let secs = days * SECS_PER_DAY + u128::from(hh * 3600 + mm * 60 + ss);
+    Ok(UnixNanos(secs * NANOS_PER_SEC + frac_nanos))
+}
+
+// Howard Hinnant's `days_from_civil` / `civil_from_days` for the proleptic
+// Gregorian calendar.
+
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = i64::from((m + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_rfc3339() {
+        let ts = parse("2024-02-29T23:59:58.123456789Z").unwrap();
+        assert_eq!(format(ts), "2024-02-29T23:59:58.123456789Z");
+        assert_eq!(parse("1970-01-01T00:00:00Z").unwrap(), UnixNanos(0));
+        assert_eq!(
+            parse("2023-11-14T22:13:20+00:00").unwrap(),
+            UnixNanos(1_700_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn accepts_raw_nanos_and_rejects_garbage() {
+        assert_eq!(parse("42").unwrap(), UnixNanos(42));
+        assert!(parse("2024-13-01T00:00:00Z").is_err());
+        assert!(parse("2024-01-01T00:00:00+02:00").is_err());
+        assert!(parse("yesterday").is_err());
+    }
+}
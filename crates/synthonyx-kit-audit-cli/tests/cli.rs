@@ -0,0 +1,229 @@
+//! End-to-end tests running the `synthonyx-audit` binary.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{FileAuditLogger, RotationPolicy, SegmentedAuditLogger};
+use synthonyx_kit_core::{
+    AuditEntry, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-cli-{pid}-{seq}-{name}"))
+}
+
+fn entry(i: u8) -> AuditEntry {
+    AuditEntry {
+        // 2023-11-14T22:13:20Z plus `i` seconds.
+        timestamp: UnixNanos(1_700_000_000_000_000_000 + u128::from(i) * 1_000_000_000),
+        correlation_id: CorrelationId([i % 2; 16]),
+        module: Cow::Borrowed("accounts"),
+        action: Cow::Owned(format!("act{i}")),
+        origin: OriginSnapshot {
+            kind: OriginKind::User,
+            principal: Some("op,1".into()),
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: Some(format!("subj-{i}")),
+        fields: BTreeMap::from([("seq".to_string(), AuditValue::UInt(u64::from(i)))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn write_log(path: &Path, n: u8) {
+    let logger = FileAuditLogger::open(path).unwrap();
+    for i in 0..n {
+        logger.record(entry(i)).unwrap();
+    }
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_synthonyx-audit"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(out: &Output) -> String {
+    String::from_utf8(out.stdout.clone()).unwrap()
+}
+
+#[test]
+fn verify_reports_ok_and_break_location() {
+    let path = tmp_path("verify.jsonl");
+    write_log(&path, 4);
+    let p = path.to_str().unwrap();
+
+    let out = run(&["verify", p]);
+    assert!(out.status.success());
+    assert!(stdout(&out).contains("4 entries"));
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replacen("\"act1\"", "\"actX\"", 1)).unwrap();
+    let out = run(&["verify", p]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains(&format!("{p}:3:")), "{stderr}");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn verify_reports_every_break_and_trusted_ranges() {
+    let path = tmp_path("forensic.jsonl");
+    write_log(&path, 6);
+    let p = path.to_str().unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let tampered = contents
+        .replacen("\"act1\"", "\"actX\"", 1)
+        .replacen("\"act3\"", "\"actY\"", 1);
+    std::fs::write(&path, tampered).unwrap();
+
+    let out = run(&["verify", p]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr.clone()).unwrap();
+    assert!(stderr.contains(&format!("{p}:3: chain broken")), "{stderr}");
+    assert!(stderr.contains(&format!("{p}:5: chain broken")), "{stderr}");
+    let trusted = stdout(&out);
+    assert!(
+        trusted.contains(&format!("TRUSTED {p}:1-2: 2 entries")),
+        "{trusted}"
+    );
+    assert!(
+        trusted.contains(&format!("TRUSTED {p}:5-6: 2 entries")),
+        "{trusted}"
+    );
+
+    let out = run(&["verify", p, "--json"]);
+    assert_eq!(out.status.code(), Some(1));
+    let doc: serde_json::Value = serde_json::from_str(&stdout(&out)).unwrap();
+    assert_eq!(doc["clean"], false);
+    let findings = doc["files"][0]["report"]["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[1]["kind"], "chain_broken");
+    assert_eq!(findings[1]["line"], 5);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn verify_accepts_segment_directories() {
+    let dir = tmp_path("segments");
+    let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+    logger.record(entry(0)).unwrap();
+    logger.rotate().unwrap();
+    logger.record(entry(1)).unwrap();
+    drop(logger);
+
+    let out = run(&["verify", dir.to_str().unwrap()]);
+    assert!(out.status.success(), "{out:?}");
+    assert!(stdout(&out).contains("2 entries"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn search_filters_by_correlation_id_and_time() {
+    let path = tmp_path("search.jsonl");
+    write_log(&path, 6);
+    let p = path.to_str().unwrap();
+    let odd = "01".repeat(16);
+
+    let out = run(&["search", p, "--correlation-id", &odd]);
+    assert!(out.status.success());
+    assert_eq!(stdout(&out).lines().count(), 3);
+
+    let out = run(&[
+        "search",
+        p,
+        "--since",
+        "2023-11-14T22:13:22Z",
+        "--until",
+        "2023-11-14T22:13:24Z",
+        "--format",
+        "csv",
+    ]);
+    let csv = stdout(&out);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3, "{csv}");
+    assert!(rows[0].starts_with("timestamp,time,correlation_id"));
+    assert!(rows[1].contains("2023-11-14T22:13:22.000000000Z,"));
+    assert!(rows[1].contains(",\"op,1\","));
+    assert!(rows[0].ends_with(",prev_hash,sequence,writer"));
+    assert!(rows[1].ends_with(",2,"), "{}", rows[1]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn tail_and_export() {
+    let path = tmp_path("export.jsonl");
+    let dest = tmp_path("export.json");
+    write_log(&path, 5);
+    let p = path.to_str().unwrap();
+
+    let out = run(&["tail", p, "-n", "2"]);
+    let lines: Vec<String> = stdout(&out).lines().map(str::to_string).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains("\"act4\""));
+
+    let out = run(&[
+        "export",
+        p,
+        "--subject",
+        "subj-2",
+        "-o",
+        dest.to_str().unwrap(),
+    ]);
+    assert!(out.status.success());
+    let exported: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&dest).unwrap()).unwrap();
+    let entries = exported.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["action"], "act2");
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&dest);
+}
+
+#[test]
+fn tail_follows_a_segment_directory_as_ndjson() {
+    let dir = tmp_path("follow-dir");
+    let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+    logger.record(entry(0)).unwrap();
+    logger.rotate().unwrap();
+    logger.record(entry(1)).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_synthonyx-audit"))
+        .args(["tail", dir.to_str().unwrap(), "-f", "--interval-ms", "20"])
+        .args(["--format", "json"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut next_action = || {
+        let line = lines.next().expect("tail exited").unwrap();
+        let entry: serde_json::Value = serde_json::from_str(&line).unwrap();
+        entry["action"].as_str().unwrap().to_string()
+    };
+    assert_eq!(next_action(), "act0");
+    assert_eq!(next_action(), "act1");
+
+    logger.record(entry(2)).unwrap();
+    logger.rotate().unwrap();
+    logger.record(entry(3)).unwrap();
+    assert_eq!(next_action(), "act2");
+    assert_eq!(next_action(), "act3");
+
+    let _ = child.kill();
+    let _ = child.wait();
+    drop(logger);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,381 @@
+//! Compressed, sealed archives of sealed audit segments.
+//!
+//! [`archive_segments`] copies every sealed segment of a segment directory
+//! into an archive directory as a zstd-compressed file, after re-verifying
+//! it, and records it in a signed `archive.json`. Optionally it then
+//! deletes the raw segments from the (hot) segment directory.
+//! [`verify_archive`] checks the archive straight from the compressed
+//! files: seal signature, compressed-file hashes, the chain inside every
+//! archived segment, and agreement with the live segment manifest through
+//! to the live chain head.
+//!
+//! ```text
+//! archive/
+//!   archive.json                  signed: per-segment hashes, counts, time range
+//!   segment-00000000.jsonl.zst
+//!   segment-00000001.jsonl.zst
+//! ```
+
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use synthonyx_kit_core::{AuditError, UnixNanos};
+
+use crate::chain::{self, GENESIS};
+use crate::checkpoint::{CheckpointSigner, CheckpointVerifier};
+use crate::segment::{
+    SegmentInfo, SegmentManifest, check_no_stray_segments, check_sealed, store_json, verify_active,
+};
+
+/// File name of the archive manifest within an archive directory.
+pub const ARCHIVE_MANIFEST_FILE: &str = "archive.json";
+const ARCHIVE_VERSION: u32 = 1;
+/// Domain separator for the archive seal signature.
+const SEAL_DOMAIN: &[u8] = b"synthonyx-kit-audit archive v1\0";
+
+/// One archived segment: the live manifest record plus the compressed
+/// file's location, size and hash.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedSegment {
+    /// The segment's record from the live [`SegmentManifest`].
+    pub segment: SegmentInfo,
+    /// File name of the compressed segment, relative to the archive
+    /// directory.
+    pub file: String,
+    /// Size of the uncompressed segment.
+    pub raw_bytes: u64,
+    /// Size of the compressed file.
+    pub compressed_bytes: u64,
+    /// BLAKE3 hash of the compressed file.
+    #[serde(with = "chain::hex_hash")]
+    pub compressed_hash: [u8; 32],
+}
+
+/// The archive manifest: every archived segment, in chain order, sealed by
+/// a signature over all of it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// Manifest format version.
+    pub version: u32,
+    /// Archived segments, ordered by index.
+    pub segments: Vec<ArchivedSegment>,
+    /// Signature over [`Self::seal_message`]; `None` only while empty.
+    pub seal: Option<ArchiveSeal>,
+}
+
+/// Signature sealing an [`ArchiveManifest`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSeal {
+    /// Identifier of the signing key.
+    pub key_id: String,
+    /// Hex-encoded signature.
+    pub signature: String,
+}
+
+impl Default for ArchiveManifest {
+    fn default() -> Self {
+        Self {
+            version: ARCHIVE_VERSION,
+            segments: Vec::new(),
+            seal: None,
+        }
+    }
+}
+
+impl ArchiveManifest {
+    /// Load the manifest from `dir`, or an empty manifest if none exists.
+    ///
+    /// The seal is not checked; [`verify_archive`] does that.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let path = dir.as_ref().join(ARCHIVE_MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let bytes = fs::read(&path)?;
+        let manifest: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| AuditError::Manifest(format!("{}: {e}", path.display())))?;
+        if manifest.version != ARCHIVE_VERSION {
+            return Err(AuditError::Manifest(format!(
+                "unsupported archive manifest version {}",
+                manifest.version
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// The chain head after the last archived segment (all zeros if none).
+    pub fn head(&self) -> [u8; 32] {
+        self.segments.last().map_or(GENESIS, |s| s.segment.end_hash)
+    }
+
+    /// The bytes the seal signs: a fixed binary encoding of every segment
+    /// record, independent of the JSON layout of `archive.json`.
+    pub fn seal_message(&self) -> Vec<u8> {
+        fn push_str(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_be_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        fn push_time(out: &mut Vec<u8>, t: Option<UnixNanos>) {
+            match t {
+                Some(t) => {
+                    out.push(1);
+                    out.extend_from_slice(&t.0.to_be_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+        let mut out = SEAL_DOMAIN.to_vec();
+        out.extend_from_slice(&(self.segments.len() as u64).to_be_bytes());
+        for archived in &self.segments {
+            let s = &archived.segment;
+            out.extend_from_slice(&s.index.to_be_bytes());
+            push_str(&mut out, &s.file);
+            out.extend_from_slice(&s.entries.to_be_bytes());
+            out.extend_from_slice(&s.start_hash);
+            out.extend_from_slice(&s.end_hash);
+            push_time(&mut out, s.first_timestamp);
+            push_time(&mut out, s.last_timestamp);
+            push_str(&mut out, &archived.file);
+            out.extend_from_slice(&archived.raw_bytes.to_be_bytes());
+            out.extend_from_slice(&archived.compressed_bytes.to_be_bytes());
+            out.extend_from_slice(&archived.compressed_hash);
+        }
+        out
+    }
+
+    fn check_seal(&self, verifier: &dyn CheckpointVerifier) -> Result<(), AuditError> {
+        let Some(seal) = &self.seal else {
+            if self.segments.is_empty() {
+                return Ok(());
+            }
+            return Err(AuditError::Checkpoint(
+                "archive manifest is not sealed".into(),
+            ));
+        };
+        let signature = hex::decode(&seal.signature)
+            .map_err(|e| AuditError::Checkpoint(format!("malformed archive seal: {e}")))?;
+        verifier.verify(&seal.key_id, &self.seal_message(), &signature)
+    }
+}
+
+/// How [`archive_segments`] compresses and what it does afterwards.
+#[derive(Clone, Copy, Debug)]
+pub struct ArchiveOptions {
+    level: i32,
+    prune: bool,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            level: 19,
+            prune: false,
+        }
+    }
+}
+
+impl ArchiveOptions {
+    /// zstd level 19, keeping the raw segments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the zstd compression level (1–22).
+    pub fn level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Delete each raw segment from the segment directory once the archive
+    /// manifest recording it has been written.
+    ///
+    /// The segment directory alone is then no longer a complete log:
+    /// [`crate::verify_segments`], [`crate::AuditReader::open_segments`] and
+    /// `synthonyx-audit verify` on it fail with [`AuditError::Io`]
+    /// (`NotFound`) for the first pruned segment. Verify the whole chain
+    /// with [`verify_archive`] instead. [`crate::SegmentedAuditLogger`] and
+    /// [`crate::verify_latest_segment`] only read the active segment and
+    /// keep working.
+    pub fn prune_sources(mut self) -> Self {
+        self.prune = true;
+        self
+    }
+}
+
+/// Archive every sealed segment of `segment_dir` not yet in `archive_dir`.
+///
+/// Each segment is re-verified against the live manifest before it is
+/// compressed; the compressed file is fsynced and renamed into place, and
+/// the manifest is re-sealed with `signer` and replaced atomically. The
+/// live manifest is the root of trust: an archive whose records disagree
+/// with it is rejected with [`AuditError::Manifest`] rather than re-signed.
+///
+/// The active segment is never archived. Returns the new manifest.
+pub fn archive_segments(
+    segment_dir: impl AsRef<Path>,
+    archive_dir: impl AsRef<Path>,
+    signer: &dyn CheckpointSigner,
+    options: &ArchiveOptions,
+) -> Result<ArchiveManifest, AuditError> {
+    let (segment_dir, archive_dir) = (segment_dir.as_ref(), archive_dir.as_ref());
+    fs::create_dir_all(archive_dir)?;
+    let live = SegmentManifest::load(segment_dir)?;
+    let mut manifest = ArchiveManifest::load(archive_dir)?;
+    check_prefix(&manifest, &live)?;
+
+    let pending = &live.segments[manifest.segments.len()..];
+    for info in pending {
+        let source = segment_dir.join(&info.file);
+        check_sealed(info, &chain::verify_file(&source, info.start_hash)?)?;
+        manifest
+            .segments
+            .push(compress(&source, archive_dir, info, options.level)?);
+    }
+    if !pending.is_empty() {
+        let signature = signer.sign(&manifest.seal_message())?;
+        manifest.seal = Some(ArchiveSeal {
+            key_id: signer.key_id().to_string(),
+            signature: hex::encode(signature),
+        });
+        store_json(&archive_dir.join(ARCHIVE_MANIFEST_FILE), &manifest)?;
+    }
+    if options.prune {
+        for archived in &manifest.segments {
+            let source = segment_dir.join(&archived.segment.file);
+            if source.exists() {
+                fs::remove_file(source)?;
+            }
+        }
+    }
+    Ok(manifest)
+}
+
+fn compress(
+    source: &Path,
+    archive_dir: &Path,
+    info: &SegmentInfo,
+    level: i32,
+) -> Result<ArchivedSegment, AuditError> {
+    let file = format!("{}.zst", info.file);
+    let path = archive_dir.join(&file);
+    let tmp = archive_dir.join(format!("{file}.tmp"));
+    let raw_bytes = fs::metadata(source)?.len();
+    let compressed = zstd::stream::encode_all(BufReader::new(File::open(source)?), level)?;
+    let mut out = File::create(&tmp)?;
+    out.write_all(&compressed)?;
+    out.sync_all()?;
+    fs::rename(&tmp, &path)?;
+    Ok(ArchivedSegment {
+        segment: info.clone(),
+        file,
+        raw_bytes,
+        compressed_bytes: compressed.len() as u64,
+        compressed_hash: *blake3::hash(&compressed).as_bytes(),
+    })
+}
+
+/// Reject an archive whose records are not a prefix of the live manifest.
+fn check_prefix(archive: &ArchiveManifest, live: &SegmentManifest) -> Result<(), AuditError> {
+    if archive.segments.len() > live.segments.len() {
+        return Err(AuditError::Manifest(format!(
+            "archive holds {} segments, live manifest only {}",
+            archive.segments.len(),
+            live.segments.len()
+        )));
+    }
+    for (archived, info) in archive.segments.iter().zip(&live.segments) {
+        if archived.segment != *info {
+            return Err(AuditError::Manifest(format!(
+                "archived segment {} disagrees with the live manifest",
+                archived.segment.index
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Verify `archive_dir` from its compressed files, then continue through
+/// the live segments of `segment_dir` to the live chain head.
+///
+/// Checks the seal against `verifier`, each compressed file's size and
+/// hash, and — decompressing in memory, one segment at a time — that each
+/// archived segment chains from its predecessor and matches its recorded
+/// entry count, end hash and time range. The archive must agree with the
+/// live manifest; live segments past the archive are read from
+/// `segment_dir`, which therefore need not hold archived raw segments.
+/// Returns the hash of the final entry of the live log.
+pub fn verify_archive(
+    archive_dir: impl AsRef<Path>,
+    segment_dir: impl AsRef<Path>,
+    verifier: &dyn CheckpointVerifier,
+) -> Result<[u8; 32], AuditError> {
+    let (archive_dir, segment_dir) = (archive_dir.as_ref(), segment_dir.as_ref());
+    let manifest = ArchiveManifest::load(archive_dir)?;
+    manifest.check_seal(verifier)?;
+    let live = SegmentManifest::load(segment_dir)?;
+    check_prefix(&manifest, &live)?;
+    check_no_stray_segments(segment_dir, live.active_index())?;
+
+    let mut running = GENESIS;
+    for (position, archived) in manifest.segments.iter().enumerate() {
+        let info = &archived.segment;
+        if info.index != position as u64 {
+            return Err(AuditError::Manifest(format!(
+                "expected segment {position}, archive lists {}",
+                info.index
+            )));
+        }
+        if info.start_hash != running {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(running),
+                actual: hex::encode(info.start_hash),
+            });
+        }
+        let compressed = fs::read(archive_dir.join(&archived.file))?;
+        if compressed.len() as u64 != archived.compressed_bytes
+            || *blake3::hash(&compressed).as_bytes() != archived.compressed_hash
+        {
+            return Err(AuditError::Manifest(format!(
+                "compressed segment {} does not match the archive manifest",
+                info.index
+            )));
+        }
+        let raw = zstd::stream::decode_all(&compressed[..])?;
+        if raw.len() as u64 != archived.raw_bytes {
+            return Err(AuditError::Manifest(format!(
+                "segment {} decompresses to {} bytes, archive records {}",
+                info.index,
+                raw.len(),
+                archived.raw_bytes
+            )));
+        }
+        let summary = chain::walk_reader(&raw[..], running, |_, _| Ok(()))?;
+        check_sealed(info, &summary)?;
+        if (summary.first_timestamp, summary.last_timestamp)
+            != (info.first_timestamp, info.last_timestamp)
+        {
+            return Err(AuditError::Manifest(format!(
+                "segment {} time range does not match the manifest",
+                info.index
+            )));
+        }
+        running = info.end_hash;
+    }
+
+    for info in &live.segments[manifest.segments.len()..] {
+        if info.start_hash != running {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(running),
+                actual: hex::encode(info.start_hash),
+            });
+        }
+        check_sealed(
+            info,
+            &chain::verify_file(&segment_dir.join(&info.file), running)?,
+        )?;
+        running = info.end_hash;
+    }
+    verify_active(segment_dir, &live)
+}
@@ -0,0 +1,303 @@
+//! Shared chain primitives: entry encoding, line hashing, and the
+//! append/verify loops every file-backed sink builds on.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use synthonyx_kit_core::{AuditEntry, AuditError, UnixNanos};
+
+use crate::encoding::{EntryFormat, decode_entry, encode_entry};
+
+/// The all-zero genesis hash every chain starts from.
+pub(crate) const GENESIS: [u8; 32] = [0u8; 32];
+
+/// Encode `entry` as a single line (no trailing newline) in
+/// [`EntryFormat::CURRENT`].
+pub(crate) fn encode(entry: &AuditEntry) -> Result<String, AuditError> {
+    encode_entry(entry, EntryFormat::CURRENT)
+}
+
+/// Decode a single stored line, in any supported format, into an
+/// [`AuditEntry`].
+pub(crate) fn decode(line: &str) -> Result<AuditEntry, AuditError> {
+    decode_entry(line).map(|(entry, _)| entry)
+}
+
+/// BLAKE3-32 of a stored line (sans trailing newline).
+pub(crate) fn hash_line(line: &str) -> [u8; 32] {
+    *blake3::hash(line.as_bytes()).as_bytes()
+}
+
+/// Summary of a verified run of chained entries.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ChainSummary {
+    /// Hash of the final entry (or the start hash if there were none).
+    pub(crate) last_hash: [u8; 32],
+    /// Number of entries verified.
+    pub(crate) entries: u64,
+    /// Timestamp of the first entry, if any.
+    pub(crate) first_timestamp: Option<UnixNanos>,
+    /// Timestamp of the last entry, if any.
+    pub(crate) last_timestamp: Option<UnixNanos>,
+}
+
+impl ChainSummary {
+    /// The summary of an empty run starting at `start`.
+    pub(crate) fn empty(start: [u8; 32]) -> Self {
+        Self {
+            last_hash: start,
+            entries: 0,
+            first_timestamp: None,
+            last_timestamp: None,
+        }
+    }
+}
+
+/// Verify the chain stored at `path`, expecting the first entry's
+/// `prev_hash` to equal `start`.
+pub(crate) fn verify_file(path: &Path, start: [u8; 32]) -> Result<ChainSummary, AuditError> {
+    walk_file(path, start, |_, _| Ok(()))
+}
+
+/// Like [`verify_file`], additionally calling `visit` with each verified
+/// entry and the running hash *after* it.
+pub(crate) fn walk_file(
+    path: &Path,
+    start: [u8; 32],
+    visit: impl FnMut(&AuditEntry, [u8; 32]) -> Result<(), AuditError>,
+) -> Result<ChainSummary, AuditError> {
+    walk_reader(BufReader::new(File::open(path)?), start, visit)
+}
+
+/// Like [`walk_file`], over any buffered reader of stored lines.
+pub(crate) fn walk_reader(
+    reader: impl BufRead,
+    start: [u8; 32],
+    mut visit: impl FnMut(&AuditEntry, [u8; 32]) -> Result<(), AuditError>,
+) -> Result<ChainSummary, AuditError> {
+    let mut summary = ChainSummary::empty(start);
+    for line in reader.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let entry = decode(&line)?;
+        if entry.prev_hash != summary.last_hash {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(summary.last_hash),
+                actual: hex::encode(entry.prev_hash),
+            });
+        }
+        summary.last_hash = hash_line(&line);
+        summary.entries += 1;
+        visit(&entry, summary.last_hash)?;
+        summary.first_timestamp.get_or_insert(entry.timestamp);
+        summary.last_timestamp = Some(entry.timestamp);
+    }
+    Ok(summary)
+}
+
+/// Append-side chain state: the open file, the running hash, the number of
+/// entries in the file, and what to stamp on the next entry.
+pub(crate) struct ChainWriter {
+    pub(crate) file: File,
+    pub(crate) last_hash: [u8; 32],
+    pub(crate) entries: u64,
+    /// Sequence number of the next entry: its position in the whole log,
+    /// which for a segment includes the entries of sealed segments.
+    pub(crate) sequence: u64,
+    pub(crate) writer_id: Option<String>,
+    /// Set once the file can no longer be vouched for: a failed append
+    /// could not be rolled back, or a group-commit batch failed to write
+    /// or fsync. Every later append fails with it as
+    /// [`AuditError::Closed`].
+    pub(crate) failed: Option<String>,
+}
+
+impl ChainWriter {
+    /// Open the log at `path` for appending, verifying any existing chain
+    /// from genesis and restoring the running hash from it.
+    pub(crate) fn open(path: &Path) -> Result<Self, AuditError> {
+        let summary = if path.exists() {
+            verify_file(path, GENESIS)?
+        } else {
+            ChainSummary::empty(GENESIS)
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file,
+            last_hash: summary.last_hash,
+            entries: summary.entries,
+            sequence: summary.entries,
+            writer_id: None,
+            failed: None,
+        })
+    }
+
+    /// Open the existing log at `path` without write access, verifying its
+    /// chain from genesis. Fails if the log does not exist.
+    pub(crate) fn open_read_only(path: &Path) -> Result<Self, AuditError> {
+        let file = File::open(path)?;
+        let summary = walk_reader(BufReader::new(&file), GENESIS, |_, _| Ok(()))?;
+        Ok(Self {
+            file,
+            last_hash: summary.last_hash,
+            entries: summary.entries,
+            sequence: summary.entries,
+            writer_id: None,
+            failed: None,
+        })
+    }
+
+    /// Chain `entry` onto the running hash, append it as one line, and
+    /// return the number of bytes written.
+    ///
+    /// The running hash, entry count and sequence only advance once the
+    /// line has been written and flushed. If the write fails, any partial
+    /// line is truncated away so the next append chains onto the last
+    /// complete entry; if even that fails, the writer refuses every later
+    /// append with [`AuditError::Closed`].
+    pub(crate) fn append(&mut self, entry: AuditEntry) -> Result<u64, AuditError> {
+        if let Some(reason) = &self.failed {
+            return Err(AuditError::Closed(reason.clone()));
+        }
+        let (last_hash, entries, sequence) = (self.last_hash, self.entries, self.sequence);
+        let mut bytes = Vec::new();
+        self.stage(entry, &mut bytes)?;
+        let len = self.file.metadata()?.len();
+        if let Err(e) = self.file.write_all(&bytes).and_then(|()| self.file.flush()) {
+            self.last_hash = last_hash;
+            self.entries = entries;
+            self.sequence = sequence;
+            self.discard_partial(len);
+            return Err(e.into());
+        }
+        Ok(bytes.len() as u64)
+    }
+
+    /// Cut the file back to `len` after a failed write, or mark the writer
+    /// failed if a partial line may remain.
+    fn discard_partial(&mut self, len: u64) {
+        let rolled_back = match self.file.metadata() {
+            Ok(meta) if meta.len() == len => Ok(()),
+            Ok(_) => self.file.set_len(len),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = rolled_back {
+            self.failed = Some(format!(
+                "audit writer failed: could not remove a partial line: {e}"
+            ));
+        }
+    }
+
+    /// Stamp `entry` with the next sequence number and the writer id, chain
+    /// it onto the running hash, and push its line onto `buf` without
+    /// touching the file, so a batch can be written in one call.
+    ///
+    /// The running hash advances immediately; a caller writing `buf`
+    /// itself must stop using the writer if that write fails, as the
+    /// group-commit worker does. [`Self::append`] handles this for single
+    /// entries.
+    pub(crate) fn stage(
+        &mut self,
+        mut entry: AuditEntry,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), AuditError> {
+        entry.prev_hash = self.last_hash;
+        entry.sequence = Some(self.sequence);
+        entry.writer = self.writer_id.clone();
+        let line = encode(&entry)?;
+        self.last_hash = hash_line(&line);
+        self.entries += 1;
+        self.sequence += 1;
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// Serde adapter storing a 32-byte hash as lower-case hex.
+pub(crate) mod hex_hash {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(crate) fn serialize<S: Serializer>(hash: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex::encode(hash))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        let s = String::deserialize(d)?;
+        let mut out = [0u8; 32];
+        hex::decode_to_slice(&s, &mut out).map_err(D::Error::custom)?;
+        Ok(out)
+    }
+}
+
+// The test writes to /dev/full to make an append fail.
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use synthonyx_kit_core::{CorrelationId, OriginKind, OriginSnapshot, Outcome};
+
+    use super::*;
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn tmp_path(name: &str) -> PathBuf {
+        let pid = std::process::id();
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("synthonyx-audit-chain-{pid}-{seq}-{name}"))
+    }
+
+    fn entry(action: &'static str) -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(1),
+            correlation_id: CorrelationId([1u8; 16]),
+            module: Cow::Borrowed("test"),
+            action: Cow::Borrowed(action),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    #[test]
+    fn failed_append_does_not_advance_the_chain() {
+        let path = tmp_path("enospc.jsonl");
+        let mut writer = ChainWriter::open(&path).unwrap();
+        writer.append(entry("first")).unwrap();
+        let head = writer.last_hash;
+
+        // Every write to /dev/full fails with ENOSPC.
+        let log = std::mem::replace(
+            &mut writer.file,
+            OpenOptions::new().append(true).open("/dev/full").unwrap(),
+        );
+        assert!(matches!(
+            writer.append(entry("lost")),
+            Err(AuditError::Io(_))
+        ));
+        assert_eq!(writer.last_hash, head);
+        assert_eq!((writer.entries, writer.sequence), (1, 1));
+        assert!(writer.failed.is_none());
+
+        writer.file = log;
+        writer.append(entry("second")).unwrap();
+        let summary = verify_file(&path, GENESIS).unwrap();
+        assert_eq!(summary.entries, 2);
+        assert_eq!(summary.last_hash, writer.last_hash);
+        let _ = std::fs::remove_file(&path);
+    }
+}
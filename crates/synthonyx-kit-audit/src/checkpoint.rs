@@ -0,0 +1,429 @@
+//! Signed checkpoints over the audit chain.
+//!
+//! A forward-only hash chain cannot detect tampering with its terminal
+//! entry, nor the deletion of a whole tail (see ADR 0008). A checkpoint
+//! binds the chain head at a given entry count under a signature:
+//!
+//! ```text
+//! message = "synthonyx-audit-checkpoint/v1" || entries (u64, big-endian) || head_hash
+//! ```
+//!
+//! Checkpoints are appended, one JSON object per line, to a sidecar file
+//! next to the log (`<log>.checkpoints`). [`verify_checkpoints`] fails if
+//! the log is shorter than, or diverges from, any recorded checkpoint.
+//!
+//! Signing is pluggable via [`CheckpointSigner`] / [`CheckpointVerifier`];
+//! [`Ed25519CheckpointSigner`] and [`Ed25519CheckpointVerifier`] are the
+//! reference implementations. Every checkpoint records the `key_id` it was
+//! signed with, so signing keys can be rotated without invalidating older
+//! checkpoints: keep retired public keys in the verifier.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use synthonyx_kit_core::{AuditError, Secret};
+
+use crate::chain;
+
+const DOMAIN: &[u8] = b"synthonyx-audit-checkpoint/v1";
+
+/// A signed statement that the log held `entries` entries ending in
+/// `head_hash`.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    /// Number of entries covered by the checkpoint.
+    pub entries: u64,
+    /// BLAKE3 hash of the entry at position `entries` (all zeros for an
+    /// empty log).
+    #[serde(with = "chain::hex_hash")]
+    pub head_hash: [u8; 32],
+    /// Identifier of the key that produced `signature`.
+    pub key_id: String,
+    /// Hex-encoded signature over [`Checkpoint::message`].
+    pub signature: String,
+}
+
+impl Checkpoint {
+    /// Sign a checkpoint for `entries` / `head_hash` with `signer`.
+    pub fn sign(
+        signer: &dyn CheckpointSigner,
+        entries: u64,
+        head_hash: [u8; 32],
+    ) -> Result<Self, AuditError> {
+        let signature = signer.sign(&Self::message(entries, &head_hash))?;
+        Ok(Self {
+            entries,
+            head_hash,
+            key_id: signer.key_id().to_string(),
+            signature: hex::encode(signature),
+        })
+    }
+
+    /// The exact bytes covered by the signature.
+    pub fn message(entries: u64, head_hash: &[u8; 32]) -> Vec<u8> {
+        let mut message = Vec::with_capacity(DOMAIN.len() + 8 + 32);
+        message.extend_from_slice(DOMAIN);
+        message.extend_from_slice(&entries.to_be_bytes());
+        message.extend_from_slice(head_hash);
+        message
+    }
+
+    /// Check the signature with `verifier`.
+    pub fn verify_signature(&self, verifier: &dyn CheckpointVerifier) -> Result<(), AuditError> {
+        let signature = hex::decode(&self.signature)
+            .map_err(|e| AuditError::Checkpoint(format!("malformed signature: {e}")))?;
+        verifier.verify(
+            &self.key_id,
+            &Self::message(self.entries, &self.head_hash),
+            &signature,
+        )
+    }
+}
+
+/// Produces checkpoint signatures.
+///
+/// Implementations backed by an HSM or KMS should keep the private key out
+/// of process memory entirely; [`Ed25519CheckpointSigner`] holds it in a
+/// [`Secret`].
+pub trait CheckpointSigner: Send + Sync + 'static {
+    /// Stable identifier of the signing key, recorded on every checkpoint.
+    fn key_id(&self) -> &str;
+    /// Sign `message`.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, AuditError>;
+}
+
+/// Checks checkpoint signatures.
+pub trait CheckpointVerifier {
+    /// Verify `signature` over `message` under the key named `key_id`.
+    ///
+    /// Returns [`AuditError::Checkpoint`] for unknown keys and invalid
+    /// signatures alike.
+    fn verify(&self, key_id: &str, message: &[u8], signature: &[u8]) -> Result<(), AuditError>;
+}
+
+/// Ed25519 checkpoint signer backed by a [`Secret`] 32-byte seed.
+pub struct Ed25519CheckpointSigner {
+    key_id: String,
+    seed: Secret<[u8; 32]>,
+}
+
+impl Ed25519CheckpointSigner {
+    /// Construct a signer from a key identifier and a 32-byte Ed25519 seed.
+    pub fn new(key_id: impl Into<String>, seed: Secret<[u8; 32]>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            seed,
+        }
+    }
+
+    /// The public key to distribute to verifiers.
+    pub fn verifying_key(&self) -> [u8; 32] {
+        SigningKey::from_bytes(self.seed.expose())
+            .verifying_key()
+            .to_bytes()
+    }
+}
+
+impl core::fmt::Debug for Ed25519CheckpointSigner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ed25519CheckpointSigner")
+            .field("key_id", &self.key_id)
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+impl CheckpointSigner for Ed25519CheckpointSigner {
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, AuditError> {
+        let key = SigningKey::from_bytes(self.seed.expose());
+        Ok(key.sign(message).to_bytes().to_vec())
+    }
+}
+
+/// Ed25519 checkpoint verifier holding a set of named public keys.
+#[derive(Clone, Debug, Default)]
+pub struct Ed25519CheckpointVerifier {
+    keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl Ed25519CheckpointVerifier {
+    /// An empty verifier. Add keys with [`Self::with_key`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust `public_key` for checkpoints signed under `key_id`.
+    pub fn with_key(
+        mut self,
+        key_id: impl Into<String>,
+        public_key: [u8; 32],
+    ) -> Result<Self, AuditError> {
+        let key = VerifyingKey::from_bytes(&public_key)
+            .map_err(|e| AuditError::Checkpoint(format!("invalid public key: {e}")))?;
+        self.keys.insert(key_id.into(), key);
+        Ok(self)
+    }
+}
+
+impl CheckpointVerifier for Ed25519CheckpointVerifier {
+    fn verify(&self, key_id: &str, message: &[u8], signature: &[u8]) -> Result<(), AuditError> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| AuditError::Checkpoint(format!("unknown checkpoint key `{key_id}`")))?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|e| AuditError::Checkpoint(format!("malformed signature: {e}")))?;
+        key.verify(message, &signature)
+            .map_err(|_| AuditError::Checkpoint(format!("invalid signature under `{key_id}`")))
+    }
+}
+
+/// Path of the checkpoint sidecar for the log at `log_path`.
+pub fn checkpoint_path(log_path: impl AsRef<Path>) -> PathBuf {
+    let mut path = log_path.as_ref().as_os_str().to_owned();
+    path.push(".checkpoints");
+    PathBuf::from(path)
+}
+
+/// Read every checkpoint recorded for the log at `log_path`, oldest first.
+///
+/// Returns an empty list if no sidecar exists. Signatures are not checked.
+pub fn read_checkpoints(log_path: impl AsRef<Path>) -> Result<Vec<Checkpoint>, AuditError> {
+    let path = checkpoint_path(log_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut out = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        out.push(
+            serde_json::from_str(&line).map_err(|e| AuditError::Serialization(e.to_string()))?,
+        );
+    }
+    Ok(out)
+}
+
+/// Verify the log at `log_path` against its signed checkpoints.
+///
+/// Performs a full [`crate::verify_chain`] pass and additionally checks
+/// that every checkpoint's signature is valid, that checkpoint counts never
+/// decrease, that the log holds at least as many entries as the latest
+/// checkpoint ([`AuditError::Truncated`] otherwise) and that the running
+/// hash at each checkpointed count equals the signed `head_hash`
+/// ([`AuditError::ChainBroken`] otherwise).
+///
+/// Returns the latest checkpoint, or `None` if none has been written.
+/// Entries appended after the latest checkpoint are covered only by the
+/// chain itself.
+///
+/// The checkpoints are read from the sidecar next to the log, so an
+/// attacker able to truncate the log can truncate or delete the sidecar
+/// too: a missing sidecar verifies as `Ok(None)`. Hold the latest
+/// checkpoint somewhere the log's host cannot rewrite and pass it to
+/// [`verify_checkpoints_against`] to close that gap.
+pub fn verify_checkpoints(
+    log_path: impl AsRef<Path>,
+    verifier: &dyn CheckpointVerifier,
+) -> Result<Option<Checkpoint>, AuditError> {
+    verify_with_trusted(log_path.as_ref(), verifier, None)
+}
+
+/// Like [`verify_checkpoints`], additionally requiring the log to match
+/// `trusted`, a checkpoint held outside the log's host (for example shipped
+/// to a SIEM or a second system when it was written).
+///
+/// `trusted` must carry a valid signature, the log must hold at least
+/// `trusted.entries` entries ([`AuditError::Truncated`] otherwise) and its
+/// running hash at that count must equal `trusted.head_hash`. This holds
+/// even when the sidecar has been truncated or deleted along with the log.
+///
+/// Returns the later of `trusted` and the latest sidecar checkpoint.
+pub fn verify_checkpoints_against(
+    log_path: impl AsRef<Path>,
+    verifier: &dyn CheckpointVerifier,
+    trusted: &Checkpoint,
+) -> Result<Checkpoint, AuditError> {
+    let latest = verify_with_trusted(log_path.as_ref(), verifier, Some(trusted))?;
+    Ok(latest.unwrap_or_else(|| trusted.clone()))
+}
+
+fn verify_with_trusted(
+    log_path: &Path,
+    verifier: &dyn CheckpointVerifier,
+    trusted: Option<&Checkpoint>,
+) -> Result<Option<Checkpoint>, AuditError> {
+    let mut checkpoints = read_checkpoints(log_path)?;
+    let mut previous = 0;
+    for checkpoint in &checkpoints {
+        checkpoint.verify_signature(verifier)?;
+        if checkpoint.entries < previous {
+            return Err(AuditError::Checkpoint(format!(
+                "checkpoint at {} entries follows one at {previous}",
+                checkpoint.entries
+            )));
+        }
+        previous = checkpoint.entries;
+    }
+    if let Some(trusted) = trusted {
+        trusted.verify_signature(verifier)?;
+        let at = checkpoints.partition_point(|cp| cp.entries <= trusted.entries);
+        checkpoints.insert(at, trusted.clone());
+    }
+
+    let mut pending = checkpoints.iter().peekable();
+    while let Some(cp) = pending.next_if(|cp| cp.entries == 0) {
+        check_head(cp, chain::GENESIS)?;
+    }
+    let mut count = 0u64;
+    let summary = chain::walk_file(log_path, chain::GENESIS, |_, hash| {
+        count += 1;
+        while let Some(cp) = pending.next_if(|cp| cp.entries == count) {
+            check_head(cp, hash)?;
+        }
+        Ok(())
+    })?;
+    if let Some(cp) = pending.next() {
+        return Err(AuditError::Truncated {
+            checkpoint_entries: cp.entries,
+            log_entries: summary.entries,
+        });
+    }
+    Ok(checkpoints.last().cloned())
+}
+
+fn check_head(checkpoint: &Checkpoint, actual: [u8; 32]) -> Result<(), AuditError> {
+    if checkpoint.head_hash != actual {
+        return Err(AuditError::ChainBroken {
+            expected: hex::encode(checkpoint.head_hash),
+            actual: hex::encode(actual),
+        });
+    }
+    Ok(())
+}
+
+/// Checkpoint writer attached to a [`crate::FileAuditLogger`].
+pub(crate) struct Checkpointer {
+    pub(crate) signer: Box<dyn CheckpointSigner>,
+    pub(crate) every: u64,
+    pub(crate) file: File,
+    /// The last checkpoint attempt failed; the next append retries it.
+    pub(crate) overdue: bool,
+}
+
+impl Checkpointer {
+    pub(crate) fn open(
+        log_path: &Path,
+        signer: Box<dyn CheckpointSigner>,
+        every: u64,
+    ) -> Result<Self, AuditError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(checkpoint_path(log_path))?;
+        Ok(Self {
+            signer,
+            every,
+            file,
+            overdue: false,
+        })
+    }
+
+    /// Sign and durably append a checkpoint for `entries` / `head_hash`.
+    ///
+    /// `log` is fsynced first, so a checkpoint never reaches disk ahead of
+    /// the entries it covers; otherwise a power loss could leave a signed
+    /// checkpoint over entries the log no longer holds.
+    ///
+    /// A failure leaves a checkpoint [`Self::due`] until one succeeds.
+    pub(crate) fn write(
+        &mut self,
+        log: &File,
+        entries: u64,
+        head_hash: [u8; 32],
+    ) -> Result<Checkpoint, AuditError> {
+        let result = self.sign_and_append(log, entries, head_hash);
+        self.overdue = result.is_err();
+        result
+    }
+
+    fn sign_and_append(
+        &mut self,
+        log: &File,
+        entries: u64,
+        head_hash: [u8; 32],
+    ) -> Result<Checkpoint, AuditError> {
+        log.sync_data()?;
+        let checkpoint = Checkpoint::sign(self.signer.as_ref(), entries, head_hash)?;
+        let mut line = serde_json::to_vec(&checkpoint)
+            .map_err(|e| AuditError::Serialization(e.to_string()))?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file.sync_data()?;
+        Ok(checkpoint)
+    }
+
+    /// Whether a checkpoint is due after `entries` entries: periodically,
+    /// or because the last attempt failed.
+    pub(crate) fn due(&self, entries: u64) -> bool {
+        self.overdue || (self.every != 0 && entries % self.every == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer(key_id: &str, seed: u8) -> Ed25519CheckpointSigner {
+        Ed25519CheckpointSigner::new(key_id, Secret::new([seed; 32]))
+    }
+
+    #[test]
+    fn signature_round_trips() {
+        let signer = signer("k1", 7);
+        let verifier = Ed25519CheckpointVerifier::new()
+            .with_key("k1", signer.verifying_key())
+            .unwrap();
+        let cp = Checkpoint::sign(&signer, 3, [9u8; 32]).unwrap();
+        cp.verify_signature(&verifier).unwrap();
+    }
+
+    #[test]
+    fn altered_count_fails_signature() {
+        let signer = signer("k1", 7);
+        let verifier = Ed25519CheckpointVerifier::new()
+            .with_key("k1", signer.verifying_key())
+            .unwrap();
+        let mut cp = Checkpoint::sign(&signer, 3, [9u8; 32]).unwrap();
+        cp.entries = 2;
+        assert!(matches!(
+            cp.verify_signature(&verifier),
+            Err(AuditError::Checkpoint(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_key_is_rejected() {
+        let cp = Checkpoint::sign(&signer("k1", 7), 1, [0u8; 32]).unwrap();
+        let verifier = Ed25519CheckpointVerifier::new();
+        assert!(matches!(
+            cp.verify_signature(&verifier),
+            Err(AuditError::Checkpoint(_))
+        ));
+    }
+
+    #[test]
+    fn debug_redacts_seed() {
+        let debug = format!("{:?}", signer("k1", 7));
+        assert!(debug.contains("Secret(<redacted>)"));
+    }
+}
@@ -0,0 +1,390 @@
+//! Combinators for writing one entry to several sinks.
+//!
+//! `Config::Audit` takes a single [`AuditLogger`]; these wrap several into
+//! one. In every combinator the durable sink is written first and a failing
+//! secondary sink can never prevent that write. Secondary failures are
+//! either surfaced to the caller or logged at `warn` level via `tracing`,
+//! never swallowed silently.
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+
+/// What a combinator does when a non-durable sink fails.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SinkFailurePolicy {
+    /// Log the failure at `warn` level via `tracing` and report success.
+    #[default]
+    Warn,
+    /// Return the failure from `record()`. The entry has still reached the
+    /// sinks that succeeded.
+    Propagate,
+}
+
+fn warn_sink_failure(sink: &'static str, entry: &AuditEntry, err: &AuditError) {
+    ::tracing::warn!(
+        target: "synthonyx::audit",
+        sink,
+        module = %entry.module,
+        action = %entry.action,
+        correlation_id = %entry.correlation_id,
+        error = %err,
+        "audit sink failed",
+    );
+}
+
+/// Writes every entry to a primary sink, then to a secondary one.
+///
+/// A primary failure is always returned, after the secondary has still been
+/// given the entry. A secondary failure is handled per
+/// [`SinkFailurePolicy`] (default [`SinkFailurePolicy::Warn`]).
+///
+/// ```
+/// use synthonyx_kit_audit::{SinkFailurePolicy, TeeAuditLogger, TracingAuditLogger};
+/// # fn demo(durable: synthonyx_kit_audit::FileAuditLogger) {
+/// let audit = TeeAuditLogger::new(durable, TracingAuditLogger)
+///     .on_secondary_failure(SinkFailurePolicy::Warn);
+/// # }
+/// ```
+pub struct TeeAuditLogger<P, S> {
+    primary: P,
+    secondary: S,
+    policy: SinkFailurePolicy,
+}
+
+impl<P: AuditLogger, S: AuditLogger> TeeAuditLogger<P, S> {
+    /// Tee entries to `primary` and `secondary`.
+    pub fn new(primary: P, secondary: S) -> Self {
+        Self {
+            primary,
+            secondary,
+            policy: SinkFailurePolicy::default(),
+        }
+    }
+
+    /// Set how a failing secondary sink is handled.
+    pub fn on_secondary_failure(mut self, policy: SinkFailurePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl<P: AuditLogger, S: AuditLogger> AuditLogger for TeeAuditLogger<P, S> {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let primary = self.primary.record(entry.clone());
+        let secondary = match self.secondary.record(entry.clone()) {
+            Err(e) if self.policy == SinkFailurePolicy::Warn => {
+                warn_sink_failure("secondary", &entry, &e);
+                Ok(())
+            }
+            other => other,
+        };
+        primary.and(secondary)
+    }
+}
+
+/// Writes to a primary sink, and only if that fails, to a fallback.
+///
+/// Succeeds if either sink accepts the entry; every use of the fallback is
+/// logged at `warn` level. If both fail, both failures are logged and
+/// returned together as [`AuditError::Quorum`] with one sink required and
+/// none succeeding, primary first.
+pub struct FallbackAuditLogger<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: AuditLogger, F: AuditLogger> FallbackAuditLogger<P, F> {
+    /// Write to `primary`, falling back to `fallback`.
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl<P: AuditLogger, F: AuditLogger> AuditLogger for FallbackAuditLogger<P, F> {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let err = match self.primary.record(entry.clone()) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        warn_sink_failure("primary", &entry, &err);
+        match self.fallback.record(entry.clone()) {
+            Ok(()) => Ok(()),
+            Err(fallback_err) => {
+                warn_sink_failure("fallback", &entry, &fallback_err);
+                Err(AuditError::Quorum {
+                    required: 1,
+                    succeeded: 0,
+                    errors: vec![err.to_string(), fallback_err.to_string()],
+                })
+            }
+        }
+    }
+}
+
+/// Writes every entry to all sinks and succeeds once at least `required`
+/// of them accept it.
+///
+/// Every sink is always attempted. Failures below the quorum are handled
+/// per [`SinkFailurePolicy`] (default [`SinkFailurePolicy::Warn`]); missing
+/// the quorum fails with [`AuditError::Quorum`].
+///
+/// Assembled with [`Self::builder`], whose [`QuorumBuilder::build`] rejects
+/// a quorum that can never be meaningful — `required` of zero, or more
+/// than the number of sinks — so a misconfiguration surfaces at startup
+/// rather than at the first audited dispatch.
+///
+/// ```
+/// use synthonyx_kit_audit::{QuorumAuditLogger, TracingAuditLogger};
+/// # fn demo(a: synthonyx_kit_audit::FileAuditLogger, b: synthonyx_kit_audit::FileAuditLogger)
+/// # -> Result<(), synthonyx_kit_core::AuditError> {
+/// let audit = QuorumAuditLogger::builder(2)
+///     .with_sink(a)
+///     .with_sink(b)
+///     .with_sink(TracingAuditLogger)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct QuorumAuditLogger {
+    sinks: Vec<Box<dyn AuditLogger>>,
+    required: usize,
+    policy: SinkFailurePolicy,
+}
+
+impl QuorumAuditLogger {
+    /// Start a quorum requiring `required` successes. Add sinks with
+    /// [`QuorumBuilder::with_sink`].
+    pub fn builder(required: usize) -> QuorumBuilder {
+        QuorumBuilder {
+            sinks: Vec::new(),
+            required,
+            policy: SinkFailurePolicy::default(),
+        }
+    }
+}
+
+/// Assembles a [`QuorumAuditLogger`].
+pub struct QuorumBuilder {
+    sinks: Vec<Box<dyn AuditLogger>>,
+    required: usize,
+    policy: SinkFailurePolicy,
+}
+
+impl QuorumBuilder {
+    /// Add a sink.
+    pub fn with_sink(mut self, sink: impl AuditLogger) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Set how failures are handled when the quorum is still met.
+    pub fn on_minority_failure(mut self, policy: SinkFailurePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Finish the quorum. Fails with [`AuditError::Quorum`] if `required`
+    /// is zero or exceeds the number of sinks.
+    pub fn build(self) -> Result<QuorumAuditLogger, AuditError> {
+        if self.required == 0 || self.required > self.sinks.len() {
+            return Err(AuditError::Quorum {
+                required: self.required,
+                succeeded: 0,
+                errors: vec![format!(
+                    "misconfigured quorum: {} of {} sinks",
+                    self.required,
+                    self.sinks.len()
+                )],
+            });
+        }
+        Ok(QuorumAuditLogger {
+            sinks: self.sinks,
+            required: self.required,
+            policy: self.policy,
+        })
+    }
+}
+
+impl AuditLogger for QuorumAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let mut errors = Vec::new();
+        for sink in &self.sinks {
+            if let Err(e) = sink.record(entry.clone()) {
+                warn_sink_failure("quorum member", &entry, &e);
+                errors.push(e.to_string());
+            }
+        }
+        let succeeded = self.sinks.len() - errors.len();
+        if succeeded < self.required
+            || (!errors.is_empty() && self.policy == SinkFailurePolicy::Propagate)
+        {
+            return Err(AuditError::Quorum {
+                required: self.required,
+                succeeded,
+                errors,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use synthonyx_kit_core::{CorrelationId, OriginKind, OriginSnapshot, Outcome, UnixNanos};
+
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Recorder {
+        fn actions(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    impl AuditLogger for Recorder {
+        fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+            self.0.lock().unwrap().push(entry.action.into_owned());
+            Ok(())
+        }
+    }
+
+    struct Broken;
+
+    impl AuditLogger for Broken {
+        fn record(&self, _: AuditEntry) -> Result<(), AuditError> {
+            Err(AuditError::Closed("broken".into()))
+        }
+    }
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(0),
+            correlation_id: CorrelationId::ZERO,
+            module: Cow::Borrowed("test"),
+            action: Cow::Borrowed("act"),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    #[test]
+    fn tee_secondary_failure_follows_policy() {
+        let durable = Recorder::default();
+        let warn = TeeAuditLogger::new(durable.clone(), Broken);
+        warn.record(entry()).unwrap();
+        let strict = TeeAuditLogger::new(durable.clone(), Broken)
+            .on_secondary_failure(SinkFailurePolicy::Propagate);
+        assert!(matches!(strict.record(entry()), Err(AuditError::Closed(_))));
+        assert_eq!(durable.actions().len(), 2);
+    }
+
+    #[test]
+    fn tee_still_feeds_secondary_when_primary_fails() {
+        let observer = Recorder::default();
+        let tee = TeeAuditLogger::new(Broken, observer.clone());
+        assert!(tee.record(entry()).is_err());
+        assert_eq!(observer.actions(), ["act"]);
+    }
+
+    #[test]
+    fn fallback_only_used_on_primary_failure() {
+        let fallback = Recorder::default();
+        FallbackAuditLogger::new(Recorder::default(), fallback.clone())
+            .record(entry())
+            .unwrap();
+        assert!(fallback.actions().is_empty());
+        FallbackAuditLogger::new(Broken, fallback.clone())
+            .record(entry())
+            .unwrap();
+        assert_eq!(fallback.actions(), ["act"]);
+    }
+
+    #[test]
+    fn fallback_reports_both_failures() {
+        let err = FallbackAuditLogger::new(Broken, Broken)
+            .record(entry())
+            .unwrap_err();
+        let AuditError::Quorum {
+            required: 1,
+            succeeded: 0,
+            errors,
+        } = err
+        else {
+            panic!("got {err:?}");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.contains("broken")));
+    }
+
+    #[test]
+    fn quorum_counts_successes() {
+        let a = Recorder::default();
+        let quorum = QuorumAuditLogger::builder(2)
+            .with_sink(a.clone())
+            .with_sink(Broken)
+            .with_sink(Arc::new(Recorder::default()))
+            .build()
+            .unwrap();
+        quorum.record(entry()).unwrap();
+        assert_eq!(a.actions(), ["act"]);
+
+        let short = QuorumAuditLogger::builder(2)
+            .with_sink(a)
+            .with_sink(Broken)
+            .build()
+            .unwrap();
+        let err = short.record(entry()).unwrap_err();
+        assert!(matches!(
+            err,
+            AuditError::Quorum {
+                required: 2,
+                succeeded: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn quorum_of_zero_is_rejected() {
+        let built = QuorumAuditLogger::builder(0)
+            .with_sink(Recorder::default())
+            .build();
+        assert!(matches!(
+            built,
+            Err(AuditError::Quorum {
+                required: 0,
+                succeeded: 0,
+                ..
+            })
+        ));
+        assert!(QuorumAuditLogger::builder(0).build().is_err());
+    }
+
+    #[test]
+    fn quorum_larger_than_sinks_is_rejected() {
+        let built = QuorumAuditLogger::builder(2)
+            .with_sink(Recorder::default())
+            .build();
+        assert!(matches!(
+            built,
+            Err(AuditError::Quorum {
+                required: 2,
+                succeeded: 0,
+                ..
+            })
+        ));
+        assert!(QuorumAuditLogger::builder(1).build().is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! Versioned on-disk encodings of [`AuditEntry`].
+//!
+//! Chain hashes are taken over the stored bytes, so the encoding is part of
+//! the log format. [`EntryFormat::V2`] and [`EntryFormat::V3`] are the
+//! canonical JSON specified in ADR 0010, produced by hand independent of
+//! `serde_json`'s output, and record their version in every line. V3 adds
+//! the sink-assigned `sequence` and `writer`. [`EntryFormat::V1`] is the
+//! legacy serde-derived shape with no version marker. Older formats are
+//! still decoded so logs written before a switch keep verifying.
+//!
+//! ```text
+//! {"action":"login","correlation_id":"<32 hex>","fields":{"n":{"uint":1}},
+//!  "module":"auth","origin":{"kind":"user","principal":"u1","service":null},
+//!  "outcome":"success","prev_hash":"<64 hex>","sequence":0,"subject":null,
+//!  "timestamp":"1700000000000000000","v":3,"writer":"node-a"}
+//! ```
+//! (shown wrapped; stored as one line).
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde_json::{Map, Value};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditValue, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    UnixNanos,
+};
+
+/// Version of the line encoding used for one stored entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EntryFormat {
+    /// `serde_json` output of the `AuditEntry` derive. Read-only: decoded
+    /// for old logs, never written.
+    V1,
+    /// Canonical JSON per ADR 0010, tagged `"v":2`. Cannot carry
+    /// `sequence` or `writer`.
+    V2,
+    /// V2 plus `sequence` and `writer`, tagged `"v":3`.
+    V3,
+}
+
+impl EntryFormat {
+    /// The format every sink writes.
+    pub const CURRENT: Self = Self::V3;
+}
+
+/// Encode `entry` as a single line (no trailing newline) in `format`.
+pub fn encode_entry(entry: &AuditEntry, format: EntryFormat) -> Result<String, AuditError> {
+    match format {
+        EntryFormat::V1 => {
+            serde_json::to_string(entry).map_err(|e| AuditError::Serialization(e.to_string()))
+        }
+        EntryFormat::V2 if entry.sequence.is_some() || entry.writer.is_some() => Err(
+            AuditError::Serialization("v2 cannot encode sequence or writer".into()),
+        ),
+        EntryFormat::V2 => Ok(encode_canonical(entry, 2)),
+        EntryFormat::V3 => Ok(encode_canonical(entry, 3)),
+    }
+}
+
+/// Decode one stored line, detecting its format.
+///
+/// A line carrying `"v":2` or `"v":3` must be byte-identical to the
+/// canonical re-encoding of what it decodes to; anything else (extra
+/// whitespace, reordered keys, unnecessary escapes) is rejected. Lines
+/// without a `v` member are decoded as [`EntryFormat::V1`].
+pub fn decode_entry(line: &str) -> Result<(AuditEntry, EntryFormat), AuditError> {
+    let err = |msg: String| AuditError::Serialization(msg);
+    let value: Value = serde_json::from_str(line).map_err(|e| err(e.to_string()))?;
+    match value.get("v") {
+        None => {
+            let entry = serde_json::from_value(value).map_err(|e| err(e.to_string()))?;
+            Ok((entry, EntryFormat::V1))
+        }
+        Some(v) if matches!(v.as_u64(), Some(2 | 3)) => {
+            let version = v.as_u64().unwrap_or_default();
+            let entry = decode_canonical(&value, version).map_err(err)?;
+            if encode_canonical(&entry, version) != line {
+                return Err(err(format!("entry is not in canonical v{version} form")));
+            }
+            let format = if version == 2 {
+                EntryFormat::V2
+            } else {
+                EntryFormat::V3
+            };
+            Ok((entry, format))
+        }
+        Some(v) => Err(err(format!("unsupported audit entry format version {v}"))),
+    }
+}
+
+fn encode_canonical(entry: &AuditEntry, version: u64) -> String {
+    let mut out = String::with_capacity(320);
+    out.push_str("{\"action\":");
+    push_str(&mut out, &entry.action);
+    let _ = write!(out, ",\"correlation_id\":\"{}\"", entry.correlation_id);
+    out.push_str(",\"fields\":{");
+    for (i, (key, value)) in entry.fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_str(&mut out, key);
+        out.push(':');
+        push_value(&mut out, value);
+    }
+    out.push_str("},\"module\":");
+    push_str(&mut out, &entry.module);
+    let _ = write!(
+        out,
+        ",\"origin\":{{\"kind\":\"{}\",\"principal\":",
+        kind_name(entry.origin.kind)
+    );
+    push_opt_str(&mut out, entry.origin.principal.as_deref());
+    out.push_str(",\"service\":");
+    push_opt_str(&mut out, entry.origin.service.as_deref());
+    let _ = write!(
+        out,
+        "}},\"outcome\":\"{}\",\"prev_hash\":\"{}\"",
+        outcome_name(entry.outcome),
+        hex::encode(entry.prev_hash)
+    );
+    if version >= 3 {
+        match entry.sequence {
+            Some(n) => {
+                let _ = write!(out, ",\"sequence\":{n}");
+            }
+            None => out.push_str(",\"sequence\":null"),
+        }
+    }
+    out.push_str(",\"subject\":");
+    push_opt_str(&mut out, entry.subject.as_deref());
+    let _ = write!(
+        out,
+        ",\"timestamp\":\"{}\",\"v\":{version}",
+        entry.timestamp.0
+    );
+    if version >= 3 {
+        out.push_str(",\"writer\":");
+        push_opt_str(&mut out, entry.writer.as_deref());
+    }
+    out.push('}');
+    out
+}
+
+/// Push `s` as a JSON string, escaping only what RFC 8259 requires.
+fn push_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn push_opt_str(out: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => push_str(out, s),
+        None => out.push_str("null"),
+    }
+}
+
+fn push_value(out: &mut String, value: &AuditValue) {
+    let _ = match value {
+        AuditValue::Bool(b) => write!(out, "{{\"bool\":{b}}}"),
+        AuditValue::Int(i) => write!(out, "{{\"int\":{i}}}"),
+        AuditValue::UInt(u) => write!(out, "{{\"uint\":{u}}}"),
+        AuditValue::Str(s) => {
+            out.push_str("{\"str\":");
+            push_str(out, s);
+            out.push('}');
+            Ok(())
+        }
+        AuditValue::Hash(h) => {
+            out.push_str("{\"hash\":");
+            push_str(out, h);
+            out.push('}');
+            Ok(())
+        }
+    };
+}
+
+pub(crate) fn kind_name(kind: OriginKind) -> &'static str {
+    match kind {
+        OriginKind::System => "system",
+        OriginKind::Service => "service",
+        OriginKind::User => "user",
+        OriginKind::Anonymous => "anonymous",
+    }
+}
+
+pub(crate) fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Success => "success",
+        Outcome::Denied => "denied",
+        Outcome::Error => "error",
+    }
+}
+
+fn decode_canonical(value: &Value, version: u64) -> Result<AuditEntry, String> {
+    let obj = object(value, "entry")?;
+    let origin = object(member(obj, "origin")?, "origin")?;
+    let kind = match string(origin, "kind")? {
+        "system" => OriginKind::System,
+        "service" => OriginKind::Service,
+        "user" => OriginKind::User,
+        "anonymous" => OriginKind::Anonymous,
+        other => return Err(format!("unknown origin kind {other:?}")),
+    };
+    let outcome = match string(obj, "outcome")? {
+        "success" => Outcome::Success,
+        "denied" => Outcome::Denied,
+        "error" => Outcome::Error,
+        other => return Err(format!("unknown outcome {other:?}")),
+    };
+    let mut correlation_id = [0u8; 16];
+    hex::decode_to_slice(string(obj, "correlation_id")?, &mut correlation_id)
+        .map_err(|e| format!("correlation_id: {e}"))?;
+    let mut prev_hash = [0u8; 32];
+    hex::decode_to_slice(string(obj, "prev_hash")?, &mut prev_hash)
+        .map_err(|e| format!("prev_hash: {e}"))?;
+    let timestamp = string(obj, "timestamp")?
+        .parse()
+        .map_err(|e| format!("timestamp: {e}"))?;
+    let mut fields = BTreeMap::new();
+    for (key, value) in object(member(obj, "fields")?, "fields")? {
+        fields.insert(key.clone(), decode_value(key, value)?);
+    }
+    let (sequence, writer) = if version >= 3 {
+        let sequence = match member(obj, "sequence")? {
+            Value::Null => None,
+            n => Some(n.as_u64().ok_or("sequence: expected an integer or null")?),
+        };
+        (sequence, opt_string(obj, "writer")?.map(str::to_string))
+    } else {
+        (None, None)
+    };
+    Ok(AuditEntry {
+        timestamp: UnixNanos(timestamp),
+        correlation_id: CorrelationId(correlation_id),
+        module: Cow::Owned(string(obj, "module")?.to_string()),
+        action: Cow::Owned(string(obj, "action")?.to_string()),
+        origin: OriginSnapshot {
+            kind,
+            principal: opt_string(origin, "principal")?.map(str::to_string),
+            service: opt_string(origin, "service")?.map(|s| Cow::Owned(s.to_string())),
+        },
+        outcome,
+        subject: opt_string(obj, "subject")?.map(str::to_string),
+        fields,
+        prev_hash,
+        sequence,
+        writer,
+    })
+}
+
+fn decode_value(key: &str, value: &Value) -> Result<AuditValue, String> {
+    let bad = || format!("field {key:?}: expected a one-member tagged value");
+    let tagged = object(value, key)?;
+    let (tag, inner) = match tagged.iter().next() {
+        Some(pair) if tagged.len() == 1 => pair,
+        _ => return Err(bad()),
+    };
+    match (tag.as_str(), inner) {
+        ("bool", Value::Bool(b)) => Ok(AuditValue::Bool(*b)),
+        ("int", n) if n.is_i64() => Ok(AuditValue::Int(n.as_i64().unwrap_or_default())),
+        ("uint", n) if n.is_u64() => Ok(AuditValue::UInt(n.as_u64().unwrap_or_default())),
+        ("str", Value::String(s)) => Ok(AuditValue::Str(s.clone())),
+        ("hash", Value::String(s)) => Ok(AuditValue::Hash(s.clone())),
+        _ => Err(bad()),
+    }
+}
+
+fn member<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<&'a Value, String> {
+    obj.get(name).ok_or_else(|| format!("missing {name:?}"))
+}
+
+fn object<'a>(value: &'a Value, name: &str) -> Result<&'a Map<String, Value>, String> {
+    value
+        .as_object()
+        .ok_or_else(|| format!("{name}: expected an object"))
+}
+
+fn string<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<&'a str, String> {
+    member(obj, name)?
+        .as_str()
+        .ok_or_else(|| format!("{name}: expected a string"))
+}
+
+fn opt_string<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<Option<&'a str>, String> {
+    match member(obj, name)? {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s)),
+        _ => Err(format!("{name}: expected a string or null")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(7),
+            correlation_id: CorrelationId([0xab; 16]),
+            module: Cow::Borrowed("m"),
+            action: Cow::Borrowed("a\"\\\n\u{1}é"),
+            origin: OriginSnapshot {
+                kind: OriginKind::Service,
+                principal: None,
+                service: Some(Cow::Borrowed("svc")),
+            },
+            outcome: Outcome::Denied,
+            subject: Some("s".into()),
+            fields: BTreeMap::from([
+                ("b".to_string(), AuditValue::Int(-3)),
+                ("a".to_string(), AuditValue::Hash("00ff".into())),
+            ]),
+            prev_hash: [1u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    #[test]
+    fn v2_round_trips_and_keeps_hash_tag() {
+        let line = encode_entry(&entry(), EntryFormat::V2).unwrap();
+        assert!(line.contains(r#""action":"a\"\\\n\u0001é""#), "{line}");
+        let (decoded, format) = decode_entry(&line).unwrap();
+        assert_eq!(format, EntryFormat::V2);
+        assert!(matches!(decoded.fields["a"], AuditValue::Hash(_)));
+        assert_eq!(encode_entry(&decoded, EntryFormat::V2).unwrap(), line);
+    }
+
+    #[test]
+    fn non_canonical_v2_is_rejected() {
+        let line = encode_entry(&entry(), EntryFormat::V2).unwrap();
+        let spaced = line.replacen("\"module\":", "\"module\": ", 1);
+        assert!(decode_entry(&spaced).is_err());
+        let escaped = line.replacen("\"svc\"", "\"\\u0073vc\"", 1);
+        assert!(decode_entry(&escaped).is_err());
+        let future = line.replacen("\"v\":2", "\"v\":4", 1);
+        assert!(decode_entry(&future).is_err());
+    }
+
+    #[test]
+    fn v3_carries_sequence_and_writer() {
+        let mut e = entry();
+        e.sequence = Some(41);
+        e.writer = Some("node-a".into());
+        assert!(encode_entry(&e, EntryFormat::V2).is_err());
+        let line = encode_entry(&e, EntryFormat::V3).unwrap();
+        assert!(line.contains(r#""sequence":41,"subject""#), "{line}");
+        assert!(line.ends_with(r#""v":3,"writer":"node-a"}"#), "{line}");
+        let (decoded, format) = decode_entry(&line).unwrap();
+        assert_eq!(format, EntryFormat::V3);
+        assert_eq!((decoded.sequence, decoded.writer), (e.sequence, e.writer));
+    }
+
+    #[test]
+    fn v1_lines_still_decode() {
+        let line = encode_entry(&entry(), EntryFormat::V1).unwrap();
+        let (decoded, format) = decode_entry(&line).unwrap();
+        assert_eq!(format, EntryFormat::V1);
+        assert_eq!(decoded.action, entry().action);
+    }
+}
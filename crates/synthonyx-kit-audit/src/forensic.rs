@@ -0,0 +1,224 @@
+//! Forensic verification: scan a whole log and report every defect.
+//!
+//! [`verify_chain`](crate::verify_chain) answers "is this log intact?" and
+//! stops at the first problem. For incident response the useful question
+//! is "which records can still be trusted?". [`forensic_scan`] reads to the
+//! end regardless of what it finds, records each chain break, gap,
+//! malformed line and torn tail with its line number and byte offset, and
+//! splits the log into [`TrustedRange`]s of internally consistent entries.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::Serialize;
+use synthonyx_kit_core::AuditError;
+
+use crate::chain::{self, GENESIS};
+
+/// Result of a [`forensic_scan`].
+#[derive(Clone, Debug, Serialize)]
+pub struct ForensicReport {
+    /// Non-empty lines scanned, including malformed ones and a torn tail.
+    pub lines: u64,
+    /// Bytes scanned.
+    pub bytes: u64,
+    /// Lines that decoded as entries.
+    pub entries: u64,
+    /// Every defect found, in file order.
+    pub findings: Vec<Finding>,
+    /// Maximal runs of decodable entries in which each entry's `prev_hash`
+    /// is the hash of the line before it, in file order.
+    pub trusted: Vec<TrustedRange>,
+    /// Hash of the last complete line (the scan's start hash if there is
+    /// none); what the next appended entry would chain to.
+    #[serde(with = "chain::hex_hash")]
+    pub head: [u8; 32],
+}
+
+impl ForensicReport {
+    /// True if nothing was found: the log would pass `verify_chain`.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// One defect, located in the scanned file.
+#[derive(Clone, Debug, Serialize)]
+pub struct Finding {
+    /// 1-based line number.
+    pub line: u64,
+    /// Byte offset of the start of the line.
+    pub offset: u64,
+    /// What is wrong with it.
+    #[serde(flatten)]
+    pub kind: FindingKind,
+}
+
+/// The kind of a [`Finding`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FindingKind {
+    /// The entry's `prev_hash` matches no earlier line: the line before it
+    /// was altered or entries before this one were removed, or this entry
+    /// was altered or inserted.
+    ChainBroken {
+        /// Hash of the preceding line (or the start hash for line 1).
+        #[serde(with = "chain::hex_hash")]
+        expected: [u8; 32],
+        /// The entry's `prev_hash`.
+        #[serde(with = "chain::hex_hash")]
+        actual: [u8; 32],
+    },
+    /// The entry's `prev_hash` is the hash of an earlier line other than
+    /// the preceding one, leaving a gap in the chain: the lines in between
+    /// are not part of it (inserted or replayed), or this entry was moved.
+    Gap {
+        /// Line whose hash the entry chains to; `0` for the start hash.
+        links_to_line: u64,
+    },
+    /// The line is not a decodable entry (invalid UTF-8, bad JSON, or a
+    /// non-canonical encoding).
+    Malformed {
+        /// The decoder's error.
+        error: String,
+    },
+    /// The file ends with bytes after the final newline, as left by a
+    /// crash mid-write (see `recover_torn_tail`).
+    TornTail {
+        /// Number of trailing bytes.
+        len: u64,
+    },
+}
+
+/// A run of consecutive entries that chain to each other.
+///
+/// As with the chain as a whole, the final entry of a range is not bound by
+/// a successor and may have been altered undetectably.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct TrustedRange {
+    /// 1-based line number of the first entry.
+    pub first_line: u64,
+    /// 1-based line number of the last entry.
+    pub last_line: u64,
+    /// Byte offset of the first entry.
+    pub start_offset: u64,
+    /// Byte offset just past the last entry's newline.
+    pub end_offset: u64,
+    /// Number of entries in the range.
+    pub entries: u64,
+    /// Whether the first entry also chains to the line before it (or to the
+    /// start hash). False when the range begins right after a break or gap.
+    pub linked: bool,
+}
+
+/// Scan the log at `path` from the all-zero genesis hash, reporting every
+/// defect instead of stopping at the first.
+///
+/// Only I/O errors are returned as `Err`. Memory use grows with the number
+/// of lines (one hash and line number each), used to tell gaps from breaks.
+pub fn forensic_scan(path: impl AsRef<Path>) -> Result<ForensicReport, AuditError> {
+    forensic_scan_from(path, GENESIS)
+}
+
+/// Like [`forensic_scan`], for a log (such as a segment) whose first entry
+/// chains to `start`.
+pub fn forensic_scan_from(
+    path: impl AsRef<Path>,
+    start: [u8; 32],
+) -> Result<ForensicReport, AuditError> {
+    scan_reader(BufReader::new(File::open(path.as_ref())?), start)
+}
+
+fn scan_reader(mut reader: impl BufRead, start: [u8; 32]) -> Result<ForensicReport, AuditError> {
+    let mut report = ForensicReport {
+        lines: 0,
+        bytes: 0,
+        entries: 0,
+        findings: Vec::new(),
+        trusted: Vec::new(),
+        head: start,
+    };
+    let mut seen = HashMap::from([(start, 0u64)]);
+    let mut open: Option<TrustedRange> = None;
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)? as u64;
+        if read == 0 {
+            break;
+        }
+        let offset = report.bytes;
+        report.bytes += read;
+        if buf == b"\n" {
+            continue;
+        }
+        report.lines += 1;
+        let line_no = report.lines;
+        let finding = |kind| Finding {
+            line: line_no,
+            offset,
+            kind,
+        };
+
+        let Some(line) = buf.strip_suffix(b"\n") else {
+            report
+                .findings
+                .push(finding(FindingKind::TornTail { len: read }));
+            break;
+        };
+        let line_hash = *blake3::hash(line).as_bytes();
+        let decoded = std::str::from_utf8(line)
+            .map_err(|e| e.to_string())
+            .and_then(|s| chain::decode(s).map_err(|e| e.to_string()));
+        let prev = report.head;
+        report.head = line_hash;
+        seen.entry(line_hash).or_insert(line_no);
+
+        let entry = match decoded {
+            Ok(entry) => entry,
+            Err(error) => {
+                report
+                    .findings
+                    .push(finding(FindingKind::Malformed { error }));
+                report.trusted.extend(open.take());
+                continue;
+            }
+        };
+        report.entries += 1;
+        let linked = entry.prev_hash == prev;
+        if !linked {
+            let kind = match seen.get(&entry.prev_hash) {
+                Some(&links_to_line) if links_to_line < line_no => {
+                    FindingKind::Gap { links_to_line }
+                }
+                _ => FindingKind::ChainBroken {
+                    expected: prev,
+                    actual: entry.prev_hash,
+                },
+            };
+            report.findings.push(finding(kind));
+        }
+        match open.as_mut() {
+            Some(range) if linked => {
+                range.last_line = line_no;
+                range.end_offset = report.bytes;
+                range.entries += 1;
+            }
+            _ => {
+                report.trusted.extend(open.take());
+                open = Some(TrustedRange {
+                    first_line: line_no,
+                    last_line: line_no,
+                    start_offset: offset,
+                    end_offset: report.bytes,
+                    entries: 1,
+                    linked,
+                });
+            }
+        }
+    }
+    report.trusted.extend(open);
+    Ok(report)
+}
@@ -0,0 +1,336 @@
+//! Group-commit audit logger: a background writer thread that batches
+//! entries from many callers into one write and one fsync.
+//!
+//! Callers block in `record()` until their entry reaches the durability
+//! level chosen by the [`SyncPolicy`]; entries are never dropped silently.
+//! If the writer hits an I/O error it fails every pending and later
+//! `record()` call with `AuditError::Closed` instead of continuing on a log
+//! it can no longer vouch for.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+
+use crate::chain::ChainWriter;
+use crate::lock::WriteLock;
+
+/// Upper bound on entries taken from the queue for a single write.
+const MAX_BATCH: usize = 1024;
+
+/// When the background writer fsyncs, and therefore when `record()`
+/// returns.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncPolicy {
+    /// fsync every batch before acknowledging it. `record()` returns only
+    /// once the entry is on stable storage; concurrent callers share one
+    /// fsync.
+    EveryEntry,
+    /// fsync once `n` entries have accumulated since the last fsync
+    /// (`0` behaves as `1`). `record()` returns once the entry is written to
+    /// the OS, so up to `n - 1` acknowledged entries can be lost on power
+    /// failure.
+    EveryN(u64),
+    /// fsync at most this long after the first unsynced write. `record()`
+    /// returns once the entry is written to the OS, so up to this window of
+    /// acknowledged entries can be lost on power failure.
+    Interval(Duration),
+}
+
+type Ack = SyncSender<Result<(), AuditError>>;
+
+enum Msg {
+    Record(Box<AuditEntry>, Ack),
+    Sync(Ack),
+    Shutdown(Ack),
+}
+
+/// BLAKE3-chained file audit logger with a background group-commit writer.
+///
+/// Produces the same on-disk format as [`crate::FileAuditLogger`], so the
+/// file verifies with [`crate::verify_chain`] and can be reopened by either
+/// logger.
+///
+/// Call [`Self::shutdown`] for a clean stop: it drains every queued entry,
+/// fsyncs, and joins the writer thread. Dropping the logger does the same
+/// but discards any error.
+pub struct GroupCommitAuditLogger {
+    tx: Sender<Msg>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    _lock: WriteLock,
+}
+
+impl GroupCommitAuditLogger {
+    /// Open or create the log at `path`, verifying any existing chain, and
+    /// start the background writer.
+    ///
+    /// Takes the same cross-process write lock as
+    /// [`crate::FileAuditLogger::open`], failing with [`AuditError::Locked`]
+    /// while another logger holds it.
+    pub fn open(path: impl AsRef<Path>, policy: SyncPolicy) -> Result<Self, AuditError> {
+        let lock = WriteLock::acquire(path.as_ref())?;
+        let writer = ChainWriter::open(path.as_ref())?;
+        let (tx, rx) = mpsc::channel();
+        let handle = std::thread::Builder::new()
+            .name("synthonyx-audit-writer".into())
+            .spawn(move || Worker::new(writer, policy).run(rx))?;
+        Ok(Self {
+            tx,
+            handle: Mutex::new(Some(handle)),
+            _lock: lock,
+        })
+    }
+
+    /// Block until every entry recorded so far is fsynced, regardless of
+    /// the [`SyncPolicy`].
+    pub fn sync(&self) -> Result<(), AuditError> {
+        self.request(Msg::Sync)
+    }
+
+    /// Drain the queue, fsync, and stop the writer thread. Later calls to
+    /// `record()` fail with [`AuditError::Closed`]; calling this twice is a
+    /// no-op.
+    pub fn shutdown(&self) -> Result<(), AuditError> {
+        let Some(handle) = self
+            .handle
+            .lock()
+            .expect("group commit audit logger mutex poisoned")
+            .take()
+        else {
+            return Ok(());
+        };
+        let result = self.request(Msg::Shutdown);
+        let _ = handle.join();
+        result
+    }
+
+    fn request(&self, msg: impl FnOnce(Ack) -> Msg) -> Result<(), AuditError> {
+        let (ack, done) = mpsc::sync_channel(1);
+        self.tx.send(msg(ack)).map_err(|_| closed())?;
+        done.recv().map_err(|_| closed())?
+    }
+}
+
+impl AuditLogger for GroupCommitAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.request(|ack| Msg::Record(Box::new(entry), ack))
+    }
+}
+
+impl Drop for GroupCommitAuditLogger {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+fn closed() -> AuditError {
+    AuditError::Closed("background writer has stopped".into())
+}
+
+struct Worker {
+    writer: ChainWriter,
+    policy: SyncPolicy,
+    unsynced: u64,
+    first_unsynced: Option<Instant>,
+}
+
+impl Worker {
+    fn new(writer: ChainWriter, policy: SyncPolicy) -> Self {
+        Self {
+            writer,
+            policy,
+            unsynced: 0,
+            first_unsynced: None,
+        }
+    }
+
+    fn run(mut self, rx: Receiver<Msg>) {
+        loop {
+            let first = match self.sync_deadline() {
+                Some(deadline) => {
+                    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                        Ok(msg) => msg,
+                        Err(RecvTimeoutError::Timeout) => {
+                            let _ = self.fsync();
+                            continue;
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                None => match rx.recv() {
+                    Ok(msg) => msg,
+                    Err(_) => break,
+                },
+            };
+            let mut batch = vec![first];
+            while batch.len() < MAX_BATCH {
+                match rx.try_recv() {
+                    Ok(msg) => batch.push(msg),
+                    Err(_) => break,
+                }
+            }
+            if self.commit(batch) {
+                return;
+            }
+        }
+        let _ = self.fsync();
+    }
+
+    /// Write and (per policy) fsync one batch, then answer every request in
+    /// it. Returns true if the batch contained a shutdown request.
+    fn commit(&mut self, batch: Vec<Msg>) -> bool {
+        let mut buf = Vec::new();
+        let mut written = Vec::new();
+        let mut waiters = Vec::new();
+        let mut force_sync = false;
+        let mut shutdown = None;
+        for msg in batch {
+            match msg {
+                Msg::Record(entry, ack) => {
+                    if let Some(reason) = &self.writer.failed {
+                        let _ = ack.send(Err(AuditError::Closed(reason.clone())));
+                        continue;
+                    }
+                    match self.writer.stage(*entry, &mut buf) {
+                        Ok(()) => written.push(ack),
+                        Err(e) => {
+                            let _ = ack.send(Err(e));
+                        }
+                    }
+                }
+                Msg::Sync(ack) => {
+                    force_sync = true;
+                    waiters.push(ack);
+                }
+                Msg::Shutdown(ack) => {
+                    force_sync = true;
+                    shutdown = Some(ack);
+                }
+            }
+        }
+
+        let mut result = self.write(&buf, written.len() as u64);
+        if result.is_ok() && (force_sync || self.sync_due()) {
+            result = self.fsync();
+        }
+        let is_shutdown = shutdown.is_some();
+        for ack in written.into_iter().chain(waiters).chain(shutdown) {
+            let _ = ack.send(result.clone().map_err(AuditError::Closed));
+        }
+        is_shutdown
+    }
+
+    fn write(&mut self, buf: &[u8], entries: u64) -> Result<(), String> {
+        if let Some(reason) = &self.writer.failed {
+            return Err(reason.clone());
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        if let Err(e) = std::io::Write::write_all(&mut self.writer.file, buf) {
+            return Err(self.fail(&e));
+        }
+        self.unsynced += entries;
+        self.first_unsynced.get_or_insert_with(Instant::now);
+        Ok(())
+    }
+
+    fn fsync(&mut self) -> Result<(), String> {
+        if let Some(reason) = &self.writer.failed {
+            return Err(reason.clone());
+        }
+        if self.unsynced == 0 {
+            return Ok(());
+        }
+        if let Err(e) = self.writer.file.sync_data() {
+            return Err(self.fail(&e));
+        }
+        self.unsynced = 0;
+        self.first_unsynced = None;
+        Ok(())
+    }
+
+    /// Stop the writer after an I/O error: the file may hold a partial
+    /// batch, so every pending and later request fails with
+    /// [`AuditError::Closed`].
+    fn fail(&mut self, e: &std::io::Error) -> String {
+        let reason = format!("audit writer failed: {e}");
+        self.writer.failed = Some(reason.clone());
+        reason
+    }
+
+    fn sync_due(&self) -> bool {
+        match self.policy {
+            SyncPolicy::EveryEntry => true,
+            SyncPolicy::EveryN(n) => self.unsynced >= n.max(1),
+            SyncPolicy::Interval(window) => self
+                .first_unsynced
+                .is_some_and(|since| since.elapsed() >= window),
+        }
+    }
+
+    fn sync_deadline(&self) -> Option<Instant> {
+        match self.policy {
+            SyncPolicy::Interval(window) => self.first_unsynced.map(|since| since + window),
+            _ => None,
+        }
+    }
+}
+
+// The test writes to /dev/full to make a batch fail.
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use std::fs::OpenOptions;
+
+    use synthonyx_kit_core::{CorrelationId, OriginKind, OriginSnapshot, Outcome, UnixNanos};
+
+    use super::*;
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(1),
+            correlation_id: CorrelationId([1u8; 16]),
+            module: Cow::Borrowed("test"),
+            action: Cow::Borrowed("batched"),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    fn record(worker: &mut Worker) -> Result<(), AuditError> {
+        let (ack, done) = mpsc::sync_channel(1);
+        worker.commit(vec![Msg::Record(Box::new(entry()), ack)]);
+        done.recv().unwrap()
+    }
+
+    #[test]
+    fn failed_batch_and_later_records_report_closed() {
+        let path = std::env::temp_dir().join(format!(
+            "synthonyx-audit-group-{}-enospc.jsonl",
+            std::process::id()
+        ));
+        let mut writer = ChainWriter::open(&path).unwrap();
+        writer.file = OpenOptions::new().append(true).open("/dev/full").unwrap();
+        let mut worker = Worker::new(writer, SyncPolicy::EveryEntry);
+
+        let failed = record(&mut worker).unwrap_err();
+        assert!(matches!(failed, AuditError::Closed(ref m) if m.contains("audit writer failed")));
+        assert!(worker.writer.failed.is_some());
+        assert!(matches!(record(&mut worker), Err(AuditError::Closed(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+}
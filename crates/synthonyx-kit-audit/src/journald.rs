@@ -0,0 +1,189 @@
+//! systemd-journald sink using the journal's native datagram protocol.
+//!
+//! Each entry is one datagram of `NAME=value` fields sent to journald's
+//! socket, so `journalctl SYNTHONYX_CORRELATION_ID=…` finds an operation
+//! without parsing the message text. Values containing a newline use the
+//! protocol's length-prefixed binary form.
+
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+
+use crate::encoding::{kind_name, outcome_name};
+use crate::syslog::{Facility, SyslogSeverity, value_text};
+
+/// Where journald listens for native-protocol datagrams.
+pub const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Journal field names are at most 64 bytes.
+const MAX_FIELD_NAME: usize = 64;
+
+/// Audit sink that writes each entry to the systemd journal.
+///
+/// Entry attributes become `SYNTHONYX_*` fields and each entry field
+/// `SYNTHONYX_FIELD_<KEY>` (upper-cased, other characters replaced by
+/// `_`). `PRIORITY` follows [`SyslogSeverity::for_entry`] unless
+/// [`Self::with_severity`] says otherwise.
+///
+/// A forwarding sink like [`crate::SyslogAuditLogger`]: entries are not
+/// chained, so pair it with a durable sink. Entries larger than the
+/// socket's datagram limit fail with [`AuditError::Io`]; journald's
+/// memfd fallback for oversized entries is not implemented.
+pub struct JournaldAuditLogger {
+    socket: UnixDatagram,
+    path: PathBuf,
+    identifier: String,
+    facility: Facility,
+    severity: fn(&AuditEntry) -> SyslogSeverity,
+}
+
+impl JournaldAuditLogger {
+    /// A sink writing to [`JOURNALD_SOCKET`].
+    pub fn new() -> Result<Self, AuditError> {
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path: PathBuf::from(JOURNALD_SOCKET),
+            identifier: "synthonyx".into(),
+            facility: Facility::LogAudit,
+            severity: SyslogSeverity::for_entry,
+        })
+    }
+
+    /// Send to the socket at `path` instead of [`JOURNALD_SOCKET`].
+    pub fn with_socket(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Set `SYSLOG_IDENTIFIER` (default `synthonyx`).
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = identifier.into();
+        self
+    }
+
+    /// Set `SYSLOG_FACILITY` (default [`Facility::LogAudit`]).
+    pub fn with_facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Choose each entry's `PRIORITY` (default [`SyslogSeverity::for_entry`]).
+    pub fn with_severity(mut self, severity: fn(&AuditEntry) -> SyslogSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Encode `entry` as a native-protocol datagram.
+    pub fn encode(&self, entry: &AuditEntry) -> Vec<u8> {
+        let mut out = Vec::with_capacity(512);
+        let outcome = outcome_name(entry.outcome);
+        let message = format!("{}.{} {outcome}", entry.module, entry.action);
+        push_field(&mut out, "MESSAGE", &message);
+        push_field(
+            &mut out,
+            "PRIORITY",
+            &(self.severity)(entry).code().to_string(),
+        );
+        push_field(
+            &mut out,
+            "SYSLOG_FACILITY",
+            &self.facility.code().to_string(),
+        );
+        push_field(&mut out, "SYSLOG_IDENTIFIER", &self.identifier);
+        push_field(
+            &mut out,
+            "SYNTHONYX_CORRELATION_ID",
+            &entry.correlation_id.to_string(),
+        );
+        push_field(&mut out, "SYNTHONYX_MODULE", &entry.module);
+        push_field(&mut out, "SYNTHONYX_ACTION", &entry.action);
+        push_field(&mut out, "SYNTHONYX_OUTCOME", outcome);
+        push_field(
+            &mut out,
+            "SYNTHONYX_ORIGIN_KIND",
+            kind_name(entry.origin.kind),
+        );
+        if let Some(principal) = &entry.origin.principal {
+            push_field(&mut out, "SYNTHONYX_PRINCIPAL", principal);
+        }
+        if let Some(service) = &entry.origin.service {
+            push_field(&mut out, "SYNTHONYX_SERVICE", service);
+        }
+        if let Some(subject) = &entry.subject {
+            push_field(&mut out, "SYNTHONYX_SUBJECT", subject);
+        }
+        push_field(
+            &mut out,
+            "SYNTHONYX_TIMESTAMP",
+            &entry.timestamp.0.to_string(),
+        );
+        if let Some(sequence) = entry.sequence {
+            push_field(&mut out, "SYNTHONYX_SEQUENCE", &sequence.to_string());
+        }
+        if let Some(writer) = &entry.writer {
+            push_field(&mut out, "SYNTHONYX_WRITER", writer);
+        }
+        for (key, value) in &entry.fields {
+            let name = field_name(&format!("SYNTHONYX_FIELD_{key}"));
+            push_field(&mut out, &name, &value_text(value));
+        }
+        out
+    }
+}
+
+impl AuditLogger for JournaldAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.socket.send_to(&self.encode(&entry), &self.path)?;
+        Ok(())
+    }
+}
+
+/// Upper-case `name`, replace anything but `A-Z`, `0-9` and `_` with `_`,
+/// and truncate to the journal's limit.
+fn field_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ ('A'..='Z' | '0'..='9') => c,
+            _ => '_',
+        })
+        .take(MAX_FIELD_NAME)
+        .collect()
+}
+
+fn push_field(out: &mut Vec<u8>, name: &str, value: &str) {
+    out.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        out.push(b'\n');
+        out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        out.push(b'=');
+    }
+    out.extend_from_slice(value.as_bytes());
+    out.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_line_values_use_binary_form() {
+        let mut out = Vec::new();
+        push_field(&mut out, "A", "x");
+        push_field(&mut out, "B", "y\nz");
+        let mut expected = b"A=x\nB\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"y\nz\n");
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn field_names_are_sanitised() {
+        assert_eq!(
+            field_name("SYNTHONYX_FIELD_ip-addr.v4"),
+            "SYNTHONYX_FIELD_IP_ADDR_V4"
+        );
+        assert_eq!(field_name(&"X".repeat(80)).len(), MAX_FIELD_NAME);
+    }
+}
@@ -3,23 +3,154 @@
 //! The [`AuditLogger`] trait and [`AuditEntry`] type live in
 //! [`synthonyx_kit_core`]. This crate provides reference sinks:
 //!
-//! - [`FileAuditLogger`]: append-only, BLAKE3-chained, tamper-evident.
+//! - [`FileAuditLogger`]: append-only, BLAKE3-chained, tamper-evident,
+//!   and locked against a second writing process (see [`lock_path`]).
 //! - [`TracingAuditLogger`]: emits entries via the `tracing` crate for
 //!   development and observability.
-//! - [`verify_chain`]: standalone verifier for a stored audit log file.
+//! - [`MemoryAuditLogger`]: the same chain kept in memory, with assertion
+//!   helpers for testing what an RTM audited.
+//! - [`SyslogAuditLogger`] / `JournaldAuditLogger` (Unix): forward entries
+//!   to a SOC as RFC 5424 structured data over UDP, TCP, TLS or a Unix
+//!   socket, or to the systemd journal as native fields, with
+//!   [`SyslogSeverity`] mapped from the entry's `Outcome`.
+//! - [`GroupCommitAuditLogger`]: the same file format written by a
+//!   background thread that batches entries into one write and one fsync,
+//!   acknowledging each `record()` per its [`SyncPolicy`].
+//! - [`QueuedAuditLogger`]: a bounded queue in front of any sink, failing
+//!   `record()` with `AuditError::Backpressure` instead of blocking when
+//!   full, with [`QueueMetrics`] and a draining shutdown.
+//! - [`TeeAuditLogger`] / [`FallbackAuditLogger`] / [`QuorumAuditLogger`]:
+//!   combine several sinks into the single `Config::Audit` logger, with a
+//!   [`SinkFailurePolicy`] for the non-durable ones.
+//! - [`SegmentedAuditLogger`]: the same chain split across size- or
+//!   age-rotated segment files, tracked by a [`SegmentManifest`].
+//! - `StorageAuditLogger` (feature `storage`): the same chain stored under
+//!   sequential keys in any `synthonyx-kit-storage` `Backend`.
+//! - [`recover_torn_tail`] / [`FileAuditLogger::open_recovering`]:
+//!   quarantine a partial final line left by a crash instead of refusing to
+//!   open.
+//! - [`verify_chain`] / [`verify_chain_from`]: standalone verifiers for a
+//!   stored audit log file.
+//! - [`verify_chain_with`] / [`VerifyOptions`]: the same, optionally also
+//!   checking sink-assigned sequence numbers for contiguity, timestamps for
+//!   regressions, and that a single writer produced the log.
+//! - [`forensic_scan`]: scans a whole log and reports every chain break,
+//!   gap, malformed line and torn tail with its position, plus the
+//!   [`TrustedRange`]s that remain internally consistent.
+//! - [`verify_segments`] / [`verify_latest_segment`]: verifiers for a
+//!   segment directory.
+//! - `archive_segments` / `verify_archive` (feature `archive`): move sealed
+//!   segments into zstd-compressed files under a signed manifest of their
+//!   hashes, entry counts and time ranges, and verify them without
+//!   unpacking to disk.
+//! - [`Checkpoint`] / [`verify_checkpoints`]: signed checkpoints that bind
+//!   the chain head, closing the truncation and terminal-entry gaps of a
+//!   plain hash chain; [`verify_checkpoints_against`] also checks a
+//!   checkpoint held off-host, so deleting the sidecar cannot hide a
+//!   truncation.
+//! - [`MerkleTree`]: a Merkle index over a log producing per-entry
+//!   [`InclusionProof`]s and [`ConsistencyProof`]s between tree heads.
+//! - [`AuditReader`] / [`AuditQuery`]: a streaming, chain-verifying reader
+//!   with filters, plus an optional [`CorrelationIndex`] sidecar for
+//!   lookups by correlation id.
+//! - [`ShreddingAuditLogger`]: writes subjects as keyed tokens under
+//!   per-subject keys held in a [`SubjectKeyStore`], so erasing a key
+//!   honours a GDPR Art. 17 request without touching the chain.
+//! - [`PiiGuardAuditLogger`]: scans string fields, subject and principal
+//!   for emails, phone numbers, IBANs, card numbers and national ids, and
+//!   rejects, hashes or redacts them per [`PiiKind`], raising a
+//!   Security-severity [`PiiDetected`] signal.
+//! - [`encode_entry`] / [`decode_entry`]: the versioned line encodings.
+//!   Sinks write the canonical [`EntryFormat::V3`]; verifiers also accept
+//!   [`EntryFormat::V2`] and legacy [`EntryFormat::V1`] lines, so older
+//!   logs keep verifying.
 //!
 //! On every `record()`, [`FileAuditLogger`] overwrites the entry's
-//! `prev_hash` field with the BLAKE3 hash of the previous entry's serialized
-//! form (`[0u8; 32]` for the first entry). This means callers can leave
-//! `prev_hash` zero when building entries — the logger fills it in.
+//! `prev_hash` field with the BLAKE3 hash of the previous entry's stored
+//! line (`[0u8; 32]` for the first entry), its `sequence` with the entry's
+//! zero-based position in the log, and its `writer` with the id set by
+//! [`FileAuditLogger::with_writer_id`]. This means callers can leave these
+//! fields unset when building entries — the logger fills them in.
 #![deny(missing_docs, unsafe_code, rust_2018_idioms)]
 
-use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "archive")]
+mod archive;
+mod chain;
+mod checkpoint;
+mod combinator;
+mod encoding;
+mod forensic;
+mod group;
+#[cfg(unix)]
+mod journald;
+mod lock;
+mod memory;
+mod merkle;
+mod pii_guard;
+mod queue;
+mod reader;
+mod recovery;
+mod segment;
+mod shred;
+#[cfg(feature = "storage")]
+mod storage;
+mod syslog;
+mod verify;
+
+#[cfg(feature = "archive")]
+pub use archive::{
+    ARCHIVE_MANIFEST_FILE, ArchiveManifest, ArchiveOptions, ArchiveSeal, ArchivedSegment,
+    archive_segments, verify_archive,
+};
+pub use checkpoint::{
+    Checkpoint, CheckpointSigner, CheckpointVerifier, Ed25519CheckpointSigner,
+    Ed25519CheckpointVerifier, checkpoint_path, read_checkpoints, verify_checkpoints,
+    verify_checkpoints_against,
+};
+pub use combinator::{
+    FallbackAuditLogger, QuorumAuditLogger, QuorumBuilder, SinkFailurePolicy, TeeAuditLogger,
+};
+pub use encoding::{EntryFormat, decode_entry, encode_entry};
+pub use forensic::{
+    Finding, FindingKind, ForensicReport, TrustedRange, forensic_scan, forensic_scan_from,
+};
+pub use group::{GroupCommitAuditLogger, SyncPolicy};
+#[cfg(unix)]
+pub use journald::{JOURNALD_SOCKET, JournaldAuditLogger};
+pub use lock::lock_path;
+pub use memory::MemoryAuditLogger;
+pub use merkle::{ConsistencyProof, InclusionProof, MerkleTree, TreeHead};
+pub use pii_guard::{
+    PII_GUARD_MODULE, PiiAction, PiiDetected, PiiGuardAuditLogger, PiiKind, PiiLocation, PiiMatch,
+    detect_pii,
+};
+pub use queue::{QueueMetrics, QueuedAuditLogger};
+pub use reader::{AuditQuery, AuditReader, CorrelationIndex, index_path};
+pub use recovery::{
+    QuarantinedTail, RECOVERY_ACTION, RECOVERY_MODULE, RecoveryReport, quarantine_path,
+    recover_torn_tail,
+};
+pub use segment::{
+    RotationPolicy, SegmentInfo, SegmentManifest, SegmentedAuditLogger, verify_latest_segment,
+    verify_segments,
+};
+pub use shred::{
+    ERASURE_ACTION, ERASURE_MODULE, MemorySubjectKeyStore, ShreddingAuditLogger, SubjectKeyStore,
+    subject_token,
+};
+#[cfg(feature = "storage")]
+pub use storage::{StorageAuditLogger, StorageHead, verify_storage_chain};
+pub use syslog::{DEFAULT_SD_ID, DEFAULT_TCP_TIMEOUT, Facility, SyslogAuditLogger, SyslogSeverity};
+pub use verify::{VerifyOptions, verify_chain_with};
+
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger, TimeSource};
+
+use crate::chain::ChainWriter;
+use crate::checkpoint::Checkpointer;
+use crate::lock::WriteLock;
 
 // Re-export common audit types so consumers only need one crate import.
 pub use synthonyx_kit_core::{AuditValue, OriginSnapshot, Outcome};
@@ -30,16 +161,36 @@ pub use synthonyx_kit_core::{AuditValue, OriginSnapshot, Outcome};
 /// `prev_hash` field on each entry equals BLAKE3 of the previous serialized
 /// line (sans trailing newline). The first entry's `prev_hash` is all zeros.
 ///
-/// Verify the file independently via [`verify_chain`].
+/// Verify the file independently via [`verify_chain`]. Attach a
+/// [`CheckpointSigner`] with [`Self::with_checkpoints`] to also detect
+/// truncation and terminal-entry tampering via [`verify_checkpoints`].
+///
+/// Only one logger may have a log open for writing at a time, across
+/// processes: [`Self::open`] takes an advisory lock on the [`lock_path`]
+/// sidecar and holds it until the logger is dropped. Tools that only
+/// inspect a log use [`Self::open_read_only`], which takes no lock.
 pub struct FileAuditLogger {
     inner: Mutex<State>,
 }
 
 struct State {
-    file: File,
-    last_hash: [u8; 32],
-    #[allow(dead_code)]
+    writer: ChainWriter,
+    checkpointer: Option<Checkpointer>,
     path: PathBuf,
+    /// `None` when opened read-only.
+    lock: Option<WriteLock>,
+}
+
+impl State {
+    fn check_writable(&self) -> Result<(), AuditError> {
+        match self.lock {
+            Some(_) => Ok(()),
+            None => Err(AuditError::Closed(format!(
+                "{} opened read-only",
+                self.path.display()
+            ))),
+        }
+    }
 }
 
 impl FileAuditLogger {
@@ -47,37 +198,182 @@ impl FileAuditLogger {
     ///
     /// If the file already exists, its chain is verified end-to-end and the
     /// running hash is restored from the last entry. Opening fails with
-    /// [`AuditError::ChainBroken`] if the on-disk chain is broken.
+    /// [`AuditError::ChainBroken`] if the on-disk chain is broken, and with
+    /// [`AuditError::Locked`] if another logger, in this or any other
+    /// process, has the log open for writing.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, AuditError> {
         let path = path.as_ref().to_path_buf();
-        let last_hash = if path.exists() {
-            verify_chain(&path)?
-        } else {
-            [0u8; 32]
-        };
-        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let lock = WriteLock::acquire(&path)?;
         Ok(Self {
             inner: Mutex::new(State {
-                file,
-                last_hash,
+                writer: ChainWriter::open(&path)?,
+                checkpointer: None,
                 path,
+                lock: Some(lock),
             }),
         })
     }
+
+    /// Open an existing log for inspection, without taking the write lock.
+    ///
+    /// The chain is verified end-to-end as by [`Self::open`], so
+    /// [`Self::head`] and [`Self::len`] describe the log as it was at that
+    /// moment; a writer in another process may extend it afterwards. Every
+    /// `record()` fails with [`AuditError::Closed`], as do checkpoints. The
+    /// file is never created or modified.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let path = path.as_ref().to_path_buf();
+        Ok(Self {
+            inner: Mutex::new(State {
+                writer: ChainWriter::open_read_only(&path)?,
+                checkpointer: None,
+                path,
+                lock: None,
+            }),
+        })
+    }
+
+    /// Like [`Self::open`], but first quarantines a torn final line left by
+    /// a crash mid-write (see [`recover_torn_tail`]) instead of failing.
+    ///
+    /// A broken chain before the final line is still an error: only the
+    /// unterminated tail is ever removed. A recovery is logged at `warn`
+    /// level via `tracing`, and recorded in the chain itself as a marker
+    /// entry timestamped from `time`.
+    pub fn open_recovering(
+        path: impl AsRef<Path>,
+        time: &impl TimeSource,
+    ) -> Result<Self, AuditError> {
+        let path = path.as_ref();
+        if let Some(report) = recover_torn_tail(path, time)? {
+            ::tracing::warn!(
+                target: "synthonyx::audit",
+                log = %path.display(),
+                quarantine = %report.quarantine.display(),
+                offset = report.tail.offset,
+                entries_kept = report.entries_kept,
+                "quarantined torn audit log tail",
+            );
+        }
+        Self::open(path)
+    }
+
+    /// Stamp every entry written from now on with `writer_id`, so
+    /// [`VerifyOptions::single_writer`] can detect entries appended by
+    /// another process.
+    pub fn with_writer_id(self, writer_id: impl Into<String>) -> Self {
+        let mut state = self
+            .inner
+            .into_inner()
+            .expect("file audit logger mutex poisoned");
+        state.writer.writer_id = Some(writer_id.into());
+        Self {
+            inner: Mutex::new(state),
+        }
+    }
+
+    /// Write a signed [`Checkpoint`] to the `<path>.checkpoints` sidecar
+    /// after every `every` entries (`0` disables periodic checkpoints, leaving
+    /// only explicit [`Self::checkpoint`] calls).
+    ///
+    /// Before each checkpoint the log itself is fsynced, then the checkpoint;
+    /// both happen before the triggering `record()` returns. A checkpoint
+    /// that fails does not fail that `record()`, whose entry is already
+    /// written: the error is logged at `warn` level via `tracing` and the
+    /// checkpoint is retried on every later `record()` or
+    /// [`Self::checkpoint`] until one succeeds.
+    /// Fails with [`AuditError::Closed`] on a read-only logger.
+    pub fn with_checkpoints(
+        self,
+        signer: impl CheckpointSigner,
+        every: u64,
+    ) -> Result<Self, AuditError> {
+        let mut state = self
+            .inner
+            .into_inner()
+            .expect("file audit logger mutex poisoned");
+        state.check_writable()?;
+        state.checkpointer = Some(Checkpointer::open(&state.path, Box::new(signer), every)?);
+        Ok(Self {
+            inner: Mutex::new(state),
+        })
+    }
+
+    /// Write a signed checkpoint for the current chain head now, e.g. during
+    /// graceful shutdown so the final entries are covered.
+    ///
+    /// Returns `Ok(None)` if no signer is attached.
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>, AuditError> {
+        let mut state = self.inner.lock().expect("file audit logger mutex poisoned");
+        state.check_writable()?;
+        let State {
+            writer,
+            checkpointer,
+            ..
+        } = &mut *state;
+        checkpointer
+            .as_mut()
+            .map(|c| c.write(&writer.file, writer.entries, writer.last_hash))
+            .transpose()
+    }
+
+    /// Hash of the last entry in the log (all zeros if empty).
+    pub fn head(&self) -> [u8; 32] {
+        self.inner
+            .lock()
+            .expect("file audit logger mutex poisoned")
+            .writer
+            .last_hash
+    }
+
+    /// Number of entries in the log.
+    pub fn len(&self) -> u64 {
+        self.inner
+            .lock()
+            .expect("file audit logger mutex poisoned")
+            .writer
+            .entries
+    }
+
+    /// True if the log holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// True if opened with [`Self::open_read_only`].
+    pub fn is_read_only(&self) -> bool {
+        self.inner
+            .lock()
+            .expect("file audit logger mutex poisoned")
+            .lock
+            .is_none()
+    }
 }
 
 impl AuditLogger for FileAuditLogger {
-    fn record(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
         let mut state = self.inner.lock().expect("file audit logger mutex poisoned");
-        entry.prev_hash = state.last_hash;
-        let line =
-            serde_json::to_string(&entry).map_err(|e| AuditError::Serialization(e.to_string()))?;
-        let new_hash = *blake3::hash(line.as_bytes()).as_bytes();
-        let mut bytes = line.into_bytes();
-        bytes.push(b'\n');
-        state.file.write_all(&bytes)?;
-        state.file.flush()?;
-        state.last_hash = new_hash;
+        state.check_writable()?;
+        let State {
+            path,
+            writer,
+            checkpointer,
+            ..
+        } = &mut *state;
+        writer.append(entry)?;
+        if let Some(checkpointer) = checkpointer.as_mut().filter(|c| c.due(writer.entries)) {
+            // The entry is durable; a missed checkpoint is retried on the
+            // next record rather than reported as a failed append.
+            if let Err(e) = checkpointer.write(&writer.file, writer.entries, writer.last_hash) {
+                ::tracing::warn!(
+                    target: "synthonyx::audit",
+                    log = %path.display(),
+                    entries = writer.entries,
+                    error = %e,
+                    "audit checkpoint failed; retrying on the next record",
+                );
+            }
+        }
         Ok(())
     }
 }
@@ -90,25 +386,17 @@ impl AuditLogger for FileAuditLogger {
 /// Returns [`AuditError::ChainBroken`] on the first mismatch and
 /// [`AuditError::Serialization`] on the first malformed line.
 pub fn verify_chain(path: impl AsRef<Path>) -> Result<[u8; 32], AuditError> {
-    let f = File::open(path)?;
-    let reader = BufReader::new(f);
-    let mut expected_prev = [0u8; 32];
-    for line in reader.lines() {
-        let line = line?;
-        if line.is_empty() {
-            continue;
-        }
-        let entry: AuditEntry =
-            serde_json::from_str(&line).map_err(|e| AuditError::Serialization(e.to_string()))?;
-        if entry.prev_hash != expected_prev {
-            return Err(AuditError::ChainBroken {
-                expected: hex::encode(expected_prev),
-                actual: hex::encode(entry.prev_hash),
-            });
-        }
-        expected_prev = *blake3::hash(line.as_bytes()).as_bytes();
-    }
-    Ok(expected_prev)
+    verify_chain_from(path, chain::GENESIS)
+}
+
+/// Verify a stored audit log file whose first entry chains to `start`
+/// rather than to the all-zero genesis hash.
+///
+/// Use this to check a single segment against a trusted hash (for example
+/// the previous segment's `end_hash` from a [`SegmentManifest`]) without
+/// re-reading the history before it.
+pub fn verify_chain_from(path: impl AsRef<Path>, start: [u8; 32]) -> Result<[u8; 32], AuditError> {
+    Ok(chain::verify_file(path.as_ref(), start)?.last_hash)
 }
 
 /// Audit sink that emits entries via the `tracing` crate.
@@ -120,8 +408,7 @@ pub struct TracingAuditLogger;
 
 impl AuditLogger for TracingAuditLogger {
     fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
-        let payload =
-            serde_json::to_string(&entry).map_err(|e| AuditError::Serialization(e.to_string()))?;
+        let payload = chain::encode(&entry)?;
         ::tracing::info!(
             target: "synthonyx::audit",
             module = %entry.module,
@@ -172,6 +459,8 @@ mod tests {
             subject: None,
             fields: BTreeMap::from([("count".to_string(), AuditValue::UInt(7))]),
             prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
         }
     }
 
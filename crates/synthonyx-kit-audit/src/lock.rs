@@ -0,0 +1,50 @@
+//! Advisory cross-process write locks.
+//!
+//! The `Mutex` inside each logger serialises writers within one process;
+//! it does nothing about a second process (a misconfigured replica, a CLI
+//! tool) opening the same log and interleaving lines. Every appending sink
+//! therefore holds an exclusive advisory lock on a `<log>.lock` sidecar for
+//! as long as it is open. The lock lives on the sidecar rather than the log
+//! itself so that readers, which take no lock, are never blocked by it on
+//! platforms where locks are mandatory.
+
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use fs4::fs_std::FileExt;
+use synthonyx_kit_core::AuditError;
+
+/// Path of the write-lock sidecar for the log at `log_path`.
+pub fn lock_path(log_path: impl AsRef<Path>) -> PathBuf {
+    let mut path = log_path.as_ref().as_os_str().to_owned();
+    path.push(".lock");
+    PathBuf::from(path)
+}
+
+/// An exclusive lock on a log's sidecar, released when dropped (or when the
+/// process exits, however it exits).
+///
+/// The sidecar file itself is left in place: removing it on release would
+/// let a waiting process lock a file that a third process has already
+/// replaced.
+#[derive(Debug)]
+pub(crate) struct WriteLock {
+    _file: File,
+}
+
+impl WriteLock {
+    /// Take the lock for the log at `log_path` without blocking, failing
+    /// with [`AuditError::Locked`] if another open logger holds it.
+    pub(crate) fn acquire(log_path: &Path) -> Result<Self, AuditError> {
+        let path = lock_path(log_path);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)?;
+        if !FileExt::try_lock_exclusive(&file)? {
+            return Err(AuditError::Locked(log_path.display().to_string()));
+        }
+        Ok(Self { _file: file })
+    }
+}
@@ -0,0 +1,297 @@
+//! In-memory audit sink for tests.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger, CorrelationId, Outcome};
+
+use crate::chain::{self, GENESIS};
+use crate::reader::AuditQuery;
+use crate::verify::{VerifyOptions, verify_reader_with};
+
+/// Audit sink that keeps the chain in memory, for testing RTMs.
+///
+/// Entries are chained, sequenced and encoded exactly as by
+/// [`crate::FileAuditLogger`]; only the storage differs. Clones share the
+/// same log, so a test can hand one clone to the runtime as its
+/// `Config::Audit` and inspect the other:
+///
+/// ```
+/// use synthonyx_kit_audit::MemoryAuditLogger;
+/// use synthonyx_kit_core::{AuditEntry, AuditLogger, CorrelationId, Outcome};
+/// # struct UsersRtm<A> { audit: A }
+/// # impl<A: AuditLogger> UsersRtm<A> {
+/// #     fn register_user(&self, entry: AuditEntry) { self.audit.record(entry).unwrap() }
+/// # }
+/// # fn demo(entry: AuditEntry) {
+/// let audit = MemoryAuditLogger::new();
+/// let users = UsersRtm { audit: audit.clone() };
+/// users.register_user(entry);
+///
+/// audit.assert_outcome(CorrelationId([7; 16]), Outcome::Success);
+/// audit.assert_not_contains("alice@example.com");
+/// audit.assert_chain_valid();
+/// # }
+/// ```
+///
+/// The `assert_*` helpers panic with a description of the log on failure.
+#[derive(Clone, Default)]
+pub struct MemoryAuditLogger {
+    inner: Arc<Mutex<State>>,
+}
+
+struct State {
+    lines: Vec<String>,
+    entries: Vec<AuditEntry>,
+    last_hash: [u8; 32],
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            entries: Vec::new(),
+            last_hash: GENESIS,
+        }
+    }
+}
+
+impl MemoryAuditLogger {
+    /// An empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.inner
+            .lock()
+            .expect("memory audit logger mutex poisoned")
+    }
+
+    /// Every recorded entry, as stored (with `prev_hash` and `sequence`).
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        self.state().entries.clone()
+    }
+
+    /// Every stored line, as [`crate::FileAuditLogger`] would write it.
+    pub fn lines(&self) -> Vec<String> {
+        self.state().lines.clone()
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// True if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash of the last stored line (all zeros if empty).
+    pub fn head(&self) -> [u8; 32] {
+        self.state().last_hash
+    }
+
+    /// Entries matching `query`, in recording order.
+    pub fn query(&self, query: &AuditQuery) -> Vec<AuditEntry> {
+        self.state()
+            .entries
+            .iter()
+            .filter(|e| query.matches(e))
+            .cloned()
+            .collect()
+    }
+
+    /// Entries recorded for `action`, in recording order.
+    pub fn find_by_action(&self, action: &str) -> Vec<AuditEntry> {
+        self.query(&AuditQuery::new().action(action))
+    }
+
+    /// Entries carrying `id`, in recording order.
+    pub fn find_by_correlation_id(&self, id: CorrelationId) -> Vec<AuditEntry> {
+        self.query(&AuditQuery::new().correlation_id(id))
+    }
+
+    /// Re-verify the stored lines: the hash chain, and that each sequence
+    /// number matches its position, plus whatever `options` selects.
+    pub fn verify(&self, options: &VerifyOptions) -> Result<[u8; 32], AuditError> {
+        let joined = self.state().lines.join("\n");
+        verify_reader_with(joined.as_bytes(), &options.clone().check_sequence())
+    }
+
+    /// Assert that at least one entry carries `id` and every such entry has
+    /// `outcome`.
+    #[track_caller]
+    pub fn assert_outcome(&self, id: CorrelationId, outcome: Outcome) {
+        let found = self.find_by_correlation_id(id);
+        assert!(
+            !found.is_empty(),
+            "no audit entry with correlation id {id}; recorded: {:?}",
+            self.summary()
+        );
+        for entry in &found {
+            assert_eq!(
+                entry.outcome, outcome,
+                "audit entry {}.{} for correlation id {id} has outcome {:?}, expected {outcome:?}",
+                entry.module, entry.action, entry.outcome
+            );
+        }
+    }
+
+    /// Assert that `needle` appears in no stored line — neither in a field,
+    /// the subject, the origin, nor anywhere else. Use it to check that PII
+    /// was never written.
+    #[track_caller]
+    pub fn assert_not_contains(&self, needle: &str) {
+        // Copied out so the panic does not poison the logger's mutex.
+        let hit = self
+            .state()
+            .lines
+            .iter()
+            .enumerate()
+            .find(|(_, line)| line.contains(needle))
+            .map(|(i, line)| (i, line.clone()));
+        if let Some((i, line)) = hit {
+            panic!("audit entry {i} contains {needle:?}: {line}");
+        }
+    }
+
+    /// Assert that the stored lines form a valid chain with contiguous
+    /// sequence numbers, returning the head hash.
+    #[track_caller]
+    pub fn assert_chain_valid(&self) -> [u8; 32] {
+        match self.verify(&VerifyOptions::new()) {
+            Ok(head) => head,
+            Err(e) => panic!("audit chain invalid: {e}"),
+        }
+    }
+
+    /// `module.action outcome` for each entry, for panic messages.
+    fn summary(&self) -> Vec<String> {
+        self.state()
+            .entries
+            .iter()
+            .map(|e| format!("{}.{} {:?}", e.module, e.action, e.outcome))
+            .collect()
+    }
+}
+
+impl AuditLogger for MemoryAuditLogger {
+    fn record(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+        let mut state = self.state();
+        entry.prev_hash = state.last_hash;
+        entry.sequence = Some(state.entries.len() as u64);
+        entry.writer = None;
+        let line = chain::encode(&entry)?;
+        state.last_hash = chain::hash_line(&line);
+        state.lines.push(line);
+        state.entries.push(entry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use synthonyx_kit_core::{AuditValue, OriginKind, OriginSnapshot, UnixNanos};
+
+    fn entry(action: &'static str, id: u8, outcome: Outcome) -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(0),
+            correlation_id: CorrelationId([id; 16]),
+            module: Cow::Borrowed("users"),
+            action: Cow::Borrowed(action),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome,
+            subject: None,
+            fields: BTreeMap::from([("n".to_string(), AuditValue::UInt(1))]),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    #[test]
+    fn chains_like_the_file_logger() {
+        let path = std::env::temp_dir().join(format!(
+            "synthonyx-memory-{}-chain.jsonl",
+            std::process::id()
+        ));
+        let file = crate::FileAuditLogger::open(&path).unwrap();
+        let memory = MemoryAuditLogger::new();
+        for e in [
+            entry("register", 1, Outcome::Success),
+            entry("login", 2, Outcome::Denied),
+        ] {
+            file.record(e.clone()).unwrap();
+            memory.record(e).unwrap();
+        }
+        drop(file);
+
+        let stored = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(stored.lines().collect::<Vec<_>>(), memory.lines());
+        assert_eq!(
+            memory.assert_chain_valid(),
+            crate::verify_chain(&path).unwrap()
+        );
+        assert_eq!(memory.head(), memory.assert_chain_valid());
+        assert_eq!(memory.entries()[1].sequence, Some(1));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn assertion_helpers() {
+        let memory = MemoryAuditLogger::new();
+        memory
+            .record(entry("register", 1, Outcome::Success))
+            .unwrap();
+        memory.record(entry("login", 2, Outcome::Denied)).unwrap();
+        memory.record(entry("login", 2, Outcome::Denied)).unwrap();
+
+        assert_eq!(memory.find_by_action("login").len(), 2);
+        assert!(memory.find_by_action("logout").is_empty());
+        memory.assert_outcome(CorrelationId([2; 16]), Outcome::Denied);
+        memory.assert_not_contains("alice");
+        assert_eq!(memory.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "has outcome Denied, expected Success")]
+    fn assert_outcome_reports_mismatch() {
+        let memory = MemoryAuditLogger::new();
+        memory.record(entry("login", 2, Outcome::Denied)).unwrap();
+        memory.assert_outcome(CorrelationId([2; 16]), Outcome::Success);
+    }
+
+    #[test]
+    #[should_panic(expected = "no audit entry with correlation id")]
+    fn assert_outcome_requires_an_entry() {
+        MemoryAuditLogger::new().assert_outcome(CorrelationId([9; 16]), Outcome::Success);
+    }
+
+    #[test]
+    #[should_panic(expected = "audit entry 0 contains \"users\"")]
+    fn assert_not_contains_reports_the_entry() {
+        let memory = MemoryAuditLogger::new();
+        memory.record(entry("login", 2, Outcome::Denied)).unwrap();
+        memory.assert_not_contains("users");
+    }
+
+    #[test]
+    fn failed_assertion_leaves_the_logger_usable() {
+        let memory = MemoryAuditLogger::new();
+        memory.record(entry("login", 2, Outcome::Denied)).unwrap();
+        let failed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            memory.assert_not_contains("users")
+        }));
+        assert!(failed.is_err());
+        memory.record(entry("logout", 2, Outcome::Success)).unwrap();
+        assert_eq!(memory.len(), 2);
+    }
+}
@@ -0,0 +1,482 @@
+//! Merkle-tree index over an audit log, with inclusion and consistency
+//! proofs in the style of RFC 9162 (Certificate Transparency v2).
+//!
+//! The linear chain proves the *whole* log is intact; a Merkle tree lets an
+//! auditor check a *single* entry, or that one tree head is an append-only
+//! extension of another, with `O(log n)` hashes:
+//!
+//! - an [`InclusionProof`] shows that a given stored line is entry `index`
+//!   of the tree identified by a [`TreeHead`], without revealing any other
+//!   entry;
+//! - a [`ConsistencyProof`] shows that the tree at `old_size` is a prefix of
+//!   the tree at `new_size`, i.e. nothing was rewritten between two
+//!   published heads.
+//!
+//! Leaves are the entries' chain hashes (BLAKE3 of the stored line), so the
+//! index can be rebuilt from any verified log with [`MerkleTree::from_log`].
+//! Hashing is domain-separated as in RFC 9162, using BLAKE3:
+//!
+//! ```text
+//! leaf = BLAKE3(0x00 || BLAKE3(line))
+//! node = BLAKE3(0x01 || left || right)
+//! ```
+
+use std::path::Path;
+
+use synthonyx_kit_core::AuditError;
+
+use crate::chain;
+
+/// Root hash of the empty tree: BLAKE3 of the empty string.
+fn empty_root() -> [u8; 32] {
+    *blake3::hash(&[]).as_bytes()
+}
+
+fn leaf_hash(entry_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[0x00]);
+    hasher.update(entry_hash);
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// Largest power of two strictly less than `n` (`n >= 2`).
+fn split_point(n: usize) -> usize {
+    debug_assert!(n >= 2);
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+/// Size and root hash of a Merkle tree — the value a log operator publishes
+/// (or signs) so auditors can check proofs against it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TreeHead {
+    /// Number of leaves.
+    pub size: u64,
+    /// Merkle root.
+    #[serde(with = "chain::hex_hash")]
+    pub root: [u8; 32],
+}
+
+/// Proof that one entry is included in a tree of a given size.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InclusionProof {
+    /// Zero-based position of the entry.
+    pub index: u64,
+    /// Size of the tree the proof is against.
+    pub tree_size: u64,
+    /// Sibling hashes from the leaf up to the root.
+    #[serde(with = "hex_hashes")]
+    pub path: Vec<[u8; 32]>,
+}
+
+impl InclusionProof {
+    /// Verify that `line` (a stored log line, sans trailing newline) is
+    /// entry [`Self::index`] of the tree identified by `head`.
+    pub fn verify(&self, line: &str, head: &TreeHead) -> Result<(), AuditError> {
+        self.verify_entry_hash(&chain::hash_line(line), head)
+    }
+
+    /// Like [`Self::verify`], given the entry's chain hash instead of the
+    /// line itself.
+    pub fn verify_entry_hash(
+        &self,
+        entry_hash: &[u8; 32],
+        head: &TreeHead,
+    ) -> Result<(), AuditError> {
+        if self.tree_size != head.size {
+            return Err(AuditError::Proof(format!(
+                "proof is for tree size {}, head has size {}",
+                self.tree_size, head.size
+            )));
+        }
+        if self.index >= self.tree_size {
+            return Err(AuditError::Proof(format!(
+                "index {} is outside a tree of size {}",
+                self.index, self.tree_size
+            )));
+        }
+        let mut fn_ = self.index;
+        let mut sn = self.tree_size - 1;
+        let mut r = leaf_hash(entry_hash);
+        for p in &self.path {
+            if sn == 0 {
+                return Err(AuditError::Proof("inclusion path is too long".into()));
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                r = node_hash(p, &r);
+                if fn_ & 1 == 0 {
+                    while fn_ & 1 == 0 && fn_ != 0 {
+                        fn_ >>= 1;
+                        sn >>= 1;
+                    }
+                }
+            } else {
+                r = node_hash(&r, p);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        if sn != 0 || r != head.root {
+            return Err(AuditError::Proof(
+                "inclusion proof does not lead to the tree root".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Proof that a smaller tree is a prefix of a larger one.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConsistencyProof {
+    /// Size of the older tree.
+    pub old_size: u64,
+    /// Size of the newer tree.
+    pub new_size: u64,
+    /// Intermediate node hashes.
+    #[serde(with = "hex_hashes")]
+    pub path: Vec<[u8; 32]>,
+}
+
+impl ConsistencyProof {
+    /// Verify that `old` is a prefix of `new`.
+    pub fn verify(&self, old: &TreeHead, new: &TreeHead) -> Result<(), AuditError> {
+        if self.old_size != old.size || self.new_size != new.size {
+            return Err(AuditError::Proof(format!(
+                "proof is for sizes {}..{}, heads have {}..{}",
+                self.old_size, self.new_size, old.size, new.size
+            )));
+        }
+        if old.size > new.size {
+            return Err(AuditError::Proof("old tree is larger than new tree".into()));
+        }
+        if old.size == new.size {
+            return if self.path.is_empty() && old.root == new.root {
+                Ok(())
+            } else {
+                Err(AuditError::Proof(
+                    "equal-size trees have different roots".into(),
+                ))
+            };
+        }
+        if old.size == 0 {
+            // Every tree extends the empty tree.
+            return if self.path.is_empty() {
+                Ok(())
+            } else {
+                Err(AuditError::Proof(
+                    "non-empty proof from the empty tree".into(),
+                ))
+            };
+        }
+        let mut nodes = Vec::with_capacity(self.path.len() + 1);
+        if old.size.is_power_of_two() {
+            nodes.push(old.root);
+        }
+        nodes.extend_from_slice(&self.path);
+        let Some((&first, rest)) = nodes.split_first() else {
+            return Err(AuditError::Proof("empty consistency proof".into()));
+        };
+
+        let mut fn_ = old.size - 1;
+        let mut sn = new.size - 1;
+        while fn_ & 1 == 1 {
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        let mut fr = first;
+        let mut sr = first;
+        for c in rest {
+            if sn == 0 {
+                return Err(AuditError::Proof("consistency path is too long".into()));
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                fr = node_hash(c, &fr);
+                sr = node_hash(c, &sr);
+                if fn_ & 1 == 0 {
+                    while fn_ & 1 == 0 && fn_ != 0 {
+                        fn_ >>= 1;
+                        sn >>= 1;
+                    }
+                }
+            } else {
+                sr = node_hash(&sr, c);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+        if sn != 0 || fr != old.root || sr != new.root {
+            return Err(AuditError::Proof(
+                "consistency proof does not reproduce both roots".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// An in-memory Merkle tree over audit entry hashes.
+///
+/// Appending is `O(1)`; roots and proofs are computed on demand in `O(n)`
+/// hashing for the subtrees involved. Rebuild from disk with
+/// [`Self::from_log`] when a proof is requested, or keep one alongside a
+/// long-running logger and [`Self::push_line`] each stored line.
+#[derive(Clone, Debug, Default)]
+pub struct MerkleTree {
+    leaves: Vec<[u8; 32]>,
+}
+
+impl MerkleTree {
+    /// An empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a tree over every entry of the log at `path`, verifying the
+    /// hash chain on the way.
+    pub fn from_log(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let mut tree = Self::new();
+        chain::walk_file(path.as_ref(), chain::GENESIS, |_, hash| {
+            tree.push_entry_hash(hash);
+            Ok(())
+        })?;
+        Ok(tree)
+    }
+
+    /// Append a stored log line (sans trailing newline).
+    pub fn push_line(&mut self, line: &str) {
+        self.push_entry_hash(chain::hash_line(line));
+    }
+
+    /// Append an entry by its chain hash.
+    pub fn push_entry_hash(&mut self, entry_hash: [u8; 32]) {
+        self.leaves.push(leaf_hash(&entry_hash));
+    }
+
+    /// Number of leaves.
+    pub fn len(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    /// True if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// The current tree head.
+    pub fn head(&self) -> TreeHead {
+        TreeHead {
+            size: self.len(),
+            root: subtree_root(&self.leaves),
+        }
+    }
+
+    /// The head of the tree as it was when it held `size` leaves.
+    pub fn head_at(&self, size: u64) -> Result<TreeHead, AuditError> {
+        let leaves = self.prefix(size)?;
+        Ok(TreeHead {
+            size,
+            root: subtree_root(leaves),
+        })
+    }
+
+    /// Prove that entry `index` is included in the tree of size `tree_size`.
+    pub fn inclusion_proof(
+        &self,
+        index: u64,
+        tree_size: u64,
+    ) -> Result<InclusionProof, AuditError> {
+        let leaves = self.prefix(tree_size)?;
+        if index >= tree_size {
+            return Err(AuditError::Proof(format!(
+                "index {index} is outside a tree of size {tree_size}"
+            )));
+        }
+        let mut path = Vec::new();
+        inclusion_path(index as usize, leaves, &mut path);
+        Ok(InclusionProof {
+            index,
+            tree_size,
+            path,
+        })
+    }
+
+    /// Prove that the tree of size `old_size` is a prefix of the tree of
+    /// size `new_size`.
+    pub fn consistency_proof(
+        &self,
+        old_size: u64,
+        new_size: u64,
+    ) -> Result<ConsistencyProof, AuditError> {
+        let leaves = self.prefix(new_size)?;
+        if old_size > new_size {
+            return Err(AuditError::Proof(format!(
+                "old size {old_size} exceeds new size {new_size}"
+            )));
+        }
+        let mut path = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            subproof(old_size as usize, leaves, true, &mut path);
+        }
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            path,
+        })
+    }
+
+    fn prefix(&self, size: u64) -> Result<&[[u8; 32]], AuditError> {
+        self.leaves
+            .get(..size as usize)
+            .ok_or_else(|| AuditError::Proof(format!("tree has {} leaves, not {size}", self.len())))
+    }
+}
+
+fn subtree_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves {
+        [] => empty_root(),
+        [leaf] => *leaf,
+        _ => {
+            let k = split_point(leaves.len());
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+fn inclusion_path(index: usize, leaves: &[[u8; 32]], out: &mut Vec<[u8; 32]>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+    let k = split_point(leaves.len());
+    if index < k {
+        inclusion_path(index, &leaves[..k], out);
+        out.push(subtree_root(&leaves[k..]));
+    } else {
+        inclusion_path(index - k, &leaves[k..], out);
+        out.push(subtree_root(&leaves[..k]));
+    }
+}
+
+fn subproof(m: usize, leaves: &[[u8; 32]], complete: bool, out: &mut Vec<[u8; 32]>) {
+    let n = leaves.len();
+    if m == n {
+        if !complete {
+            out.push(subtree_root(leaves));
+        }
+        return;
+    }
+    let k = split_point(n);
+    if m <= k {
+        subproof(m, &leaves[..k], complete, out);
+        out.push(subtree_root(&leaves[k..]));
+    } else {
+        subproof(m - k, &leaves[k..], false, out);
+        out.push(subtree_root(&leaves[..k]));
+    }
+}
+
+/// Serde adapter storing a list of 32-byte hashes as lower-case hex.
+mod hex_hashes {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error, ser::SerializeSeq};
+
+    pub(super) fn serialize<S: Serializer>(hashes: &[[u8; 32]], s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(hashes.len()))?;
+        for hash in hashes {
+            seq.serialize_element(&hex::encode(hash))?;
+        }
+        seq.end()
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<[u8; 32]>, D::Error> {
+        Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|s| {
+                let mut out = [0u8; 32];
+                hex::decode_to_slice(s, &mut out).map_err(D::Error::custom)?;
+                Ok(out)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(n: usize) -> MerkleTree {
+        let mut tree = MerkleTree::new();
+        for i in 0..n {
+            tree.push_line(&format!("{{\"entry\":{i}}}"));
+        }
+        tree
+    }
+
+    #[test]
+    fn inclusion_proofs_verify_for_every_index_and_size() {
+        let tree = tree(17);
+        for size in 1..=17u64 {
+            let head = tree.head_at(size).unwrap();
+            for index in 0..size {
+                let proof = tree.inclusion_proof(index, size).unwrap();
+                proof
+                    .verify(&format!("{{\"entry\":{index}}}"), &head)
+                    .unwrap_or_else(|e| panic!("index {index} size {size}: {e}"));
+            }
+        }
+    }
+
+    #[test]
+    fn inclusion_proof_rejects_other_line() {
+        let tree = tree(8);
+        let head = tree.head();
+        let proof = tree.inclusion_proof(3, 8).unwrap();
+        assert!(proof.verify("{\"entry\":4}", &head).is_err());
+    }
+
+    #[test]
+    fn consistency_proofs_verify_for_every_pair() {
+        let tree = tree(17);
+        for new_size in 0..=17u64 {
+            let new = tree.head_at(new_size).unwrap();
+            for old_size in 0..=new_size {
+                let old = tree.head_at(old_size).unwrap();
+                let proof = tree.consistency_proof(old_size, new_size).unwrap();
+                proof
+                    .verify(&old, &new)
+                    .unwrap_or_else(|e| panic!("{old_size}..{new_size}: {e}"));
+            }
+        }
+    }
+
+    #[test]
+    fn consistency_proof_rejects_rewritten_history() {
+        let honest = tree(6);
+        let mut rewritten = MerkleTree::new();
+        for i in 0..9 {
+            let line = if i == 2 {
+                "{\"entry\":\"rewritten\"}".to_string()
+            } else {
+                format!("{{\"entry\":{i}}}")
+            };
+            rewritten.push_line(&line);
+        }
+        let old = honest.head();
+        let new = rewritten.head();
+        let proof = rewritten.consistency_proof(6, 9).unwrap();
+        assert!(proof.verify(&old, &new).is_err());
+    }
+
+    #[test]
+    fn proofs_round_trip_through_json() {
+        let tree = tree(5);
+        let proof = tree.inclusion_proof(2, 5).unwrap();
+        let json = serde_json::to_string(&proof).unwrap();
+        let back: InclusionProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, proof);
+    }
+}
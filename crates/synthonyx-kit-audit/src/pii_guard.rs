@@ -0,0 +1,714 @@
+//! Guard against personal data leaking into audit entries.
+//!
+//! `AuditValue::Str` is meant for short non-PII strings, but nothing stops
+//! a caller putting an email address or an IBAN there. [`PiiGuardAuditLogger`]
+//! scans each entry's string fields, subject and principal with
+//! [`detect_pii`] before it reaches the inner sink, and rejects, hashes or
+//! redacts what it finds according to its per-[`PiiKind`] policy.
+//!
+//! Detection is pattern-based and deliberately conservative: every match
+//! must stand alone (no letter or digit directly before or after it), and
+//! the kinds that carry a check digit must pass it.
+//!
+//! | Kind | Recognised |
+//! |------|------------|
+//! | [`PiiKind::Email`] | `local@domain.tld` |
+//! | [`PiiKind::Phone`] | `+` and 8–15 digits; or a leading `0` and 10–11 digits |
+//! | [`PiiKind::Iban`] | country code, check digits, BBAN; ISO 13616 mod-97 check |
+//! | [`PiiKind::CardNumber`] | 13–19 digits passing the Luhn check |
+//! | [`PiiKind::NationalId`] | US SSN (`123-45-6789`), UK NINO, Spanish DNI/NIE with their check letter |
+//!
+//! Spaces, `-`, `.` and parentheses may separate digit groups.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::Range;
+
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, Event, EventBus, Secret,
+    Severity,
+};
+
+/// Module name reported by [`PiiDetected`] events.
+pub const PII_GUARD_MODULE: &str = "synthonyx-kit-audit";
+
+const HASH_CONTEXT: &[u8] = b"synthonyx-audit-pii/v1";
+const DNI_LETTERS: &[u8; 23] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+
+/// A category of personal data recognised by [`detect_pii`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PiiKind {
+    /// An email address.
+    Email,
+    /// A telephone number.
+    Phone,
+    /// An International Bank Account Number.
+    Iban,
+    /// A payment card number (PAN).
+    CardNumber,
+    /// A national identity or social-security number.
+    NationalId,
+}
+
+impl PiiKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [PiiKind; 5] = [
+        Self::Email,
+        Self::Phone,
+        Self::Iban,
+        Self::CardNumber,
+        Self::NationalId,
+    ];
+
+    /// Lower-case name, as used in redaction markers.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Phone => "phone",
+            Self::Iban => "iban",
+            Self::CardNumber => "card_number",
+            Self::NationalId => "national_id",
+        }
+    }
+}
+
+impl fmt::Display for PiiKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What [`PiiGuardAuditLogger`] does with an entry containing a kind of
+/// personal data. Ordered from least to most severe: when one value holds
+/// several kinds, the most severe action applies.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PiiAction {
+    /// Pass the value through untouched and raise no signal.
+    Allow,
+    /// Replace each match with `[redacted:<kind>]`, keeping the rest of
+    /// the value.
+    Redact,
+    /// Replace the whole value with a keyed BLAKE3 hash, so equal values
+    /// stay linkable without being readable. Needs
+    /// [`PiiGuardAuditLogger::with_hash_key`]; without a key it rejects.
+    Hash,
+    /// Refuse the entry with [`AuditError::PiiDetected`]; nothing is
+    /// recorded.
+    Reject,
+}
+
+/// Where in an entry personal data was found.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PiiLocation {
+    /// The string field with this key.
+    Field(String),
+    /// The entry's `subject`.
+    Subject,
+    /// The origin's `principal`.
+    Principal,
+}
+
+impl fmt::Display for PiiLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(key) => write!(f, "field `{key}`"),
+            Self::Subject => f.write_str("subject"),
+            Self::Principal => f.write_str("principal"),
+        }
+    }
+}
+
+/// One match found by [`detect_pii`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiiMatch {
+    /// What was found.
+    pub kind: PiiKind,
+    /// Byte range of the match in the scanned text.
+    pub range: Range<usize>,
+}
+
+/// Security-severity event raised for every match [`PiiGuardAuditLogger`]
+/// acts on. Carries the location and kind, never the matched data.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PiiDetected {
+    /// Module of the offending entry.
+    pub module: String,
+    /// Action of the offending entry.
+    pub action: String,
+    /// Correlation id of the offending entry.
+    pub correlation_id: CorrelationId,
+    /// Where the data was found.
+    pub location: PiiLocation,
+    /// What was found.
+    pub kind: PiiKind,
+    /// What the guard did about it.
+    pub handling: PiiAction,
+}
+
+impl Event for PiiDetected {
+    fn module(&self) -> &'static str {
+        PII_GUARD_MODULE
+    }
+
+    fn name(&self) -> &'static str {
+        "pii_detected"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Security
+    }
+}
+
+/// Every match of a recognised kind of personal data in `text`, ordered by
+/// position. Matches of different kinds may overlap.
+pub fn detect_pii(text: &str) -> Vec<PiiMatch> {
+    let mut found = Vec::new();
+    emails(text, &mut found);
+    digit_groups(text, &mut found);
+    ibans(text, &mut found);
+    id_tokens(text, &mut found);
+    found.sort_by_key(|m| (m.range.start, m.range.end));
+    found
+}
+
+/// Scans entries for personal data before passing them to an inner logger.
+///
+/// Every kind is rejected by default; relax per kind with
+/// [`Self::with_action`]. Each match acted on is logged at `warn` level via
+/// `tracing` and, with [`Self::with_signal`], published as a
+/// [`PiiDetected`] event of [`Severity::Security`].
+///
+/// Place the guard inside a [`crate::ShreddingAuditLogger`], not around
+/// it, so it sees the subject tokens rather than the clear identifiers the
+/// shredder is there to remove.
+///
+/// ```
+/// use synthonyx_kit_audit::{PiiAction, PiiGuardAuditLogger, PiiKind, TracingAuditLogger};
+/// use synthonyx_kit_core::Secret;
+///
+/// let audit = PiiGuardAuditLogger::new(TracingAuditLogger)
+///     .with_hash_key(Secret::new([7; 32]))
+///     .with_action(PiiKind::Email, PiiAction::Hash)
+///     .with_action(PiiKind::Phone, PiiAction::Redact)
+///     .allow_field("iban_hash");
+/// ```
+pub struct PiiGuardAuditLogger<L> {
+    inner: L,
+    actions: BTreeMap<PiiKind, PiiAction>,
+    allowed_fields: BTreeSet<String>,
+    hash_key: Option<Secret<[u8; 32]>>,
+    signal: Option<Box<dyn EventBus<PiiDetected>>>,
+}
+
+impl<L: AuditLogger> PiiGuardAuditLogger<L> {
+    /// Guard `inner`, rejecting entries that contain any kind of personal
+    /// data.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            actions: PiiKind::ALL
+                .into_iter()
+                .map(|kind| (kind, PiiAction::Reject))
+                .collect(),
+            allowed_fields: BTreeSet::new(),
+            hash_key: None,
+            signal: None,
+        }
+    }
+
+    /// Handle `kind` with `action` instead of rejecting it.
+    pub fn with_action(mut self, kind: PiiKind, action: PiiAction) -> Self {
+        self.actions.insert(kind, action);
+        self
+    }
+
+    /// Key for [`PiiAction::Hash`]. Keep it stable to keep hashes linkable
+    /// across restarts, and secret: an unkeyed hash of an email address is
+    /// reversible by guessing.
+    pub fn with_hash_key(mut self, key: Secret<[u8; 32]>) -> Self {
+        self.hash_key = Some(key);
+        self
+    }
+
+    /// Do not scan the field named `key`, e.g. one that holds a reference
+    /// number known to look like a phone number.
+    pub fn allow_field(mut self, key: impl Into<String>) -> Self {
+        self.allowed_fields.insert(key.into());
+        self
+    }
+
+    /// Publish a [`PiiDetected`] event to `bus` for every match acted on.
+    pub fn with_signal(mut self, bus: impl EventBus<PiiDetected>) -> Self {
+        self.signal = Some(Box::new(bus));
+        self
+    }
+
+    /// The wrapped logger.
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    fn action(&self, kind: PiiKind) -> PiiAction {
+        match self.actions[&kind] {
+            PiiAction::Hash if self.hash_key.is_none() => PiiAction::Reject,
+            action => action,
+        }
+    }
+
+    /// Apply the policy to one value, returning the replacement if it
+    /// changes. Raises a signal for every match acted on.
+    fn check(
+        &self,
+        entry: &AuditEntry,
+        location: PiiLocation,
+        text: &str,
+    ) -> Result<Option<Replacement>, AuditError> {
+        let mut strongest = PiiAction::Allow;
+        let mut redact = Vec::new();
+        for found in detect_pii(text) {
+            let handling = self.action(found.kind);
+            if handling == PiiAction::Allow {
+                continue;
+            }
+            self.raise(entry, &location, found.kind, handling);
+            if handling == PiiAction::Reject {
+                return Err(AuditError::PiiDetected(format!(
+                    "{} in {location} of {}.{}",
+                    found.kind, entry.module, entry.action
+                )));
+            }
+            if handling == PiiAction::Redact {
+                redact.push(found.clone());
+            }
+            strongest = strongest.max(handling);
+        }
+        Ok(match (strongest, &self.hash_key) {
+            (PiiAction::Hash, Some(key)) => Some(Replacement::Hash(pii_hash(key, text))),
+            (PiiAction::Redact, _) => Some(Replacement::Text(redacted(text, redact))),
+            _ => None,
+        })
+    }
+
+    fn raise(
+        &self,
+        entry: &AuditEntry,
+        location: &PiiLocation,
+        kind: PiiKind,
+        handling: PiiAction,
+    ) {
+        ::tracing::warn!(
+            target: "synthonyx::audit",
+            severity = "security",
+            module = %entry.module,
+            action = %entry.action,
+            correlation_id = %entry.correlation_id,
+            location = %location,
+            kind = %kind,
+            handling = ?handling,
+            "personal data in audit entry",
+        );
+        if let Some(bus) = &self.signal {
+            bus.emit(PiiDetected {
+                module: entry.module.to_string(),
+                action: entry.action.to_string(),
+                correlation_id: entry.correlation_id,
+                location: location.clone(),
+                kind,
+                handling,
+            });
+        }
+    }
+}
+
+enum Replacement {
+    Text(String),
+    Hash(String),
+}
+
+impl Replacement {
+    fn into_string(self) -> String {
+        match self {
+            Self::Text(s) | Self::Hash(s) => s,
+        }
+    }
+}
+
+impl<L: AuditLogger> AuditLogger for PiiGuardAuditLogger<L> {
+    fn record(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+        let mut fields = Vec::new();
+        for (key, value) in &entry.fields {
+            if let AuditValue::Str(text) = value {
+                if self.allowed_fields.contains(key) {
+                    continue;
+                }
+                let location = PiiLocation::Field(key.clone());
+                if let Some(replacement) = self.check(&entry, location, text)? {
+                    fields.push((key.clone(), replacement));
+                }
+            }
+        }
+        let subject = match &entry.subject {
+            Some(subject) => self.check(&entry, PiiLocation::Subject, subject)?,
+            None => None,
+        };
+        let principal = match &entry.origin.principal {
+            Some(principal) => self.check(&entry, PiiLocation::Principal, principal)?,
+            None => None,
+        };
+
+        for (key, replacement) in fields {
+            let value = match replacement {
+                Replacement::Text(text) => AuditValue::Str(text),
+                Replacement::Hash(hash) => AuditValue::Hash(hash),
+            };
+            entry.fields.insert(key, value);
+        }
+        if let Some(replacement) = subject {
+            entry.subject = Some(replacement.into_string());
+        }
+        if let Some(replacement) = principal {
+            entry.origin.principal = Some(replacement.into_string());
+        }
+        self.inner.record(entry)
+    }
+}
+
+/// Hex keyed BLAKE3 hash written in place of a value under
+/// [`PiiAction::Hash`]. Independent of where the value was found, so the
+/// same address hashes alike in a field and in the subject.
+fn pii_hash(key: &Secret<[u8; 32]>, text: &str) -> String {
+    let mut hasher = blake3::Hasher::new_keyed(key.expose());
+    hasher.update(HASH_CONTEXT);
+    hasher.update(text.as_bytes());
+    hasher.finalize().to_hex().to_string()
+}
+
+/// `text` with each of `matches` replaced by a marker. Overlapping matches
+/// merge into the first.
+fn redacted(text: &str, mut matches: Vec<PiiMatch>) -> String {
+    matches.sort_by_key(|m| m.range.start);
+    let mut out = String::with_capacity(text.len());
+    let mut at = 0;
+    for found in matches {
+        if found.range.start < at {
+            at = at.max(found.range.end);
+            continue;
+        }
+        out.push_str(&text[at..found.range.start]);
+        out.push_str("[redacted:");
+        out.push_str(found.kind.name());
+        out.push(']');
+        at = found.range.end;
+    }
+    out.push_str(&text[at..]);
+    out
+}
+
+/// True if the characters either side of `range` do not extend a match:
+/// neither is a letter or digit.
+fn stands_alone(text: &str, range: &Range<usize>) -> bool {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+fn emails(text: &str, found: &mut Vec<PiiMatch>) {
+    let bytes = text.as_bytes();
+    let local = |b: u8| b.is_ascii_alphanumeric() || b"._%+-".contains(&b);
+    let domain = |b: u8| b.is_ascii_alphanumeric() || b == b'.' || b == b'-';
+    for (at, _) in text.match_indices('@') {
+        let mut start = at;
+        while start > 0 && local(bytes[start - 1]) {
+            start -= 1;
+        }
+        let mut end = at + 1;
+        while end < bytes.len() && domain(bytes[end]) {
+            end += 1;
+        }
+        while end > at + 1 && bytes[end - 1] == b'.' {
+            end -= 1;
+        }
+        let host = &text[at + 1..end];
+        let labels: Vec<&str> = host.split('.').collect();
+        let tld = labels.last().copied().unwrap_or_default();
+        let valid = start < at
+            && labels.len() >= 2
+            && labels.iter().all(|l| !l.is_empty())
+            && tld.len() >= 2
+            && tld.bytes().all(|b| b.is_ascii_alphabetic());
+        if valid && stands_alone(text, &(start..end)) {
+            found.push(PiiMatch {
+                kind: PiiKind::Email,
+                range: start..end,
+            });
+        }
+    }
+}
+
+/// Phone numbers, card numbers and US SSNs: runs of digits and separators.
+fn digit_groups(text: &str, found: &mut Vec<PiiMatch>) {
+    let bytes = text.as_bytes();
+    let separator = |b: u8| b" -.()".contains(&b);
+    let mut i = 0;
+    while i < bytes.len() {
+        let plus = bytes[i] == b'+';
+        let opens = bytes[i] == b'(';
+        if !(bytes[i].is_ascii_digit() || plus || opens) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && (bytes[i].is_ascii_digit() || separator(bytes[i])) {
+            i += 1;
+        }
+        let mut end = i;
+        while end > start && !bytes[end - 1].is_ascii_digit() {
+            end -= 1;
+        }
+        let range = start..end;
+        if end == start || !stands_alone(text, &range) {
+            continue;
+        }
+        let span = &text[range.clone()];
+        let digits: Vec<u8> = span
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .map(|b| b - b'0')
+            .collect();
+        let kind = if is_ssn(span) {
+            Some(PiiKind::NationalId)
+        } else if !plus && (13..=19).contains(&digits.len()) && luhn(&digits) {
+            Some(PiiKind::CardNumber)
+        } else if (plus && (8..=15).contains(&digits.len()))
+            || (!plus && digits[0] == 0 && (10..=11).contains(&digits.len()))
+        {
+            Some(PiiKind::Phone)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            found.push(PiiMatch { kind, range });
+        }
+    }
+}
+
+fn luhn(digits: &[u8]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            let d = u32::from(d);
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// `AAA-GG-SSSS` with a valid area (not 000, 666 or 9xx), group and serial.
+fn is_ssn(span: &str) -> bool {
+    let b = span.as_bytes();
+    if b.len() != 11 || b[3] != b'-' || b[6] != b'-' {
+        return false;
+    }
+    let digits = |r: Range<usize>| b[r].iter().all(u8::is_ascii_digit);
+    if !(digits(0..3) && digits(4..6) && digits(7..11)) {
+        return false;
+    }
+    let (area, group, serial) = (&span[0..3], &span[4..6], &span[7..11]);
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+fn ibans(text: &str, found: &mut Vec<PiiMatch>) {
+    let bytes = text.as_bytes();
+    for start in 0..bytes.len().saturating_sub(3) {
+        let head = &bytes[start..start + 4];
+        if !(head[0].is_ascii_alphabetic()
+            && head[1].is_ascii_alphabetic()
+            && head[2].is_ascii_digit()
+            && head[3].is_ascii_digit())
+            || text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+        // Alphanumerics, allowing single spaces between groups; remember
+        // every position a candidate could end at.
+        let mut chars = Vec::new();
+        let mut ends = Vec::new();
+        let mut i = start;
+        while i < bytes.len() && chars.len() < 34 {
+            if bytes[i].is_ascii_alphanumeric() {
+                chars.push(bytes[i].to_ascii_uppercase());
+                i += 1;
+                ends.push((chars.len(), i));
+            } else if bytes[i] == b' ' && bytes.get(i + 1).is_some_and(u8::is_ascii_alphanumeric) {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let valid = ends.iter().rev().find(|&&(len, end)| {
+            len >= 15 && stands_alone(text, &(start..end)) && iban_checksum(&chars[..len])
+        });
+        if let Some(&(_, end)) = valid {
+            found.push(PiiMatch {
+                kind: PiiKind::Iban,
+                range: start..end,
+            });
+        }
+    }
+}
+
+/// ISO 13616: move the first four characters to the end, map letters to
+/// 10–35, and the number must be 1 mod 97.
+fn iban_checksum(chars: &[u8]) -> bool {
+    let mut rem = 0u32;
+    for &c in chars[4..].iter().chain(&chars[..4]) {
+        rem = match c {
+            b'0'..=b'9' => (rem * 10 + u32::from(c - b'0')) % 97,
+            b'A'..=b'Z' => (rem * 100 + u32::from(c - b'A') + 10) % 97,
+            _ => return false,
+        };
+    }
+    rem == 1
+}
+
+/// UK NINOs and Spanish DNIs/NIEs: single alphanumeric tokens (NINOs may
+/// be spaced in pairs).
+fn id_tokens(text: &str, found: &mut Vec<PiiMatch>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_alphanumeric() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut compact = Vec::new();
+        while i < bytes.len() && compact.len() < 9 {
+            if bytes[i].is_ascii_alphanumeric() {
+                compact.push(bytes[i].to_ascii_uppercase());
+                i += 1;
+            } else if (bytes[i] == b' ' || bytes[i] == b'-')
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_alphanumeric)
+                && !compact.is_empty()
+            {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let range = start..i;
+        if compact.len() == 9
+            && stands_alone(text, &range)
+            && (is_nino(&compact) || is_dni(&compact))
+        {
+            found.push(PiiMatch {
+                kind: PiiKind::NationalId,
+                range,
+            });
+        } else {
+            // Resume after the first character of this token.
+            i = start + 1;
+            while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+        }
+    }
+}
+
+fn is_nino(c: &[u8]) -> bool {
+    const BAD_FIRST: &[u8] = b"DFIQUV";
+    const BAD_SECOND: &[u8] = b"DFIOQUV";
+    const BAD_PREFIX: [&[u8]; 7] = [b"BG", b"GB", b"KN", b"NK", b"NT", b"TN", b"ZZ"];
+    c[0].is_ascii_uppercase()
+        && c[1].is_ascii_uppercase()
+        && !BAD_FIRST.contains(&c[0])
+        && !BAD_SECOND.contains(&c[1])
+        && !BAD_PREFIX.contains(&&c[..2])
+        && c[2..8].iter().all(u8::is_ascii_digit)
+        && (b'A'..=b'D').contains(&c[8])
+}
+
+fn is_dni(c: &[u8]) -> bool {
+    let prefix = match c[0] {
+        b'X' => b'0',
+        b'Y' => b'1',
+        b'Z' => b'2',
+        d if d.is_ascii_digit() => d,
+        _ => return false,
+    };
+    if !c[1..8].iter().all(u8::is_ascii_digit) {
+        return false;
+    }
+    let number = std::iter::once(prefix)
+        .chain(c[1..8].iter().copied())
+        .fold(0u32, |n, d| n * 10 + u32::from(d - b'0'));
+    DNI_LETTERS[(number % 23) as usize] == c[8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<PiiKind> {
+        detect_pii(text).into_iter().map(|m| m.kind).collect()
+    }
+
+    #[test]
+    fn recognises_each_kind() {
+        assert_eq!(
+            kinds("contact alice.smith+tag@example.co.uk now"),
+            [PiiKind::Email]
+        );
+        assert_eq!(kinds("call +44 20 7946 0958"), [PiiKind::Phone]);
+        assert_eq!(kinds("call 06-12345678"), [PiiKind::Phone]);
+        assert_eq!(kinds("DE89 3704 0044 0532 0130 00"), [PiiKind::Iban]);
+        assert_eq!(kinds("GB82WEST12345698765432"), [PiiKind::Iban]);
+        assert_eq!(kinds("card 4111 1111 1111 1111"), [PiiKind::CardNumber]);
+        assert_eq!(kinds("ssn 078-05-1120"), [PiiKind::NationalId]);
+        assert_eq!(kinds("nino QQ 12 34 56 C"), Vec::<PiiKind>::new());
+        assert_eq!(kinds("nino AB 12 34 56 C"), [PiiKind::NationalId]);
+        assert_eq!(kinds("dni 12345678Z"), [PiiKind::NationalId]);
+        assert_eq!(kinds("nie X1234567L"), [PiiKind::NationalId]);
+    }
+
+    #[test]
+    fn ignores_lookalikes() {
+        for text in [
+            "status ok",
+            "user@localhost",
+            "2024-01-15",
+            "10.0.0.1",
+            "v0.13.1",
+            "order 1700000000123",
+            "4111 1111 1111 1112",
+            "DE00 3704 0044 0532 0130 00",
+            "dni 12345678A",
+            "000-12-3456",
+            blake3::hash(b"x").to_hex().as_ref(),
+        ] {
+            assert!(
+                detect_pii(text).is_empty(),
+                "{text}: {:?}",
+                detect_pii(text)
+            );
+        }
+    }
+
+    #[test]
+    fn redaction_keeps_surrounding_text() {
+        let text = "from bob@example.com to +33 1 23 45 67 89";
+        let out = redacted(text, detect_pii(text));
+        assert_eq!(out, "from [redacted:email] to [redacted:phone]");
+    }
+}
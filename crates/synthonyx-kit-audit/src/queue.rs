@@ -0,0 +1,317 @@
+//! Bounded, non-blocking queue in front of any audit sink.
+//!
+//! [`QueuedAuditLogger`] hands entries to a background thread through a
+//! fixed-size queue. `record()` never waits for the inner sink: when the
+//! queue is full it fails at once with [`AuditError::Backpressure`], so a
+//! dispatch can deny the operation instead of hanging behind a slow sink.
+//! Shutdown drains everything already accepted.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::JoinHandle;
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+
+type Ack = SyncSender<Result<(), AuditError>>;
+
+enum Msg {
+    Record(Box<AuditEntry>),
+    Flush(Ack),
+    Shutdown(Ack),
+}
+
+/// Point-in-time counters of a [`QueuedAuditLogger`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct QueueMetrics {
+    /// Maximum number of entries the queue holds.
+    pub capacity: usize,
+    /// Entries waiting in the queue, not counting the one being written.
+    pub depth: usize,
+    /// Largest `depth` seen since the logger was created.
+    pub high_water: usize,
+    /// Entries accepted by `record()`.
+    pub enqueued: u64,
+    /// Entries the inner sink recorded successfully.
+    pub written: u64,
+    /// Entries refused with [`AuditError::Backpressure`].
+    pub rejected: u64,
+    /// Accepted entries that were not recorded: the one the inner sink
+    /// failed on, and any queued behind it.
+    pub failed: u64,
+}
+
+/// Wraps any [`AuditLogger`] in a bounded queue drained by a background
+/// thread.
+///
+/// `record()` returns as soon as the entry is queued, so acknowledged
+/// entries are only as durable as the queue until the inner sink has
+/// written them; call [`Self::flush`] or [`Self::shutdown`] where that
+/// matters. A full queue fails `record()` with
+/// [`AuditError::Backpressure`] carrying the depth; nothing is recorded.
+///
+/// If the inner sink fails, the queue fails closed like
+/// [`crate::GroupCommitAuditLogger`]: the error is logged via `tracing`,
+/// entries still queued behind it are discarded and counted in
+/// [`QueueMetrics::failed`], and every later call fails with
+/// [`AuditError::Closed`]. A sink that has failed cannot vouch for the
+/// entries that follow.
+///
+/// Dropping the logger shuts it down, discarding any error.
+pub struct QueuedAuditLogger {
+    tx: SyncSender<Msg>,
+    shared: Arc<Shared>,
+    /// `true` once shut down. Held for reading around each enqueue and for
+    /// writing while closing, so no entry is queued behind the shutdown
+    /// request and silently lost.
+    closed: RwLock<bool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct Shared {
+    capacity: usize,
+    depth: AtomicUsize,
+    high_water: AtomicUsize,
+    enqueued: AtomicU64,
+    written: AtomicU64,
+    rejected: AtomicU64,
+    failed: AtomicU64,
+    /// The inner sink's error, once it has failed.
+    failure: Mutex<Option<String>>,
+}
+
+impl Shared {
+    fn failure(&self) -> MutexGuard<'_, Option<String>> {
+        self.failure
+            .lock()
+            .expect("queued audit logger mutex poisoned")
+    }
+
+    fn status(&self) -> Result<(), AuditError> {
+        match &*self.failure() {
+            Some(reason) => Err(inner_failed(reason)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl QueuedAuditLogger {
+    /// Queue up to `capacity` entries (at least one) in front of `inner`,
+    /// and start the thread that drains them.
+    pub fn new(inner: impl AuditLogger, capacity: usize) -> Result<Self, AuditError> {
+        let capacity = capacity.max(1);
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let shared = Arc::new(Shared {
+            capacity,
+            depth: AtomicUsize::new(0),
+            high_water: AtomicUsize::new(0),
+            enqueued: AtomicU64::new(0),
+            written: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            failure: Mutex::new(None),
+        });
+        let worker = Arc::clone(&shared);
+        let handle = std::thread::Builder::new()
+            .name("synthonyx-audit-queue".into())
+            .spawn(move || drain(inner, &worker, rx))?;
+        Ok(Self {
+            tx,
+            shared,
+            closed: RwLock::new(false),
+            handle: Mutex::new(Some(handle)),
+        })
+    }
+
+    /// Current counters.
+    pub fn metrics(&self) -> QueueMetrics {
+        let s = &self.shared;
+        QueueMetrics {
+            capacity: s.capacity,
+            depth: s.depth.load(Ordering::Relaxed),
+            high_water: s.high_water.load(Ordering::Relaxed),
+            enqueued: s.enqueued.load(Ordering::Relaxed),
+            written: s.written.load(Ordering::Relaxed),
+            rejected: s.rejected.load(Ordering::Relaxed),
+            failed: s.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Block until the inner sink has returned for every entry accepted so
+    /// far. Fails with [`AuditError::Closed`] if the inner sink has failed
+    /// or the logger is shut down.
+    pub fn flush(&self) -> Result<(), AuditError> {
+        if *self
+            .closed
+            .read()
+            .expect("queued audit logger lock poisoned")
+        {
+            return Err(closed());
+        }
+        self.request(Msg::Flush)
+    }
+
+    /// Stop accepting entries, drain every entry already accepted into the
+    /// inner sink, and stop the thread. Later calls to `record()` fail with
+    /// [`AuditError::Closed`]; calling this twice is a no-op.
+    ///
+    /// Returns the inner sink's failure, if it had one.
+    pub fn shutdown(&self) -> Result<(), AuditError> {
+        let Some(handle) = self
+            .handle
+            .lock()
+            .expect("queued audit logger mutex poisoned")
+            .take()
+        else {
+            return Ok(());
+        };
+        *self
+            .closed
+            .write()
+            .expect("queued audit logger lock poisoned") = true;
+        let result = self.request(Msg::Shutdown);
+        let _ = handle.join();
+        result
+    }
+
+    /// Send a control message, waiting for room in the queue if need be,
+    /// and wait for the answer.
+    fn request(&self, msg: impl FnOnce(Ack) -> Msg) -> Result<(), AuditError> {
+        let (ack, done) = mpsc::sync_channel(1);
+        self.tx.send(msg(ack)).map_err(|_| closed())?;
+        done.recv().map_err(|_| closed())?
+    }
+}
+
+impl AuditLogger for QueuedAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let closed_guard = self
+            .closed
+            .read()
+            .expect("queued audit logger lock poisoned");
+        if *closed_guard {
+            return Err(closed());
+        }
+        self.shared.status()?;
+        let s = &self.shared;
+        let depth = s.depth.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.tx.try_send(Msg::Record(Box::new(entry))) {
+            Ok(()) => {
+                s.enqueued.fetch_add(1, Ordering::Relaxed);
+                s.high_water.fetch_max(depth, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => {
+                let depth = s.depth.fetch_sub(1, Ordering::Relaxed) - 1;
+                s.rejected.fetch_add(1, Ordering::Relaxed);
+                Err(AuditError::Backpressure {
+                    depth,
+                    capacity: s.capacity,
+                })
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                s.depth.fetch_sub(1, Ordering::Relaxed);
+                Err(closed())
+            }
+        }
+    }
+}
+
+impl Drop for QueuedAuditLogger {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+fn closed() -> AuditError {
+    AuditError::Closed("audit queue has shut down".into())
+}
+
+fn inner_failed(reason: &str) -> AuditError {
+    AuditError::Closed(format!("queued audit sink failed: {reason}"))
+}
+
+/// The background thread: hand each entry to `inner` in order until asked
+/// to shut down.
+fn drain(inner: impl AuditLogger, shared: &Shared, rx: Receiver<Msg>) {
+    for msg in rx {
+        match msg {
+            Msg::Record(entry) => {
+                shared.depth.fetch_sub(1, Ordering::Relaxed);
+                let failed = shared.failure().is_some();
+                if failed {
+                    shared.failed.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    match inner.record(*entry) {
+                        Ok(()) => {
+                            shared.written.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Err(e) => {
+                            ::tracing::error!(
+                                target: "synthonyx::audit",
+                                error = %e,
+                                "queued audit sink failed; refusing further entries",
+                            );
+                            shared.failed.fetch_add(1, Ordering::Relaxed);
+                            *shared.failure() = Some(e.to_string());
+                        }
+                    }
+                }
+            }
+            Msg::Flush(ack) => {
+                let _ = ack.send(shared.status());
+            }
+            Msg::Shutdown(ack) => {
+                let _ = ack.send(shared.status());
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use synthonyx_kit_core::{CorrelationId, OriginKind, OriginSnapshot, Outcome, UnixNanos};
+
+    fn entry() -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(0),
+            correlation_id: CorrelationId([0; 16]),
+            module: Cow::Borrowed("test"),
+            action: Cow::Borrowed("queued"),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    struct Broken;
+
+    impl AuditLogger for Broken {
+        fn record(&self, _: AuditEntry) -> Result<(), AuditError> {
+            Err(AuditError::Backend("disk full".into()))
+        }
+    }
+
+    #[test]
+    fn failure_closes_the_queue() {
+        let logger = QueuedAuditLogger::new(Broken, 4).unwrap();
+        logger.record(entry()).unwrap();
+        let err = logger.flush().unwrap_err();
+        assert!(matches!(err, AuditError::Closed(ref m) if m.contains("disk full")));
+        assert!(matches!(logger.record(entry()), Err(AuditError::Closed(_))));
+        assert_eq!(logger.metrics().failed, 1);
+        assert!(logger.shutdown().is_err());
+    }
+}
@@ -0,0 +1,711 @@
+//! Streaming audit log reader, entry filters, and the sidecar correlation
+//! index.
+//!
+//! [`AuditReader`] walks one log file (or every segment of a segment
+//! directory, in order) and yields the entries matching an [`AuditQuery`].
+//! The chain is verified as it streams: a broken link is yielded as an
+//! error and ends the iteration, so a reader never silently returns entries
+//! from a tampered log.
+//!
+//! [`CorrelationIndex`] is an optional sidecar (`<log>.idx`) mapping each
+//! correlation id to the byte offsets of its entries, so lookups for one
+//! operation don't scan the whole file. The index is a cache: it can be
+//! rebuilt from the log at any time and is never trusted for integrity.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use synthonyx_kit_core::{AuditEntry, AuditError, CorrelationId, OriginKind, Outcome, UnixNanos};
+
+use crate::chain::{self, GENESIS};
+use crate::segment::{SegmentManifest, segment_file_name};
+
+/// Filter over [`AuditEntry`] values. Every criterion left unset matches
+/// all entries; set criteria are combined with AND.
+///
+/// The time range is half-open: `since` is inclusive, `until` exclusive.
+#[derive(Clone, Debug, Default)]
+pub struct AuditQuery {
+    correlation_id: Option<CorrelationId>,
+    subject: Option<String>,
+    module: Option<String>,
+    action: Option<String>,
+    outcome: Option<Outcome>,
+    origin_kind: Option<OriginKind>,
+    since: Option<UnixNanos>,
+    until: Option<UnixNanos>,
+}
+
+impl AuditQuery {
+    /// A query matching every entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only entries carrying this correlation id.
+    pub fn correlation_id(mut self, id: CorrelationId) -> Self {
+        self.correlation_id = Some(id);
+        self
+    }
+
+    /// Only entries linked to this data subject.
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Only entries written by this module.
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        self.module = Some(module.into());
+        self
+    }
+
+    /// Only entries for this action.
+    pub fn action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Only entries with this outcome.
+    pub fn outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    /// Only entries whose origin has this kind.
+    pub fn origin_kind(mut self, kind: OriginKind) -> Self {
+        self.origin_kind = Some(kind);
+        self
+    }
+
+    /// Only entries at or after `ts`.
+    pub fn since(mut self, ts: UnixNanos) -> Self {
+        self.since = Some(ts);
+        self
+    }
+
+    /// Only entries strictly before `ts`.
+    pub fn until(mut self, ts: UnixNanos) -> Self {
+        self.until = Some(ts);
+        self
+    }
+
+    /// True if `entry` satisfies every criterion set on this query.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.correlation_id
+            .is_none_or(|id| entry.correlation_id == id)
+            && self
+                .subject
+                .as_deref()
+                .is_none_or(|s| entry.subject.as_deref() == Some(s))
+            && self.module.as_deref().is_none_or(|m| entry.module == m)
+            && self.action.as_deref().is_none_or(|a| entry.action == a)
+            && self.outcome.is_none_or(|o| entry.outcome == o)
+            && self.origin_kind.is_none_or(|k| entry.origin.kind == k)
+            && self.since.is_none_or(|ts| entry.timestamp >= ts)
+            && self.until.is_none_or(|ts| entry.timestamp < ts)
+    }
+}
+
+/// Streaming, chain-verifying reader over a stored audit log.
+///
+/// Iterates `Result<AuditEntry, AuditError>`. Non-matching entries are
+/// still decoded and chain-checked, only not yielded. After the first error
+/// the iterator is exhausted; [`Self::position`] then names the offending
+/// line.
+pub struct AuditReader {
+    files: VecDeque<PathBuf>,
+    current: Option<(PathBuf, BufReader<File>)>,
+    line_no: u64,
+    pending: String,
+    last_hash: [u8; 32],
+    query: AuditQuery,
+    follow: bool,
+    done: bool,
+    /// For a segment directory: the directory and the index of the segment
+    /// after the last file queued, so [`Self::follow`] can move on when the
+    /// writer rotates.
+    segments: Option<(PathBuf, u64)>,
+    /// Set once the segment after the current one has appeared, i.e. the
+    /// current segment is sealed and can be read to its end one last time.
+    sealed: bool,
+}
+
+impl AuditReader {
+    /// Read a single log file written by [`crate::FileAuditLogger`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        Self::from_files(VecDeque::from([path.as_ref().to_path_buf()]))
+    }
+
+    /// Read every segment of a directory written by
+    /// [`crate::SegmentedAuditLogger`], sealed segments first, then the
+    /// active one. The chain is followed across segment boundaries.
+    pub fn open_segments(dir: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let dir = dir.as_ref();
+        let manifest = SegmentManifest::load(dir)?;
+        let mut files: VecDeque<PathBuf> = manifest
+            .segments
+            .iter()
+            .map(|s| dir.join(&s.file))
+            .collect();
+        let active = manifest.active_path(dir);
+        let mut next = manifest.active_index();
+        if active.exists() {
+            files.push_back(active);
+            next += 1;
+        }
+        let mut reader = Self::from_files(files)?;
+        reader.segments = Some((dir.to_path_buf(), next));
+        Ok(reader)
+    }
+
+    fn from_files(files: VecDeque<PathBuf>) -> Result<Self, AuditError> {
+        let mut reader = Self {
+            files,
+            current: None,
+            line_no: 0,
+            pending: String::new(),
+            last_hash: GENESIS,
+            query: AuditQuery::new(),
+            follow: false,
+            done: false,
+            segments: None,
+            sealed: false,
+        };
+        reader.advance_file()?;
+        Ok(reader)
+    }
+
+    /// Only yield entries matching `query`.
+    pub fn with_query(mut self, query: AuditQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Keep the last file open at its end: once `next()` returns `None`,
+    /// later calls yield entries appended since. An unterminated final line
+    /// is held back until its newline arrives rather than reported as
+    /// malformed.
+    ///
+    /// A reader from [`Self::open_segments`] also follows rotation: once
+    /// the writer starts the next segment, the current one is read to its
+    /// end and the chain continues into the new file.
+    pub fn follow(mut self) -> Self {
+        self.follow = true;
+        self
+    }
+
+    /// The file and 1-based line number of the line read last, i.e. the
+    /// line behind the most recent entry or error.
+    pub fn position(&self) -> Option<(&Path, u64)> {
+        self.current
+            .as_ref()
+            .map(|(path, _)| (path.as_path(), self.line_no))
+    }
+
+    fn advance_file(&mut self) -> Result<(), AuditError> {
+        self.current = match self.files.pop_front() {
+            Some(path) => {
+                let file = BufReader::new(File::open(&path)?);
+                Some((path, file))
+            }
+            None => None,
+        };
+        self.line_no = 0;
+        Ok(())
+    }
+
+    /// When following a segment directory, check whether the writer has
+    /// moved on to the next segment. The first time it has, this only marks
+    /// the current segment sealed, so it is read to its end once more;
+    /// after that the next segment is queued. Returns false while the
+    /// current file is still the live one.
+    fn next_segment(&mut self) -> bool {
+        let Some((dir, next)) = self.segments.as_mut().filter(|_| self.follow) else {
+            return false;
+        };
+        let path = dir.join(segment_file_name(*next));
+        if !path.exists() {
+            return false;
+        }
+        if !self.sealed {
+            self.sealed = true;
+            return true;
+        }
+        self.sealed = false;
+        *next += 1;
+        self.files.push_back(path);
+        true
+    }
+
+    fn next_entry(&mut self) -> Result<Option<AuditEntry>, AuditError> {
+        loop {
+            let Some((_, file)) = self.current.as_mut() else {
+                return Ok(None);
+            };
+            let read = file.read_line(&mut self.pending)?;
+            if read == 0 || !self.pending.ends_with('\n') {
+                let last_file = self.files.is_empty();
+                if self.pending.is_empty() {
+                    if last_file {
+                        if !self.next_segment() {
+                            return Ok(None);
+                        }
+                        if self.files.is_empty() {
+                            // Sealed: read the rest of this segment first.
+                            continue;
+                        }
+                    }
+                    self.advance_file()?;
+                    continue;
+                }
+                if last_file && self.follow {
+                    return Ok(None);
+                }
+            }
+            let line = std::mem::take(&mut self.pending);
+            let line = line.strip_suffix('\n').unwrap_or(&line);
+            self.line_no += 1;
+            if line.is_empty() {
+                continue;
+            }
+            let entry = chain::decode(line)?;
+            if entry.prev_hash != self.last_hash {
+                return Err(AuditError::ChainBroken {
+                    expected: hex::encode(self.last_hash),
+                    actual: hex::encode(entry.prev_hash),
+                });
+            }
+            self.last_hash = chain::hash_line(line);
+            if self.query.matches(&entry) {
+                return Ok(Some(entry));
+            }
+        }
+    }
+}
+
+impl Iterator for AuditReader {
+    type Item = Result<AuditEntry, AuditError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_entry() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+            Ok(None) => {
+                self.done = !self.follow;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Path of the correlation index sidecar for the log at `log`
+/// (`<log>.idx`).
+pub fn index_path(log: impl AsRef<Path>) -> PathBuf {
+    let mut path = log.as_ref().as_os_str().to_owned();
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+/// Sidecar index from correlation id to the byte offsets of its entries.
+///
+/// Records how much of the log it covers (`log_len`, `entries`,
+/// `last_hash`), so [`Self::refresh`] only reads lines appended since the
+/// last build and rejects a log that was shortened or rewritten underneath
+/// it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorrelationIndex {
+    /// Format version; currently always `1`.
+    pub version: u32,
+    /// Byte length of the log covered by this index.
+    pub log_len: u64,
+    /// Number of entries covered by this index.
+    pub entries: u64,
+    /// Chain hash after the last covered entry.
+    #[serde(with = "chain::hex_hash")]
+    pub last_hash: [u8; 32],
+    /// Hex correlation id to the byte offsets of its entries, ascending.
+    pub offsets: BTreeMap<String, Vec<u64>>,
+}
+
+impl Default for CorrelationIndex {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            log_len: 0,
+            entries: 0,
+            last_hash: GENESIS,
+            offsets: BTreeMap::new(),
+        }
+    }
+}
+
+impl CorrelationIndex {
+    /// Build an index over the whole log at `log`, verifying the chain as
+    /// it goes.
+    pub fn build(log: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let mut index = Self::default();
+        index.refresh(log)?;
+        Ok(index)
+    }
+
+    /// Load the index sidecar for `log` and bring it up to date, or build
+    /// it from scratch if there is none. The refreshed index is written
+    /// back to the sidecar.
+    pub fn open(log: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let log = log.as_ref();
+        let sidecar = index_path(log);
+        let mut index = match fs::read(&sidecar) {
+            Ok(bytes) => serde_json::from_slice::<Self>(&bytes)
+                .map_err(|e| AuditError::Serialization(e.to_string()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e.into()),
+        };
+        if index.version != 1 {
+            return Err(AuditError::Serialization(format!(
+                "unsupported correlation index version {}",
+                index.version
+            )));
+        }
+        index.refresh(log)?;
+        index.store(&sidecar)?;
+        Ok(index)
+    }
+
+    /// Index the lines appended to `log` since this index was last
+    /// refreshed.
+    ///
+    /// Fails with [`AuditError::ChainBroken`] if the new lines don't chain
+    /// onto the covered prefix, and with [`AuditError::StaleIndex`] if the
+    /// log is shorter than the covered prefix.
+    pub fn refresh(&mut self, log: impl AsRef<Path>) -> Result<(), AuditError> {
+        let log = log.as_ref();
+        let mut file = File::open(log)?;
+        let len = file.metadata()?.len();
+        if len < self.log_len {
+            let summary = chain::verify_file(log, GENESIS)?;
+            return Err(AuditError::StaleIndex {
+                index_entries: self.entries,
+                index_bytes: self.log_len,
+                log_entries: summary.entries,
+                log_bytes: len,
+            });
+        }
+        file.seek(SeekFrom::Start(self.log_len))?;
+        let mut reader = BufReader::new(file);
+        let mut offset = self.log_len;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)? as u64;
+            if read == 0 || !line.ends_with('\n') {
+                // EOF, or a partially written line we'll pick up next time.
+                break;
+            }
+            let text = line.trim_end_matches('\n');
+            if !text.is_empty() {
+                let entry = chain::decode(text)?;
+                if entry.prev_hash != self.last_hash {
+                    return Err(AuditError::ChainBroken {
+                        expected: hex::encode(self.last_hash),
+                        actual: hex::encode(entry.prev_hash),
+                    });
+                }
+                self.last_hash = chain::hash_line(text);
+                self.entries += 1;
+                self.offsets
+                    .entry(entry.correlation_id.to_string())
+                    .or_default()
+                    .push(offset);
+            }
+            offset += read;
+        }
+        self.log_len = offset;
+        Ok(())
+    }
+
+    /// Write this index to `path` atomically and durably (temp file,
+    /// rename, directory fsync).
+    pub fn store(&self, path: impl AsRef<Path>) -> Result<(), AuditError> {
+        let bytes =
+            serde_json::to_vec(self).map_err(|e| AuditError::Serialization(e.to_string()))?;
+        crate::segment::replace_file(path.as_ref(), &bytes)
+    }
+
+    /// Byte offsets of the entries carrying `id`, ascending.
+    pub fn offsets(&self, id: CorrelationId) -> &[u64] {
+        self.offsets
+            .get(&id.to_string())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Read the entries carrying `id` from `log` by seeking to their
+    /// indexed offsets.
+    ///
+    /// This does not verify the chain; pair it with
+    /// [`crate::verify_chain`] (or an [`AuditReader`] pass) when the result
+    /// is used as evidence. An offset that no longer points at an entry
+    /// for `id` fails with [`AuditError::Serialization`].
+    pub fn lookup(
+        &self,
+        log: impl AsRef<Path>,
+        id: CorrelationId,
+    ) -> Result<Vec<AuditEntry>, AuditError> {
+        let mut reader = BufReader::new(File::open(log)?);
+        let mut out = Vec::new();
+        let mut line = String::new();
+        for &offset in self.offsets(id) {
+            reader.seek(SeekFrom::Start(offset))?;
+            line.clear();
+            reader.read_line(&mut line)?;
+            let entry = chain::decode(line.trim_end_matches('\n'))?;
+            if entry.correlation_id != id {
+                return Err(AuditError::Serialization(format!(
+                    "correlation index is stale at offset {offset}"
+                )));
+            }
+            out.push(entry);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileAuditLogger, RotationPolicy, SegmentedAuditLogger};
+    use std::borrow::Cow;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use synthonyx_kit_core::{AuditLogger, OriginSnapshot};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn tmp_path(name: &str) -> PathBuf {
+        let pid = std::process::id();
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("synthonyx-audit-reader-{pid}-{seq}-{name}"))
+    }
+
+    fn entry(cid: u8, action: &'static str, outcome: Outcome, ts: u128) -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(ts),
+            correlation_id: CorrelationId([cid; 16]),
+            module: Cow::Borrowed("accounts"),
+            action: Cow::Borrowed(action),
+            origin: OriginSnapshot {
+                kind: OriginKind::User,
+                principal: Some("u1".into()),
+                service: None,
+            },
+            outcome,
+            subject: (cid == 1).then(|| "subj-1".to_string()),
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    fn write_sample(path: &Path) {
+        let logger = FileAuditLogger::open(path).unwrap();
+        logger
+            .record(entry(1, "login", Outcome::Success, 10))
+            .unwrap();
+        logger
+            .record(entry(2, "login", Outcome::Denied, 20))
+            .unwrap();
+        logger
+            .record(entry(1, "update", Outcome::Success, 30))
+            .unwrap();
+        logger
+            .record(entry(3, "delete", Outcome::Error, 40))
+            .unwrap();
+    }
+
+    fn actions(reader: AuditReader) -> Vec<String> {
+        reader.map(|e| e.unwrap().action.into_owned()).collect()
+    }
+
+    #[test]
+    fn query_filters_combine() {
+        let path = tmp_path("query.jsonl");
+        write_sample(&path);
+        let all = AuditReader::open(&path).unwrap();
+        assert_eq!(actions(all), ["login", "login", "update", "delete"]);
+
+        let q = AuditQuery::new().correlation_id(CorrelationId([1; 16]));
+        let by_cid = AuditReader::open(&path).unwrap().with_query(q);
+        assert_eq!(actions(by_cid), ["login", "update"]);
+
+        let q = AuditQuery::new().action("login").outcome(Outcome::Denied);
+        let denied = AuditReader::open(&path).unwrap().with_query(q);
+        assert_eq!(actions(denied), ["login"]);
+
+        let q = AuditQuery::new().since(UnixNanos(20)).until(UnixNanos(40));
+        let window = AuditReader::open(&path).unwrap().with_query(q);
+        assert_eq!(actions(window), ["login", "update"]);
+
+        let q = AuditQuery::new()
+            .subject("subj-1")
+            .origin_kind(OriginKind::User);
+        let subject = AuditReader::open(&path).unwrap().with_query(q);
+        assert_eq!(actions(subject), ["login", "update"]);
+
+        let q = AuditQuery::new().origin_kind(OriginKind::System);
+        assert!(actions(AuditReader::open(&path).unwrap().with_query(q)).is_empty());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reader_stops_at_broken_chain() {
+        let path = tmp_path("tamper.jsonl");
+        write_sample(&path);
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replacen("\"update\"", "\"UPDATE\"", 1)).unwrap();
+
+        let mut reader = AuditReader::open(&path).unwrap();
+        let results: Vec<_> = reader.by_ref().collect();
+        assert_eq!(results.len(), 4);
+        assert!(results[..3].iter().all(Result::is_ok));
+        assert!(matches!(results[3], Err(AuditError::ChainBroken { .. })));
+        assert_eq!(reader.position(), Some((path.as_path(), 4)));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reader_follows_chain_across_segments() {
+        let dir = tmp_path("segments");
+        let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+        logger.record(entry(1, "a", Outcome::Success, 1)).unwrap();
+        logger.rotate().unwrap();
+        logger.record(entry(2, "b", Outcome::Success, 2)).unwrap();
+        drop(logger);
+
+        assert_eq!(
+            actions(AuditReader::open_segments(&dir).unwrap()),
+            ["a", "b"]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn follow_picks_up_appended_entries() {
+        let path = tmp_path("follow.jsonl");
+        write_sample(&path);
+        let mut reader = AuditReader::open(&path).unwrap().follow();
+        assert_eq!(reader.by_ref().count(), 4);
+
+        let line = fs::read_to_string(&path).unwrap();
+        let first = line.lines().next().unwrap();
+        let logger = FileAuditLogger::open(&path).unwrap();
+        logger
+            .record(entry(4, "logout", Outcome::Success, 50))
+            .unwrap();
+        // A torn write in progress is held back, not reported.
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&first.as_bytes()[..10])
+            .unwrap();
+
+        let next: Vec<_> = reader.by_ref().map(Result::unwrap).collect();
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].action, "logout");
+        assert!(reader.next().is_none());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn follow_continues_into_the_next_segment() {
+        let dir = tmp_path("follow-segments");
+        let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+        logger.record(entry(1, "a", Outcome::Success, 1)).unwrap();
+        logger.rotate().unwrap();
+        logger.record(entry(2, "b", Outcome::Success, 2)).unwrap();
+        let mut reader = AuditReader::open_segments(&dir).unwrap().follow();
+        assert_eq!(actions_so_far(&mut reader), ["a", "b"]);
+
+        // Written to the active segment, which is then sealed.
+        logger.record(entry(3, "c", Outcome::Success, 3)).unwrap();
+        logger.rotate().unwrap();
+        logger.record(entry(4, "d", Outcome::Success, 4)).unwrap();
+        assert_eq!(actions_so_far(&mut reader), ["c", "d"]);
+        assert!(reader.next().is_none());
+        drop(logger);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn actions_so_far(reader: &mut AuditReader) -> Vec<String> {
+        reader
+            .by_ref()
+            .map(|e| e.unwrap().action.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn correlation_index_refreshes_incrementally() {
+        let path = tmp_path("index.jsonl");
+        write_sample(&path);
+        let index = CorrelationIndex::open(&path).unwrap();
+        assert_eq!(index.entries, 4);
+        let found = index.lookup(&path, CorrelationId([1; 16])).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].action, "update");
+
+        let logger = FileAuditLogger::open(&path).unwrap();
+        logger
+            .record(entry(1, "logout", Outcome::Success, 50))
+            .unwrap();
+        drop(logger);
+
+        let index = CorrelationIndex::open(&path).unwrap();
+        assert_eq!(index.entries, 5);
+        assert_eq!(index.offsets(CorrelationId([1; 16])).len(), 3);
+        assert!(
+            index
+                .lookup(&path, CorrelationId([9; 16]))
+                .unwrap()
+                .is_empty()
+        );
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(index_path(&path));
+    }
+
+    #[test]
+    fn correlation_index_rejects_shortened_log() {
+        let path = tmp_path("short.jsonl");
+        write_sample(&path);
+        CorrelationIndex::open(&path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        let kept: Vec<&str> = contents.lines().take(2).collect();
+        fs::write(&path, format!("{}\n", kept.join("\n"))).unwrap();
+
+        let err = CorrelationIndex::open(&path).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                AuditError::StaleIndex {
+                    index_entries: 4,
+                    log_entries: 2,
+                    ..
+                }
+            ),
+            "got {err:?}"
+        );
+        assert!(err.to_string().contains("index stale"));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(index_path(&path));
+    }
+}
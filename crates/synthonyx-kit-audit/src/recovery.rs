@@ -0,0 +1,190 @@
+//! Recovery of a torn trailing write.
+//!
+//! Every sink in this crate writes an entry as one `line + '\n'` buffer, so
+//! a crash mid-write can only leave bytes *after* the final newline. Those
+//! bytes were never acknowledged to a caller. [`recover_torn_tail`] moves
+//! them to a quarantine sidecar, truncates the log back to the last
+//! complete line, and appends a chained marker entry recording what was
+//! removed. Everything before the tail must still verify: mid-file
+//! tampering is reported, never repaired.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditValue, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    TimeSource, UnixNanos,
+};
+
+use crate::chain::{self, ChainWriter, GENESIS};
+use crate::lock::WriteLock;
+
+/// Module name stamped on recovery marker entries.
+pub const RECOVERY_MODULE: &str = "synthonyx-kit-audit";
+
+/// Action name stamped on recovery marker entries.
+pub const RECOVERY_ACTION: &str = "recover_torn_tail";
+
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+/// Path of the quarantine sidecar for the log at `log`
+/// (`<log>.quarantine`).
+pub fn quarantine_path(log: impl AsRef<Path>) -> PathBuf {
+    let mut path = log.as_ref().as_os_str().to_owned();
+    path.push(".quarantine");
+    PathBuf::from(path)
+}
+
+/// One torn tail moved out of a log, as stored (one JSON object per line)
+/// in the quarantine sidecar.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuarantinedTail {
+    /// Byte offset in the log where the torn bytes started.
+    pub offset: u64,
+    /// When the tail was quarantined.
+    pub recovered_at: UnixNanos,
+    /// BLAKE3-32 of the torn bytes.
+    #[serde(with = "chain::hex_hash")]
+    pub hash: [u8; 32],
+    /// The torn bytes, hex-encoded (they need not be valid UTF-8).
+    pub bytes: String,
+}
+
+/// Outcome of a successful [`recover_torn_tail`].
+#[derive(Clone, Debug)]
+pub struct RecoveryReport {
+    /// Sidecar the torn bytes were appended to.
+    pub quarantine: PathBuf,
+    /// The quarantined tail.
+    pub tail: QuarantinedTail,
+    /// Number of complete entries kept, not counting the marker.
+    pub entries_kept: u64,
+}
+
+/// Quarantine a torn final line of the log at `path`, if there is one.
+///
+/// Returns `Ok(None)` without touching the log if it is missing or ends on
+/// a newline. Otherwise the complete prefix is verified first; if it is
+/// broken the log is left as it is and the verification error is returned.
+/// On success the tail is in the quarantine sidecar, the log is
+/// truncated and fsynced, and a marker entry ([`RECOVERY_ACTION`],
+/// [`Outcome::Error`]) timestamped from `time` is chained onto it. The
+/// marker carries the sequence number and writer id the log would have
+/// continued with, taken from the last kept entry, so
+/// [`crate::VerifyOptions::strict`] still accepts the recovered log.
+///
+/// Fails with [`AuditError::Locked`] while a logger has the log open: its
+/// final line may simply be mid-write.
+pub fn recover_torn_tail(
+    path: impl AsRef<Path>,
+    time: &impl TimeSource,
+) -> Result<Option<RecoveryReport>, AuditError> {
+    let path = path.as_ref();
+    let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let _lock = WriteLock::acquire(path)?;
+    let len = file.metadata()?.len();
+    let keep = complete_prefix_len(&mut file, len)?;
+    if keep == len {
+        return Ok(None);
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut writer_id = None;
+    let summary = chain::walk_reader(BufReader::new((&file).take(keep)), GENESIS, |entry, _| {
+        writer_id.clone_from(&entry.writer);
+        Ok(())
+    })?;
+
+    let mut torn = Vec::with_capacity((len - keep) as usize);
+    file.seek(SeekFrom::Start(keep))?;
+    file.read_to_end(&mut torn)?;
+    let tail = QuarantinedTail {
+        offset: keep,
+        recovered_at: time.now(),
+        hash: *blake3::hash(&torn).as_bytes(),
+        bytes: hex::encode(&torn),
+    };
+    let quarantine = quarantine_path(path);
+    let mut line =
+        serde_json::to_vec(&tail).map_err(|e| AuditError::Serialization(e.to_string()))?;
+    line.push(b'\n');
+    let mut sidecar = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&quarantine)?;
+    sidecar.write_all(&line)?;
+    sidecar.sync_all()?;
+
+    file.set_len(keep)?;
+    file.sync_all()?;
+    drop(file);
+
+    let mut writer = ChainWriter {
+        file: OpenOptions::new().append(true).open(path)?,
+        last_hash: summary.last_hash,
+        entries: summary.entries,
+        sequence: summary.entries,
+        writer_id,
+        failed: None,
+    };
+    writer.append(marker(&tail, torn.len() as u64))?;
+    writer.file.sync_all()?;
+
+    Ok(Some(RecoveryReport {
+        quarantine,
+        tail,
+        entries_kept: summary.entries,
+    }))
+}
+
+/// Length of the log up to and including its final newline.
+fn complete_prefix_len(file: &mut File, len: u64) -> Result<u64, AuditError> {
+    let mut end = len;
+    let mut buf = vec![0u8; TAIL_CHUNK as usize];
+    while end > 0 {
+        let start = end.saturating_sub(TAIL_CHUNK);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        if let Some(pos) = chunk.iter().rposition(|&b| b == b'\n') {
+            return Ok(start + pos as u64 + 1);
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+fn marker(tail: &QuarantinedTail, torn_bytes: u64) -> AuditEntry {
+    AuditEntry {
+        timestamp: tail.recovered_at,
+        correlation_id: CorrelationId::ZERO,
+        module: Cow::Borrowed(RECOVERY_MODULE),
+        action: Cow::Borrowed(RECOVERY_ACTION),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: Some(Cow::Borrowed(RECOVERY_MODULE)),
+        },
+        outcome: Outcome::Error,
+        subject: None,
+        fields: BTreeMap::from([
+            ("offset".to_string(), AuditValue::UInt(tail.offset)),
+            ("torn_bytes".to_string(), AuditValue::UInt(torn_bytes)),
+            (
+                "torn_hash".to_string(),
+                AuditValue::Hash(hex::encode(tail.hash)),
+            ),
+        ]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
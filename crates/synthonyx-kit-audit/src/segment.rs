@@ -0,0 +1,588 @@
+//! Segmented, rotating audit log.
+//!
+//! A segment directory holds numbered JSONL segment files plus a
+//! `manifest.json` describing every sealed segment. The chain runs across
+//! segment boundaries: the first entry of segment `n + 1` carries the final
+//! hash of segment `n` as its `prev_hash`, exactly as if both segments were
+//! one file.
+//!
+//! ```text
+//! audit/
+//!   manifest.json           sealed segments: start/end hash, counts, time range
+//!   segment-00000000.jsonl  sealed
+//!   segment-00000001.jsonl  sealed
+//!   segment-00000002.jsonl  active (not yet in the manifest)
+//! ```
+//!
+//! On open only the active segment is re-verified, starting from the
+//! manifest's final `end_hash`; [`verify_segments`] re-checks the whole
+//! directory when a full audit is required.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger, UnixNanos};
+
+use crate::chain::{self, ChainWriter};
+use crate::lock::WriteLock;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+
+/// When to seal the active segment and start a new one.
+///
+/// Both limits are optional; the default never rotates. Age is measured
+/// on entry timestamps (from `Config::Time`), not on the wall clock, so
+/// rotation is reproducible under `MockClock`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RotationPolicy {
+    /// Seal the active segment once it holds at least this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Seal the active segment once an incoming entry's timestamp is at
+    /// least this many nanoseconds after the segment's first entry.
+    pub max_age_nanos: Option<u128>,
+}
+
+impl RotationPolicy {
+    fn should_rotate(&self, active: &Active, next: UnixNanos) -> bool {
+        if active.writer.entries == 0 {
+            return false;
+        }
+        let too_big = self.max_bytes.is_some_and(|max| active.bytes >= max);
+        let too_old = match (self.max_age_nanos, active.first_timestamp) {
+            (Some(max), Some(first)) => next.0.saturating_sub(first.0) >= max,
+            _ => false,
+        };
+        too_big || too_old
+    }
+}
+
+/// Manifest entry describing one sealed segment.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SegmentInfo {
+    /// Zero-based segment index.
+    pub index: u64,
+    /// File name of the segment, relative to the segment directory.
+    pub file: String,
+    /// Number of entries in the segment.
+    pub entries: u64,
+    /// `prev_hash` of the segment's first entry (the previous segment's
+    /// `end_hash`, or all zeros for segment 0).
+    #[serde(with = "chain::hex_hash")]
+    pub start_hash: [u8; 32],
+    /// BLAKE3 hash of the segment's final entry.
+    #[serde(with = "chain::hex_hash")]
+    pub end_hash: [u8; 32],
+    /// Timestamp of the first entry.
+    pub first_timestamp: Option<UnixNanos>,
+    /// Timestamp of the last entry.
+    pub last_timestamp: Option<UnixNanos>,
+}
+
+/// The segment manifest: every sealed segment, in chain order.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SegmentManifest {
+    /// Manifest format version.
+    pub version: u32,
+    /// Sealed segments, ordered by index.
+    pub segments: Vec<SegmentInfo>,
+}
+
+impl Default for SegmentManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            segments: Vec::new(),
+        }
+    }
+}
+
+impl SegmentManifest {
+    /// Load the manifest from `dir`, or an empty manifest if none exists.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let path = dir.as_ref().join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let bytes = fs::read(&path)?;
+        let manifest: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| AuditError::Manifest(format!("{}: {e}", path.display())))?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(AuditError::Manifest(format!(
+                "unsupported manifest version {}",
+                manifest.version
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// The trusted chain head after the last sealed segment (all zeros if
+    /// nothing has been sealed yet).
+    pub fn head(&self) -> [u8; 32] {
+        self.segments.last().map_or(chain::GENESIS, |s| s.end_hash)
+    }
+
+    /// Index of the active (unsealed) segment.
+    pub fn active_index(&self) -> u64 {
+        self.segments.last().map_or(0, |s| s.index + 1)
+    }
+
+    /// Path of the active segment within `dir`.
+    pub fn active_path(&self, dir: impl AsRef<Path>) -> PathBuf {
+        dir.as_ref().join(segment_file_name(self.active_index()))
+    }
+
+    /// Atomically replace the manifest in `dir` (write to a temporary file,
+    /// fsync, rename).
+    fn store(&self, dir: &Path) -> Result<(), AuditError> {
+        store_json(&dir.join(MANIFEST_FILE), self)
+    }
+}
+
+/// Atomically replace the JSON file at `path` with `value`, pretty-printed;
+/// see [`replace_file`].
+pub(crate) fn store_json(path: &Path, value: &impl serde::Serialize) -> Result<(), AuditError> {
+    let bytes =
+        serde_json::to_vec_pretty(value).map_err(|e| AuditError::Serialization(e.to_string()))?;
+    replace_file(path, &bytes)
+}
+
+/// Atomically and durably replace the file at `path` with `bytes`: write
+/// to a temporary file, fsync, rename, then fsync the directory so the
+/// rename itself survives a crash.
+pub(crate) fn replace_file(path: &Path, bytes: &[u8]) -> Result<(), AuditError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut f = File::create(&tmp)?;
+    f.write_all(bytes)?;
+    f.sync_all()?;
+    fs::rename(&tmp, path)?;
+    sync_parent(path)?;
+    Ok(())
+}
+
+/// Fsync the directory holding `path`. Directories cannot be opened for
+/// syncing outside Unix, where this does nothing.
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let dir = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        File::open(dir)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+pub(crate) fn segment_file_name(index: u64) -> String {
+    format!("segment-{index:08}.jsonl")
+}
+
+/// Reject directories containing segment files past the active index, which
+/// means the manifest was rolled back or segments were copied in.
+pub(crate) fn check_no_stray_segments(dir: &Path, active: u64) -> Result<(), AuditError> {
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let Some(index) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("segment-"))
+            .and_then(|n| n.strip_suffix(".jsonl"))
+            .and_then(|n| n.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        if index > active {
+            return Err(AuditError::Manifest(format!(
+                "segment {index} is newer than the active segment {active}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+struct Active {
+    index: u64,
+    writer: ChainWriter,
+    start_hash: [u8; 32],
+    bytes: u64,
+    first_timestamp: Option<UnixNanos>,
+    last_timestamp: Option<UnixNanos>,
+}
+
+struct State {
+    dir: PathBuf,
+    policy: RotationPolicy,
+    manifest: SegmentManifest,
+    active: Active,
+    _lock: WriteLock,
+}
+
+impl State {
+    /// Seal the active segment into the manifest and open the next one.
+    fn rotate(&mut self) -> Result<(), AuditError> {
+        self.active.writer.file.sync_all()?;
+        let info = SegmentInfo {
+            index: self.active.index,
+            file: segment_file_name(self.active.index),
+            entries: self.active.writer.entries,
+            start_hash: self.active.start_hash,
+            end_hash: self.active.writer.last_hash,
+            first_timestamp: self.active.first_timestamp,
+            last_timestamp: self.active.last_timestamp,
+        };
+        let mut manifest = self.manifest.clone();
+        manifest.segments.push(info);
+        manifest.store(&self.dir)?;
+        self.manifest = manifest;
+        let writer_id = self.active.writer.writer_id.take();
+        self.active = open_active(&self.dir, &self.manifest)?;
+        self.active.writer.writer_id = writer_id;
+        Ok(())
+    }
+}
+
+fn open_active(dir: &Path, manifest: &SegmentManifest) -> Result<Active, AuditError> {
+    let index = manifest.active_index();
+    let path = manifest.active_path(dir);
+    let start_hash = manifest.head();
+    let summary = if path.exists() {
+        chain::verify_file(&path, start_hash)?
+    } else {
+        chain::ChainSummary::empty(start_hash)
+    };
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let bytes = file.metadata()?.len();
+    let sealed: u64 = manifest.segments.iter().map(|s| s.entries).sum();
+    Ok(Active {
+        index,
+        writer: ChainWriter {
+            file,
+            last_hash: summary.last_hash,
+            entries: summary.entries,
+            sequence: sealed + summary.entries,
+            writer_id: None,
+            failed: None,
+        },
+        start_hash,
+        bytes,
+        first_timestamp: summary.first_timestamp,
+        last_timestamp: summary.last_timestamp,
+    })
+}
+
+/// BLAKE3-chained audit log split across rotating segment files.
+///
+/// Entries are encoded and chained exactly as by [`crate::FileAuditLogger`];
+/// the only difference is that the active segment is sealed and a new one
+/// started whenever the [`RotationPolicy`] says so. Sealing fsyncs the
+/// segment and records its start/end hashes, entry count and time range in
+/// the [`SegmentManifest`].
+///
+/// Reopening verifies only the active segment, starting from the manifest's
+/// trusted head, so restart cost is bounded by the segment size rather than
+/// the age of the log.
+pub struct SegmentedAuditLogger {
+    inner: Mutex<State>,
+}
+
+impl SegmentedAuditLogger {
+    /// Open or create a segment directory at `dir`.
+    ///
+    /// Fails with [`AuditError::ChainBroken`] if the active segment does not
+    /// chain from the manifest head, with [`AuditError::Manifest`] if the
+    /// manifest is malformed or segments exist beyond the active one, and
+    /// with [`AuditError::Locked`] if another logger has the directory open;
+    /// the lock is taken on the manifest's [`crate::lock_path`] sidecar.
+    pub fn open(dir: impl AsRef<Path>, policy: RotationPolicy) -> Result<Self, AuditError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let lock = WriteLock::acquire(&dir.join(MANIFEST_FILE))?;
+        let manifest = SegmentManifest::load(&dir)?;
+        check_no_stray_segments(&dir, manifest.active_index())?;
+        let active = open_active(&dir, &manifest)?;
+        Ok(Self {
+            inner: Mutex::new(State {
+                dir,
+                policy,
+                manifest,
+                active,
+                _lock: lock,
+            }),
+        })
+    }
+
+    /// Stamp every entry written from now on with `writer_id`, so
+    /// [`crate::VerifyOptions::single_writer`] can detect entries appended
+    /// by another process.
+    pub fn with_writer_id(self, writer_id: impl Into<String>) -> Self {
+        let mut state = self
+            .inner
+            .into_inner()
+            .expect("segmented audit logger mutex poisoned");
+        state.active.writer.writer_id = Some(writer_id.into());
+        Self {
+            inner: Mutex::new(state),
+        }
+    }
+
+    /// Snapshot of the current manifest (sealed segments only).
+    pub fn manifest(&self) -> SegmentManifest {
+        self.inner
+            .lock()
+            .expect("segmented audit logger mutex poisoned")
+            .manifest
+            .clone()
+    }
+
+    /// Seal the active segment now, regardless of the rotation policy.
+    ///
+    /// A no-op if the active segment is empty.
+    pub fn rotate(&self) -> Result<(), AuditError> {
+        let mut state = self
+            .inner
+            .lock()
+            .expect("segmented audit logger mutex poisoned");
+        if state.active.writer.entries == 0 {
+            return Ok(());
+        }
+        state.rotate()
+    }
+}
+
+impl AuditLogger for SegmentedAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let mut state = self
+            .inner
+            .lock()
+            .expect("segmented audit logger mutex poisoned");
+        if state.policy.should_rotate(&state.active, entry.timestamp) {
+            state.rotate()?;
+        }
+        let timestamp = entry.timestamp;
+        let written = state.active.writer.append(entry)?;
+        let active = &mut state.active;
+        active.bytes += written;
+        active.first_timestamp.get_or_insert(timestamp);
+        active.last_timestamp = Some(timestamp);
+        Ok(())
+    }
+}
+
+/// Verify every segment in `dir` end-to-end against the manifest.
+///
+/// Checks that each sealed segment chains from its predecessor, that its
+/// recomputed final hash and entry count match the manifest, and that the
+/// active segment chains from the last sealed one. Returns the hash of the
+/// final entry across all segments.
+pub fn verify_segments(dir: impl AsRef<Path>) -> Result<[u8; 32], AuditError> {
+    let dir = dir.as_ref();
+    let manifest = SegmentManifest::load(dir)?;
+    check_no_stray_segments(dir, manifest.active_index())?;
+    let mut running = chain::GENESIS;
+    for (position, info) in manifest.segments.iter().enumerate() {
+        if info.index != position as u64 {
+            return Err(AuditError::Manifest(format!(
+                "expected segment {position}, manifest lists {}",
+                info.index
+            )));
+        }
+        if info.start_hash != running {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(running),
+                actual: hex::encode(info.start_hash),
+            });
+        }
+        let summary = chain::verify_file(&dir.join(&info.file), running)?;
+        check_sealed(info, &summary)?;
+        running = info.end_hash;
+    }
+    verify_active(dir, &manifest)
+}
+
+/// Check a re-verified sealed segment against its manifest record.
+pub(crate) fn check_sealed(
+    info: &SegmentInfo,
+    summary: &chain::ChainSummary,
+) -> Result<(), AuditError> {
+    if summary.last_hash != info.end_hash {
+        return Err(AuditError::ChainBroken {
+            expected: hex::encode(info.end_hash),
+            actual: hex::encode(summary.last_hash),
+        });
+    }
+    if summary.entries != info.entries {
+        return Err(AuditError::Manifest(format!(
+            "segment {} holds {} entries, manifest records {}",
+            info.index, summary.entries, info.entries
+        )));
+    }
+    Ok(())
+}
+
+/// Verify only the active segment of `dir`, trusting the manifest head as
+/// its starting hash. Returns the hash of the final entry.
+///
+/// This is the cheap check performed on every [`SegmentedAuditLogger::open`];
+/// pair it with a periodic [`verify_segments`] (or signed manifest heads)
+/// for full coverage.
+pub fn verify_latest_segment(dir: impl AsRef<Path>) -> Result<[u8; 32], AuditError> {
+    let dir = dir.as_ref();
+    let manifest = SegmentManifest::load(dir)?;
+    verify_active(dir, &manifest)
+}
+
+pub(crate) fn verify_active(
+    dir: &Path,
+    manifest: &SegmentManifest,
+) -> Result<[u8; 32], AuditError> {
+    let path = manifest.active_path(dir);
+    if !path.exists() {
+        return Ok(manifest.head());
+    }
+    Ok(chain::verify_file(&path, manifest.head())?.last_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use synthonyx_kit_core::{CorrelationId, OriginKind, OriginSnapshot, Outcome};
+
+    use super::*;
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let pid = std::process::id();
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("synthonyx-audit-seg-{name}-{pid}-{seq}"))
+    }
+
+    fn entry_at(action: &'static str, nanos: u128) -> AuditEntry {
+        AuditEntry {
+            timestamp: UnixNanos(nanos),
+            correlation_id: CorrelationId([1u8; 16]),
+            module: Cow::Borrowed("test"),
+            action: Cow::Borrowed(action),
+            origin: OriginSnapshot {
+                kind: OriginKind::System,
+                principal: None,
+                service: None,
+            },
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    #[test]
+    fn rotates_by_size_and_chains_across_segments() {
+        let dir = tmp_dir("size");
+        let policy = RotationPolicy {
+            max_bytes: Some(1),
+            max_age_nanos: None,
+        };
+        let logger = SegmentedAuditLogger::open(&dir, policy).unwrap();
+        for i in 0..4 {
+            logger.record(entry_at("act", i)).unwrap();
+        }
+        let manifest = logger.manifest();
+        drop(logger);
+
+        assert_eq!(manifest.segments.len(), 3);
+        for pair in manifest.segments.windows(2) {
+            assert_eq!(pair[0].end_hash, pair[1].start_hash);
+        }
+        let head = verify_segments(&dir).unwrap();
+        assert_eq!(verify_latest_segment(&dir).unwrap(), head);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotates_by_entry_age() {
+        let dir = tmp_dir("age");
+        let policy = RotationPolicy {
+            max_bytes: None,
+            max_age_nanos: Some(100),
+        };
+        let logger = SegmentedAuditLogger::open(&dir, policy).unwrap();
+        logger.record(entry_at("a", 0)).unwrap();
+        logger.record(entry_at("b", 50)).unwrap();
+        logger.record(entry_at("c", 100)).unwrap();
+        let manifest = logger.manifest();
+        assert_eq!(manifest.segments.len(), 1);
+        assert_eq!(manifest.segments[0].entries, 2);
+        assert_eq!(manifest.segments[0].first_timestamp, Some(UnixNanos(0)));
+        assert_eq!(manifest.segments[0].last_timestamp, Some(UnixNanos(50)));
+        drop(logger);
+        verify_segments(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reopen_continues_active_segment() {
+        let dir = tmp_dir("reopen");
+        let policy = RotationPolicy::default();
+        let logger = SegmentedAuditLogger::open(&dir, policy).unwrap();
+        logger.record(entry_at("a", 0)).unwrap();
+        logger.rotate().unwrap();
+        logger.record(entry_at("b", 1)).unwrap();
+        drop(logger);
+
+        let logger = SegmentedAuditLogger::open(&dir, policy).unwrap();
+        logger.record(entry_at("c", 2)).unwrap();
+        assert_eq!(logger.manifest().segments.len(), 1);
+        drop(logger);
+        verify_segments(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn manifest_end_hash_mismatch_is_detected() {
+        let dir = tmp_dir("tamper");
+        let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+        logger.record(entry_at("a", 0)).unwrap();
+        logger.record(entry_at("b", 1)).unwrap();
+        logger.rotate().unwrap();
+        drop(logger);
+
+        // Rewrite the sealed segment's last entry: nothing inside the
+        // segment binds it, but the manifest's end hash does.
+        let path = dir.join(segment_file_name(0));
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, contents.replacen("\"b\"", "\"B\"", 1)).unwrap();
+
+        let err = verify_segments(&dir).unwrap_err();
+        assert!(
+            matches!(err, AuditError::ChainBroken { .. }),
+            "expected ChainBroken, got {err:?}"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stray_segment_beyond_active_is_rejected() {
+        let dir = tmp_dir("stray");
+        let logger = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+        logger.record(entry_at("a", 0)).unwrap();
+        drop(logger);
+        fs::write(dir.join(segment_file_name(5)), b"").unwrap();
+
+        let err = SegmentedAuditLogger::open(&dir, RotationPolicy::default())
+            .err()
+            .expect("stray segment must be rejected");
+        assert!(matches!(err, AuditError::Manifest(_)), "got {err:?}");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,230 @@
+//! Crypto-shredding of data-subject identifiers (GDPR Art. 17).
+//!
+//! A chained log cannot delete or rewrite an entry without breaking
+//! verification. [`ShreddingAuditLogger`] therefore never writes a subject
+//! in clear: `subject` (and any fields named with
+//! [`ShreddingAuditLogger::shred_field`]) are replaced by a BLAKE3 keyed
+//! hash under a random per-subject key kept in a [`SubjectKeyStore`]
+//! outside the log. While the key exists the same subject always maps to
+//! the same token, so its entries can still be found. Erasing the key makes
+//! the tokens unlinkable to the person; the log bytes, and so the chain,
+//! are unchanged.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, OriginSnapshot, OriginTrait, Outcome, Secret,
+    TimeSource,
+};
+
+/// Module name stamped on erasure entries.
+pub const ERASURE_MODULE: &str = "synthonyx-kit-audit";
+
+/// Action name stamped on erasure entries.
+pub const ERASURE_ACTION: &str = "erase_subject_key";
+
+const FIELD_CONTEXT: &[u8] = b"synthonyx-audit-field/v1";
+
+type KeyMap = HashMap<String, Secret<[u8; 32]>>;
+
+/// Holds one secret key per data subject, outside the audit log.
+///
+/// Production stores should keep keys in a KMS or a database that supports
+/// real deletion (including from backups). Failures are reported as
+/// [`AuditError::Backend`].
+pub trait SubjectKeyStore: Send + Sync + 'static {
+    /// The key for `subject`, generating and storing a fresh random key if
+    /// there is none.
+    fn get_or_create(&self, subject: &str) -> Result<Secret<[u8; 32]>, AuditError>;
+
+    /// The key for `subject`, or `None` if it was never created or has been
+    /// erased.
+    fn get(&self, subject: &str) -> Result<Option<Secret<[u8; 32]>>, AuditError>;
+
+    /// Destroy the key for `subject`. Returns whether a key existed.
+    fn erase(&self, subject: &str) -> Result<bool, AuditError>;
+}
+
+/// In-process [`SubjectKeyStore`] for tests and single-node deployments
+/// whose keys need not survive a restart. Erased keys are zeroized.
+#[derive(Default)]
+pub struct MemorySubjectKeyStore {
+    keys: Mutex<KeyMap>,
+}
+
+impl MemorySubjectKeyStore {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn keys(&self) -> Result<MutexGuard<'_, KeyMap>, AuditError> {
+        self.keys
+            .lock()
+            .map_err(|_| AuditError::Backend("subject key store mutex poisoned".into()))
+    }
+}
+
+impl SubjectKeyStore for MemorySubjectKeyStore {
+    fn get_or_create(&self, subject: &str) -> Result<Secret<[u8; 32]>, AuditError> {
+        let mut keys = self.keys()?;
+        if let Some(key) = keys.get(subject) {
+            return Ok(Secret::new(*key.expose()));
+        }
+        let mut key = [0u8; 32];
+        getrandom::getrandom(&mut key)
+            .map_err(|e| AuditError::Backend(format!("subject key generation: {e}")))?;
+        keys.insert(subject.to_string(), Secret::new(key));
+        Ok(Secret::new(key))
+    }
+
+    fn get(&self, subject: &str) -> Result<Option<Secret<[u8; 32]>>, AuditError> {
+        Ok(self.keys()?.get(subject).map(|k| Secret::new(*k.expose())))
+    }
+
+    fn erase(&self, subject: &str) -> Result<bool, AuditError> {
+        Ok(self.keys()?.remove(subject).is_some())
+    }
+}
+
+/// The token written in place of `subject` under `key`: hex BLAKE3 keyed
+/// hash of the subject.
+pub fn subject_token(key: &Secret<[u8; 32]>, subject: &str) -> String {
+    blake3::keyed_hash(key.expose(), subject.as_bytes())
+        .to_hex()
+        .to_string()
+}
+
+/// The keyed hash written in place of a shredded field value. The field
+/// name and value type are bound in, so equal values under different names
+/// give different hashes.
+fn field_token(key: &Secret<[u8; 32]>, name: &str, value: &AuditValue) -> String {
+    let (tag, text) = match value {
+        AuditValue::Str(s) => ("str", s.clone()),
+        AuditValue::Hash(h) => ("hash", h.clone()),
+        AuditValue::Int(i) => ("int", i.to_string()),
+        AuditValue::UInt(u) => ("uint", u.to_string()),
+        AuditValue::Bool(b) => ("bool", b.to_string()),
+    };
+    let mut hasher = blake3::Hasher::new_keyed(key.expose());
+    for part in [
+        FIELD_CONTEXT,
+        name.as_bytes(),
+        tag.as_bytes(),
+        text.as_bytes(),
+    ] {
+        hasher.update(&(part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Replaces subject identifiers with per-subject keyed tokens before
+/// passing entries to an inner logger.
+///
+/// Entries without a subject pass through unchanged, including any fields
+/// named for shredding: there is no subject key to shred them under.
+///
+/// ```
+/// use synthonyx_kit_audit::{MemorySubjectKeyStore, ShreddingAuditLogger, TracingAuditLogger};
+///
+/// let audit = ShreddingAuditLogger::new(TracingAuditLogger, MemorySubjectKeyStore::new())
+///     .shred_field("email_hash");
+/// ```
+pub struct ShreddingAuditLogger<L, S> {
+    inner: L,
+    store: S,
+    fields: BTreeSet<String>,
+}
+
+impl<L: AuditLogger, S: SubjectKeyStore> ShreddingAuditLogger<L, S> {
+    /// Shred subjects of entries written to `inner`, with keys from
+    /// `store`.
+    pub fn new(inner: L, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            fields: BTreeSet::new(),
+        }
+    }
+
+    /// Also replace the field `name`, when present on an entry with a
+    /// subject, by a keyed [`AuditValue::Hash`].
+    pub fn shred_field(mut self, name: impl Into<String>) -> Self {
+        self.fields.insert(name.into());
+        self
+    }
+
+    /// The wrapped logger.
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    /// The key store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The token `subject` is logged as, for searching the log (e.g. with
+    /// `AuditQuery::subject`). `None` if the subject has no key, either
+    /// because nothing was logged for it or because it was erased.
+    pub fn subject_token(&self, subject: &str) -> Result<Option<String>, AuditError> {
+        Ok(self
+            .store
+            .get(subject)?
+            .map(|key| subject_token(&key, subject)))
+    }
+
+    /// Erase `subject`'s key and record the erasure.
+    ///
+    /// The erasure entry ([`ERASURE_ACTION`]) carries the subject's token,
+    /// so it can be tied to the now-unlinkable entries, and a `key_erased`
+    /// field that is `false` if no key existed. It is attributed to
+    /// `origin`, the party that requested the erasure, and timestamped from
+    /// `time`. The key is destroyed before the entry is written; if writing
+    /// fails the error is returned and the erasure still stands.
+    pub fn erase_subject<O>(
+        &self,
+        subject: &str,
+        origin: &O,
+        time: &impl TimeSource,
+    ) -> Result<bool, AuditError>
+    where
+        O: OriginTrait,
+        O::Principal: core::fmt::Display,
+    {
+        let token = self.subject_token(subject)?;
+        let erased = self.store.erase(subject)?;
+        let entry = AuditEntry {
+            timestamp: time.now(),
+            correlation_id: origin.correlation_id(),
+            module: ERASURE_MODULE.into(),
+            action: ERASURE_ACTION.into(),
+            origin: OriginSnapshot::of(origin),
+            outcome: Outcome::Success,
+            subject: token,
+            fields: [("key_erased".to_string(), AuditValue::Bool(erased))].into(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        };
+        self.inner.record(entry)?;
+        Ok(erased)
+    }
+}
+
+impl<L: AuditLogger, S: SubjectKeyStore> AuditLogger for ShreddingAuditLogger<L, S> {
+    fn record(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+        if let Some(subject) = entry.subject.take() {
+            let key = self.store.get_or_create(&subject)?;
+            for (name, value) in entry.fields.iter_mut() {
+                if self.fields.contains(name) {
+                    *value = AuditValue::Hash(field_token(&key, name, value));
+                }
+            }
+            entry.subject = Some(subject_token(&key, &subject));
+        }
+        self.inner.record(entry)
+    }
+}
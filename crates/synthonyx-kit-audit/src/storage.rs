@@ -0,0 +1,260 @@
+//! Audit sink over a [`synthonyx_kit_storage::Backend`].
+//!
+//! Entries are stored as the same JSON lines [`crate::FileAuditLogger`]
+//! writes, chained the same way, under sequential keys:
+//!
+//! - `prefix ++ b"e" ++ seq.to_be_bytes()` — the entry with sequence
+//!   number `seq` (from 0);
+//! - `prefix ++ b"h"` — a [`StorageHead`] recording the entry count and
+//!   chain head after the last completed append.
+//!
+//! The head lets open and verification detect entries deleted from the
+//! end of the chain, which a plain walk cannot see.
+
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use serde::{Deserialize, Serialize};
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger};
+use synthonyx_kit_storage::{Backend, StorageError, StorageKey};
+use tokio::runtime::Handle;
+
+use crate::chain::{self, ChainSummary, GENESIS};
+
+/// Chain head record stored at `prefix ++ b"h"`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StorageHead {
+    /// Number of entries appended.
+    pub entries: u64,
+    /// Hash of the last entry (all zeros when empty).
+    #[serde(with = "chain::hex_hash")]
+    pub head_hash: [u8; 32],
+}
+
+/// One append for the I/O thread: the entry line and the head record that
+/// follows it.
+struct Append {
+    key: StorageKey,
+    line: String,
+    head: Vec<u8>,
+    done: SyncSender<Result<(), StorageError>>,
+}
+
+/// BLAKE3-chained audit logger storing entries in a [`Backend`].
+///
+/// [`AuditLogger::record`] is synchronous, while backend clients are
+/// usually async and bound to a Tokio runtime. Each append is therefore
+/// handed to a dedicated thread that drives the backend's futures on the
+/// runtime given at open, and `record` waits for it like any blocking
+/// write. This works whether `record` is called from plain threads or
+/// from tasks on a multi-thread runtime. It cannot work from the only
+/// thread of the current-thread runtime the logger drives: that runtime
+/// would be blocked waiting for itself, so there call `record` through
+/// `spawn_blocking` or give the logger a separate runtime.
+///
+/// Dropping the logger stops the thread once the append in flight, if
+/// any, has finished.
+pub struct StorageAuditLogger<B> {
+    backend: Arc<B>,
+    prefix: StorageKey,
+    state: Mutex<StorageHead>,
+    tx: Option<SyncSender<Append>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<B: Backend> StorageAuditLogger<B> {
+    /// Open the chain stored under `prefix` in `backend`, verifying it
+    /// end-to-end and restoring the running hash. Appends run on the
+    /// current Tokio runtime.
+    ///
+    /// Fails with [`AuditError::ChainBroken`] on a broken link and with
+    /// [`AuditError::Truncated`] if fewer entries are stored than the head
+    /// record attests to.
+    ///
+    /// # Panics
+    ///
+    /// Outside a Tokio runtime, like `Handle::current`.
+    pub async fn open(backend: B, prefix: StorageKey) -> Result<Self, AuditError> {
+        Self::open_with_runtime(backend, prefix, Handle::current()).await
+    }
+
+    /// As [`Self::open`], running appends on `runtime`.
+    pub async fn open_with_runtime(
+        backend: B,
+        prefix: StorageKey,
+        runtime: Handle,
+    ) -> Result<Self, AuditError> {
+        let summary = walk(&backend, &prefix).await?;
+        let backend = Arc::new(backend);
+        let (tx, rx) = mpsc::sync_channel(1);
+        let worker = {
+            let backend = Arc::clone(&backend);
+            let head_key = head_key(&prefix);
+            std::thread::Builder::new()
+                .name("synthonyx-audit-storage".into())
+                .spawn(move || run(&*backend, &head_key, &runtime, rx))?
+        };
+        Ok(Self {
+            backend,
+            prefix,
+            state: Mutex::new(StorageHead {
+                entries: summary.entries,
+                head_hash: summary.last_hash,
+            }),
+            tx: Some(tx),
+            worker: Some(worker),
+        })
+    }
+
+    /// The backend entries are written to.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Entry count and chain head after the last append.
+    pub fn head(&self) -> StorageHead {
+        *self
+            .state
+            .lock()
+            .expect("storage audit logger mutex poisoned")
+    }
+}
+
+impl<B: Backend> AuditLogger for StorageAuditLogger<B> {
+    fn record(&self, mut entry: AuditEntry) -> Result<(), AuditError> {
+        let mut state = self
+            .state
+            .lock()
+            .expect("storage audit logger mutex poisoned");
+        entry.prev_hash = state.head_hash;
+        entry.sequence = Some(state.entries);
+        entry.writer = None;
+        let line = chain::encode(&entry)?;
+        let next = StorageHead {
+            entries: state.entries + 1,
+            head_hash: chain::hash_line(&line),
+        };
+        let (done, result) = mpsc::sync_channel(1);
+        let append = Append {
+            key: entry_key(&self.prefix, state.entries),
+            line,
+            head: encode_head(&next)?,
+            done,
+        };
+        let tx = self.tx.as_ref().ok_or_else(stopped)?;
+        tx.send(append).map_err(|_| stopped())?;
+        result
+            .recv()
+            .map_err(|_| stopped())?
+            .map_err(backend_error)?;
+        *state = next;
+        Ok(())
+    }
+}
+
+impl<B> Drop for StorageAuditLogger<B> {
+    fn drop(&mut self) {
+        drop(self.tx.take());
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Verify the chain stored under `prefix` in `backend` end-to-end.
+///
+/// Returns the hash of the final entry, like [`crate::verify_chain`].
+pub async fn verify_storage_chain<B: Backend>(
+    backend: &B,
+    prefix: &StorageKey,
+) -> Result<[u8; 32], AuditError> {
+    Ok(walk(backend, prefix).await?.last_hash)
+}
+
+async fn walk<B: Backend>(backend: &B, prefix: &StorageKey) -> Result<ChainSummary, AuditError> {
+    let head = match backend
+        .read(&head_key(prefix))
+        .await
+        .map_err(backend_error)?
+    {
+        Some(bytes) => serde_json::from_slice::<StorageHead>(&bytes)
+            .map_err(|e| AuditError::Serialization(e.to_string()))?,
+        None => StorageHead {
+            entries: 0,
+            head_hash: GENESIS,
+        },
+    };
+    let mut summary = ChainSummary::empty(GENESIS);
+    while let Some(bytes) = backend
+        .read(&entry_key(prefix, summary.entries))
+        .await
+        .map_err(backend_error)?
+    {
+        let line =
+            String::from_utf8(bytes).map_err(|e| AuditError::Serialization(e.to_string()))?;
+        let entry = chain::decode(&line)?;
+        if entry.prev_hash != summary.last_hash {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(summary.last_hash),
+                actual: hex::encode(entry.prev_hash),
+            });
+        }
+        summary.last_hash = chain::hash_line(&line);
+        summary.entries += 1;
+        summary.first_timestamp.get_or_insert(entry.timestamp);
+        summary.last_timestamp = Some(entry.timestamp);
+        if summary.entries == head.entries && summary.last_hash != head.head_hash {
+            return Err(AuditError::ChainBroken {
+                expected: hex::encode(head.head_hash),
+                actual: hex::encode(summary.last_hash),
+            });
+        }
+    }
+    // An append that crashed between the entry and head writes leaves one
+    // entry past the head; anything short of the head is missing data.
+    if summary.entries < head.entries {
+        return Err(AuditError::Truncated {
+            checkpoint_entries: head.entries,
+            log_entries: summary.entries,
+        });
+    }
+    Ok(summary)
+}
+
+fn entry_key(prefix: &StorageKey, seq: u64) -> StorageKey {
+    let mut key = prefix.0.clone();
+    key.push(b'e');
+    key.extend_from_slice(&seq.to_be_bytes());
+    StorageKey(key)
+}
+
+fn head_key(prefix: &StorageKey) -> StorageKey {
+    let mut key = prefix.0.clone();
+    key.push(b'h');
+    StorageKey(key)
+}
+
+fn encode_head(head: &StorageHead) -> Result<Vec<u8>, AuditError> {
+    serde_json::to_vec(head).map_err(|e| AuditError::Serialization(e.to_string()))
+}
+
+fn backend_error(e: StorageError) -> AuditError {
+    AuditError::Backend(e.to_string())
+}
+
+fn stopped() -> AuditError {
+    AuditError::Closed("storage audit thread has stopped".into())
+}
+
+/// The I/O thread: write each entry and then its head record, in order,
+/// until the logger is dropped.
+fn run<B: Backend>(backend: &B, head_key: &StorageKey, runtime: &Handle, rx: Receiver<Append>) {
+    for append in rx {
+        let result = runtime.block_on(async {
+            backend.write(&append.key, append.line.as_bytes()).await?;
+            backend.write(head_key, &append.head).await
+        });
+        let _ = append.done.send(result);
+    }
+}
@@ -0,0 +1,480 @@
+//! RFC 5424 syslog sink.
+//!
+//! Each entry becomes one syslog message whose structured data carries the
+//! entry's attributes, so a SOC's syslog pipeline can index them without
+//! parsing the free-text `MSG`. Messages go out over UDP (one datagram
+//! each), TCP or any byte stream such as a TLS session (RFC 6587
+//! octet-counting framing), or a Unix datagram socket such as `/dev/log`.
+//!
+//! ```text
+//! <110>1 2023-11-14T22:13:20.000000Z host app 4242 register_user
+//!  [audit@32473 correlation_id="…" module="accounts" action="register_user"
+//!  outcome="success" origin_kind="user" principal="u1" timestamp="…"
+//!  field.attempt="1"] accounts.register_user success
+//! ```
+//! (shown wrapped; sent as one message).
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use synthonyx_kit_core::{AuditEntry, AuditError, AuditLogger, AuditValue, Outcome, Severity};
+
+use crate::encoding::{kind_name, outcome_name};
+
+/// The structured-data id used unless [`SyslogAuditLogger::with_sd_id`]
+/// sets another. `32473` is the private enterprise number reserved for
+/// documentation (RFC 5612); deployments should use their own.
+pub const DEFAULT_SD_ID: &str = "audit@32473";
+
+/// How long [`SyslogAuditLogger::tcp`] waits to connect or to write one
+/// message before failing the entry.
+pub const DEFAULT_TCP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Syslog severity (RFC 5424 §6.2.1), most severe first.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum SyslogSeverity {
+    /// System is unusable.
+    Emergency = 0,
+    /// Action must be taken immediately.
+    Alert = 1,
+    /// Critical conditions.
+    Critical = 2,
+    /// Error conditions.
+    Error = 3,
+    /// Warning conditions.
+    Warning = 4,
+    /// Normal but significant condition.
+    Notice = 5,
+    /// Informational messages.
+    Informational = 6,
+    /// Debug-level messages.
+    Debug = 7,
+}
+
+impl SyslogSeverity {
+    /// The numeric code.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// The default mapping for an entry: by its [`Outcome`].
+    pub fn for_entry(entry: &AuditEntry) -> Self {
+        entry.outcome.into()
+    }
+}
+
+impl From<Outcome> for SyslogSeverity {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Success => Self::Informational,
+            Outcome::Denied => Self::Warning,
+            Outcome::Error => Self::Error,
+        }
+    }
+}
+
+impl From<Severity> for SyslogSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Info => Self::Informational,
+            Severity::Notice => Self::Notice,
+            Severity::Warning => Self::Warning,
+            Severity::Security => Self::Error,
+            Severity::Critical => Self::Critical,
+        }
+    }
+}
+
+/// Syslog facility (RFC 5424 §6.2.1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[allow(missing_docs, reason = "names follow RFC 5424 table 1")]
+pub enum Facility {
+    Kernel = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Ntp = 12,
+    /// Log audit; the default for audit sinks.
+    LogAudit = 13,
+    LogAlert = 14,
+    Clock = 15,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+impl Facility {
+    /// The numeric code.
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+enum Transport {
+    Udp(UdpSocket),
+    Tcp {
+        addrs: Vec<SocketAddr>,
+        timeout: Duration,
+        stream: Option<TcpStream>,
+    },
+    Stream(Box<dyn Write + Send>),
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+}
+
+impl Transport {
+    fn send(&mut self, message: &str) -> std::io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.send(message.as_bytes()).map(drop),
+            Self::Tcp {
+                addrs,
+                timeout,
+                stream,
+            } => {
+                if stream.is_none() {
+                    *stream = Some(connect(addrs, *timeout)?);
+                }
+                let result = stream.as_mut().map_or(Ok(()), |s| write_framed(s, message));
+                if result.is_err() {
+                    // Reconnect on the next entry rather than writing into a
+                    // stream whose framing may now be out of step.
+                    *stream = None;
+                }
+                result
+            }
+            Self::Stream(out) => write_framed(out, message),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.send(message.as_bytes()).map(drop),
+        }
+    }
+}
+
+/// Connect to the first reachable address, bounding the connect and every
+/// later read and write by `timeout`.
+fn connect(addrs: &[SocketAddr], timeout: Duration) -> std::io::Result<TcpStream> {
+    let mut last = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, timeout) {
+            Ok(stream) => {
+                stream.set_write_timeout(Some(timeout))?;
+                stream.set_read_timeout(Some(timeout))?;
+                return Ok(stream);
+            }
+            Err(e) => last = Some(e),
+        }
+    }
+    Err(last.unwrap_or_else(|| std::io::ErrorKind::AddrNotAvailable.into()))
+}
+
+/// RFC 6587 octet-counting: `<length> <message>`.
+fn write_framed(out: &mut impl Write, message: &str) -> std::io::Result<()> {
+    let framed = format!("{} {message}", message.len());
+    out.write_all(framed.as_bytes())?;
+    out.flush()
+}
+
+/// Audit sink that sends each entry as an RFC 5424 syslog message.
+///
+/// Like [`crate::TracingAuditLogger`] this is a forwarding sink, not a
+/// tamper-evident store: entries are not chained. Pair it with a durable
+/// sink via [`crate::TeeAuditLogger`].
+///
+/// Severity defaults to [`SyslogSeverity::for_entry`] and the facility to
+/// [`Facility::LogAudit`]. A send failure is returned as
+/// [`AuditError::Io`]; over TCP the connection is re-established on the
+/// next entry. TCP connects and writes are bounded by a timeout (see
+/// [`Self::tcp_with_timeout`]), so a stalled collector fails entries
+/// instead of blocking every audited dispatch behind the transport lock.
+pub struct SyslogAuditLogger {
+    transport: Mutex<Transport>,
+    facility: Facility,
+    severity: fn(&AuditEntry) -> SyslogSeverity,
+    hostname: String,
+    app_name: String,
+    procid: String,
+    sd_id: String,
+}
+
+impl SyslogAuditLogger {
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport: Mutex::new(transport),
+            facility: Facility::LogAudit,
+            severity: SyslogSeverity::for_entry,
+            hostname: "-".into(),
+            app_name: "synthonyx".into(),
+            procid: std::process::id().to_string(),
+            sd_id: DEFAULT_SD_ID.into(),
+        }
+    }
+
+    /// Send one datagram per entry to the collector at `addr`.
+    pub fn udp(addr: impl ToSocketAddrs) -> Result<Self, AuditError> {
+        let addrs = resolve(addr)?;
+        let local: SocketAddr = if addrs[0].is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(&addrs[..])?;
+        Ok(Self::new(Transport::Udp(socket)))
+    }
+
+    /// Connect to the collector at `addr` over plain TCP, with
+    /// [`DEFAULT_TCP_TIMEOUT`].
+    pub fn tcp(addr: impl ToSocketAddrs) -> Result<Self, AuditError> {
+        Self::tcp_with_timeout(addr, DEFAULT_TCP_TIMEOUT)
+    }
+
+    /// Connect to the collector at `addr` over plain TCP, failing a connect
+    /// or a single message write that takes longer than `timeout` with
+    /// [`AuditError::Io`].
+    pub fn tcp_with_timeout(
+        addr: impl ToSocketAddrs,
+        timeout: Duration,
+    ) -> Result<Self, AuditError> {
+        let addrs = resolve(addr)?;
+        let stream = connect(&addrs, timeout)?;
+        Ok(Self::new(Transport::Tcp {
+            addrs,
+            timeout,
+            stream: Some(stream),
+        }))
+    }
+
+    /// Write framed messages to an established byte stream, typically a
+    /// TLS session (RFC 5425) built with the caller's TLS library.
+    pub fn stream(out: impl Write + Send + 'static) -> Self {
+        Self::new(Transport::Stream(Box::new(out)))
+    }
+
+    /// Send one datagram per entry to the Unix socket at `path`, such as
+    /// `/dev/log`.
+    #[cfg(unix)]
+    pub fn unix(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self::new(Transport::Unix(socket)))
+    }
+
+    /// Set the facility (default [`Facility::LogAudit`]).
+    pub fn with_facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Choose each entry's severity (default [`SyslogSeverity::for_entry`]).
+    pub fn with_severity(mut self, severity: fn(&AuditEntry) -> SyslogSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Set the `HOSTNAME` header field (default `-`, the nil value).
+    pub fn with_hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
+    /// Set the `APP-NAME` header field (default `synthonyx`).
+    pub fn with_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Set the structured-data id (default [`DEFAULT_SD_ID`]), normally
+    /// `name@<your private enterprise number>`.
+    pub fn with_sd_id(mut self, sd_id: impl Into<String>) -> Self {
+        self.sd_id = sd_id.into();
+        self
+    }
+
+    /// Render `entry` as an RFC 5424 message, without transport framing.
+    pub fn format(&self, entry: &AuditEntry) -> String {
+        let pri = u16::from(self.facility.code()) * 8 + u16::from((self.severity)(entry).code());
+        let mut out = format!(
+            "<{pri}>1 {} {} {} {} {} [{}",
+            rfc5424_timestamp(entry.timestamp.0),
+            header(&self.hostname, 255),
+            header(&self.app_name, 48),
+            header(&self.procid, 128),
+            header(&entry.action, 32),
+            sd_name(&self.sd_id),
+        );
+        push_param(
+            &mut out,
+            "correlation_id",
+            &entry.correlation_id.to_string(),
+        );
+        push_param(&mut out, "module", &entry.module);
+        push_param(&mut out, "action", &entry.action);
+        push_param(&mut out, "outcome", outcome_name(entry.outcome));
+        push_param(&mut out, "origin_kind", kind_name(entry.origin.kind));
+        if let Some(principal) = &entry.origin.principal {
+            push_param(&mut out, "principal", principal);
+        }
+        if let Some(service) = &entry.origin.service {
+            push_param(&mut out, "service", service);
+        }
+        if let Some(subject) = &entry.subject {
+            push_param(&mut out, "subject", subject);
+        }
+        push_param(&mut out, "timestamp", &entry.timestamp.0.to_string());
+        if let Some(sequence) = entry.sequence {
+            push_param(&mut out, "sequence", &sequence.to_string());
+        }
+        if let Some(writer) = &entry.writer {
+            push_param(&mut out, "writer", writer);
+        }
+        for (key, value) in &entry.fields {
+            push_param(&mut out, &format!("field.{key}"), &value_text(value));
+        }
+        let _ = write!(
+            out,
+            "] {}.{} {}",
+            entry.module,
+            entry.action,
+            outcome_name(entry.outcome)
+        );
+        out
+    }
+}
+
+impl AuditLogger for SyslogAuditLogger {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let message = self.format(&entry);
+        self.transport
+            .lock()
+            .expect("syslog audit logger mutex poisoned")
+            .send(&message)?;
+        Ok(())
+    }
+}
+
+fn resolve(addr: impl ToSocketAddrs) -> Result<Vec<SocketAddr>, AuditError> {
+    let addrs: Vec<_> = addr.to_socket_addrs()?.collect();
+    if addrs.is_empty() {
+        return Err(AuditError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "syslog address resolved to nothing",
+        )));
+    }
+    Ok(addrs)
+}
+
+/// An entry field value as plain text.
+pub(crate) fn value_text(value: &AuditValue) -> Cow<'_, str> {
+    match value {
+        AuditValue::Bool(b) => Cow::Owned(b.to_string()),
+        AuditValue::Int(i) => Cow::Owned(i.to_string()),
+        AuditValue::UInt(u) => Cow::Owned(u.to_string()),
+        AuditValue::Str(s) | AuditValue::Hash(s) => Cow::Borrowed(s),
+    }
+}
+
+/// A header field: printable US-ASCII, at most `max` bytes, `-` if empty.
+fn header(s: &str, max: usize) -> String {
+    if s.is_empty() {
+        return "-".into();
+    }
+    s.chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(max)
+        .collect()
+}
+
+/// An SD-NAME: printable US-ASCII except `=`, space, `]` and `"`, at most
+/// 32 bytes.
+fn sd_name(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '=' | ']' | '"' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .take(32)
+        .collect()
+}
+
+fn push_param(out: &mut String, name: &str, value: &str) {
+    let _ = write!(out, " {}=\"", sd_name(name));
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.ffffffZ`; RFC 5424 allows at most microseconds.
+fn rfc5424_timestamp(nanos: u128) -> String {
+    let secs = nanos / 1_000_000_000;
+    let micros = (nanos % 1_000_000_000) / 1_000;
+    let rem = secs % 86_400;
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}.{micros:06}Z",
+        rem / 3600,
+        (rem / 60) % 60,
+        rem % 60
+    )
+}
+
+// Howard Hinnant's `civil_from_days` for the proleptic Gregorian calendar.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_is_rfc3339_with_microseconds() {
+        assert_eq!(rfc5424_timestamp(0), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(
+            rfc5424_timestamp(1_709_251_198_123_456_789),
+            "2024-02-29T23:59:58.123456Z"
+        );
+    }
+
+    #[test]
+    fn params_and_headers_are_escaped() {
+        let mut out = String::new();
+        push_param(&mut out, "a b=\"c]", "x\"y\\z]");
+        assert_eq!(out, r#" a_b__c_="x\"y\\z\]""#);
+        assert_eq!(header("", 5), "-");
+        assert_eq!(header("a b\u{e9}cdefg", 5), "a_b_c");
+    }
+}
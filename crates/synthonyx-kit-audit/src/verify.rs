@@ -0,0 +1,180 @@
+//! Semantic checks layered on top of hash-chain verification.
+//!
+//! The hash chain proves that no stored line was altered, removed or
+//! inserted after the fact. It does not prove the log was written sanely:
+//! a clock stepping backwards, or two processes appending to the same file
+//! after a misconfiguration, both produce a perfectly valid chain.
+//! [`verify_chain_with`] adds those checks on request.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use synthonyx_kit_core::{AuditEntry, AuditError, UnixNanos};
+
+use crate::chain::{self, GENESIS};
+
+/// Which semantic checks [`verify_chain_with`] applies in addition to the
+/// hash chain. [`VerifyOptions::new`] enables none of them.
+#[derive(Clone, Debug)]
+pub struct VerifyOptions {
+    start: [u8; 32],
+    first_sequence: u64,
+    sequence: bool,
+    timestamps: bool,
+    writer: WriterCheck,
+}
+
+#[derive(Clone, Debug)]
+enum WriterCheck {
+    Any,
+    Single,
+    Expect(Option<String>),
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            start: GENESIS,
+            first_sequence: 0,
+            sequence: false,
+            timestamps: false,
+            writer: WriterCheck::Any,
+        }
+    }
+}
+
+impl VerifyOptions {
+    /// Hash-chain verification only, from the all-zero genesis hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every check: sequence contiguity, non-decreasing timestamps, and a
+    /// single writer.
+    pub fn strict() -> Self {
+        Self::new()
+            .check_sequence()
+            .check_timestamps()
+            .single_writer()
+    }
+
+    /// Expect the first entry to chain to `start` rather than to genesis,
+    /// e.g. a segment's `start_hash`.
+    pub fn start(mut self, start: [u8; 32]) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Expect the first entry to sit at position `first` in the log, e.g.
+    /// the number of entries in the segments before this one.
+    pub fn first_sequence(mut self, first: u64) -> Self {
+        self.first_sequence = first;
+        self
+    }
+
+    /// Require every entry's sequence number to equal its position in the
+    /// log, failing with [`AuditError::SequenceGap`].
+    ///
+    /// Entries without a sequence number (written before sinks assigned
+    /// them) are not checked but still count towards the position.
+    pub fn check_sequence(mut self) -> Self {
+        self.sequence = true;
+        self
+    }
+
+    /// Require timestamps never to decrease from one entry to the next,
+    /// failing with [`AuditError::TimestampRegression`].
+    pub fn check_timestamps(mut self) -> Self {
+        self.timestamps = true;
+        self
+    }
+
+    /// Require every entry to carry the same writer id as the first entry,
+    /// failing with [`AuditError::WriterMismatch`].
+    pub fn single_writer(mut self) -> Self {
+        self.writer = WriterCheck::Single;
+        self
+    }
+
+    /// Require every entry to carry `writer` (`None`: no writer id),
+    /// failing with [`AuditError::WriterMismatch`].
+    pub fn expect_writer(mut self, writer: Option<impl Into<String>>) -> Self {
+        self.writer = WriterCheck::Expect(writer.map(Into::into));
+        self
+    }
+}
+
+/// Verify the chain of a stored audit log file end-to-end, applying the
+/// semantic checks selected in `options`.
+///
+/// Returns the hash of the final entry, like [`crate::verify_chain`]. Chain
+/// and encoding errors take precedence: each line is checked against the
+/// chain before its contents are.
+pub fn verify_chain_with(
+    path: impl AsRef<Path>,
+    options: &VerifyOptions,
+) -> Result<[u8; 32], AuditError> {
+    verify_reader_with(BufReader::new(File::open(path.as_ref())?), options)
+}
+
+/// Like [`verify_chain_with`], over any buffered reader of stored lines.
+pub(crate) fn verify_reader_with(
+    reader: impl BufRead,
+    options: &VerifyOptions,
+) -> Result<[u8; 32], AuditError> {
+    let mut checker = Checker {
+        options,
+        position: options.first_sequence,
+        previous: None,
+        writer: match &options.writer {
+            WriterCheck::Expect(writer) => Some(writer.clone()),
+            WriterCheck::Any | WriterCheck::Single => None,
+        },
+    };
+    let summary = chain::walk_reader(reader, options.start, |entry, _| checker.check(entry))?;
+    Ok(summary.last_hash)
+}
+
+struct Checker<'a> {
+    options: &'a VerifyOptions,
+    position: u64,
+    previous: Option<UnixNanos>,
+    /// The writer every entry must carry, once known.
+    writer: Option<Option<String>>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, entry: &AuditEntry) -> Result<(), AuditError> {
+        if self.options.sequence {
+            if let Some(actual) = entry.sequence.filter(|&s| s != self.position) {
+                return Err(AuditError::SequenceGap {
+                    expected: self.position,
+                    actual,
+                });
+            }
+        }
+        self.position += 1;
+
+        if self.options.timestamps {
+            if let Some(previous) = self.previous.filter(|&p| entry.timestamp < p) {
+                return Err(AuditError::TimestampRegression {
+                    previous,
+                    actual: entry.timestamp,
+                });
+            }
+        }
+        self.previous = Some(entry.timestamp);
+
+        if !matches!(self.options.writer, WriterCheck::Any) {
+            let expected = self.writer.get_or_insert_with(|| entry.writer.clone());
+            if *expected != entry.writer {
+                return Err(AuditError::WriterMismatch {
+                    expected: expected.clone(),
+                    actual: entry.writer.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Compliance contract tests for compressed segment archives.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — long-term storage of closed
+//!   segments).
+//! - DORA Art. 32 (audit-log integrity — archived evidence must stay
+//!   verifiable against the live chain).
+//!
+//! Contracts enforced:
+//! - Archived segments verify from their compressed form, with the raw
+//!   segments pruned, through to the live chain head.
+//! - After pruning, only the live-segment tools keep working on the segment
+//!   directory; whole-chain reads fail loudly rather than skip history.
+//! - Re-archiving only adds newly sealed segments.
+//! - A modified compressed file, a modified manifest or a manifest sealed
+//!   under an unknown key fails verification.
+//! - An archive that disagrees with the live segment manifest is rejected.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    ARCHIVE_MANIFEST_FILE, ArchiveManifest, ArchiveOptions, AuditReader, Ed25519CheckpointSigner,
+    Ed25519CheckpointVerifier, RotationPolicy, SegmentedAuditLogger, archive_segments,
+    verify_archive, verify_latest_segment, verify_segments,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, Secret, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-archive-{prefix}-{pid}-{seq}"))
+}
+
+fn entry(n: u64) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000 + u128::from(n)),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed("archive"),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([("n".to_string(), AuditValue::UInt(n))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn signer() -> Ed25519CheckpointSigner {
+    Ed25519CheckpointSigner::new("archive-1", Secret::new([7; 32]))
+}
+
+fn verifier() -> Ed25519CheckpointVerifier {
+    Ed25519CheckpointVerifier::new()
+        .with_key("archive-1", signer().verifying_key())
+        .unwrap()
+}
+
+/// A segment directory with one entry per segment: `n - 1` sealed
+/// segments and one active.
+fn write_segments(dir: &PathBuf, from: u64, n: u64) {
+    let policy = RotationPolicy {
+        max_bytes: Some(1),
+        max_age_nanos: None,
+    };
+    let logger = SegmentedAuditLogger::open(dir, policy).unwrap();
+    for i in from..from + n {
+        logger.record(entry(i)).unwrap();
+    }
+}
+
+fn cleanup(dirs: &[&PathBuf]) {
+    for dir in dirs {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+#[test]
+fn art_9_archived_segments_verify_after_pruning() {
+    let (live, archive) = (tmp_path("live"), tmp_path("store"));
+    write_segments(&live, 0, 4);
+    let head = verify_segments(&live).unwrap();
+
+    let manifest = archive_segments(
+        &live,
+        &archive,
+        &signer(),
+        &ArchiveOptions::new().prune_sources(),
+    )
+    .unwrap();
+    assert_eq!(manifest.segments.len(), 3);
+    for archived in &manifest.segments {
+        assert_eq!(archived.segment.entries, 1);
+        assert!(archived.segment.first_timestamp.is_some());
+        assert!(archive.join(&archived.file).exists());
+        assert!(!live.join(&archived.segment.file).exists());
+    }
+    assert_eq!(ArchiveManifest::load(&archive).unwrap(), manifest);
+    assert_eq!(verify_archive(&archive, &live, &verifier()).unwrap(), head);
+    cleanup(&[&live, &archive]);
+}
+
+#[test]
+fn art_9_pruned_directory_needs_the_archive_to_verify() {
+    let (live, archive) = (tmp_path("pruned"), tmp_path("pruned-store"));
+    write_segments(&live, 0, 3);
+    archive_segments(
+        &live,
+        &archive,
+        &signer(),
+        &ArchiveOptions::new().prune_sources(),
+    )
+    .unwrap();
+
+    let not_found = |err: AuditError| matches!(err, AuditError::Io(ref e) if e.kind() == std::io::ErrorKind::NotFound);
+    assert!(not_found(verify_segments(&live).unwrap_err()));
+    assert!(not_found(AuditReader::open_segments(&live).err().unwrap()));
+
+    let head = verify_latest_segment(&live).unwrap();
+    assert_eq!(verify_archive(&archive, &live, &verifier()).unwrap(), head);
+    // The writer only needs the active segment.
+    let logger = SegmentedAuditLogger::open(&live, RotationPolicy::default()).unwrap();
+    logger.record(entry(9)).unwrap();
+    drop(logger);
+    verify_archive(&archive, &live, &verifier()).unwrap();
+    cleanup(&[&live, &archive]);
+}
+
+#[test]
+fn art_9_rearchiving_appends_new_segments() {
+    let (live, archive) = (tmp_path("live"), tmp_path("store"));
+    write_segments(&live, 0, 3);
+    let first = archive_segments(&live, &archive, &signer(), &ArchiveOptions::new()).unwrap();
+    assert_eq!(first.segments.len(), 2);
+
+    write_segments(&live, 3, 2);
+    let second = archive_segments(&live, &archive, &signer(), &ArchiveOptions::new()).unwrap();
+    assert_eq!(second.segments.len(), 4);
+    assert_eq!(second.segments[..2], first.segments[..]);
+    assert_eq!(
+        verify_archive(&archive, &live, &verifier()).unwrap(),
+        verify_segments(&live).unwrap()
+    );
+    cleanup(&[&live, &archive]);
+}
+
+#[test]
+fn art_32_modified_compressed_segment_is_detected() {
+    let (live, archive) = (tmp_path("live"), tmp_path("store"));
+    write_segments(&live, 0, 3);
+    let manifest = archive_segments(&live, &archive, &signer(), &ArchiveOptions::new()).unwrap();
+
+    let path = archive.join(&manifest.segments[0].file);
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    std::fs::write(&path, bytes).unwrap();
+    let err = verify_archive(&archive, &live, &verifier()).unwrap_err();
+    assert!(matches!(err, AuditError::Manifest(_)), "got {err:?}");
+    cleanup(&[&live, &archive]);
+}
+
+#[test]
+fn art_32_modified_or_foreign_manifest_fails_the_seal() {
+    let (live, archive) = (tmp_path("live"), tmp_path("store"));
+    write_segments(&live, 0, 3);
+    archive_segments(&live, &archive, &signer(), &ArchiveOptions::new()).unwrap();
+    let path = archive.join(ARCHIVE_MANIFEST_FILE);
+    let original = std::fs::read_to_string(&path).unwrap();
+
+    // Understating the first segment's raw size is caught by the seal
+    // before any file is read.
+    let mut manifest: ArchiveManifest = serde_json::from_str(&original).unwrap();
+    manifest.segments[0].raw_bytes += 1;
+    std::fs::write(&path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+    let err = verify_archive(&archive, &live, &verifier()).unwrap_err();
+    assert!(matches!(err, AuditError::Checkpoint(_)), "got {err:?}");
+
+    std::fs::write(&path, &original).unwrap();
+    let err = verify_archive(&archive, &live, &Ed25519CheckpointVerifier::new()).unwrap_err();
+    assert!(matches!(err, AuditError::Checkpoint(_)), "got {err:?}");
+    cleanup(&[&live, &archive]);
+}
+
+#[test]
+fn art_32_archive_must_match_the_live_manifest() {
+    let (live, other, archive) = (tmp_path("live"), tmp_path("other"), tmp_path("store"));
+    write_segments(&live, 0, 3);
+    write_segments(&other, 10, 3);
+    archive_segments(&other, &archive, &signer(), &ArchiveOptions::new()).unwrap();
+
+    let err = verify_archive(&archive, &live, &verifier()).unwrap_err();
+    assert!(matches!(err, AuditError::Manifest(_)), "got {err:?}");
+    let err = archive_segments(&live, &archive, &signer(), &ArchiveOptions::new()).unwrap_err();
+    assert!(matches!(err, AuditError::Manifest(_)), "got {err:?}");
+    cleanup(&[&live, &other, &archive]);
+}
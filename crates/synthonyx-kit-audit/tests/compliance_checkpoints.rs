@@ -0,0 +1,329 @@
+//! Compliance contract tests for signed audit checkpoints.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — deletion of recent evidence must
+//!   be detectable).
+//! - DORA Art. 32 (audit-log integrity — including the terminal entry and
+//!   key rotation for audit signing).
+//!
+//! Contracts enforced:
+//! - Truncating the log below the latest checkpoint fails verification with
+//!   `AuditError::Truncated`.
+//! - Tampering with the terminal entry covered by a checkpoint is detected.
+//! - A forged or re-signed checkpoint fails signature verification.
+//! - A checkpoint held off-host still detects truncation after the sidecar
+//!   has been deleted.
+//! - Checkpoints signed under a retired key still verify after rotation.
+//! - A checkpoint that cannot be written does not fail the `record()` whose
+//!   entry is already in the log, and is retried on the next one.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    CheckpointSigner, Ed25519CheckpointSigner, Ed25519CheckpointVerifier, FileAuditLogger,
+    checkpoint_path, read_checkpoints, verify_checkpoints, verify_checkpoints_against,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, Secret, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-cp-{prefix}-{pid}-{seq}.jsonl"))
+}
+
+fn cleanup(path: &PathBuf) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(checkpoint_path(path));
+}
+
+fn entry(action: String) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Owned(action),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([("seq".to_string(), AuditValue::UInt(0))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn signer(key_id: &str, seed: u8) -> Ed25519CheckpointSigner {
+    Ed25519CheckpointSigner::new(key_id, Secret::new([seed; 32]))
+}
+
+fn verifier_for(signers: &[&Ed25519CheckpointSigner]) -> Ed25519CheckpointVerifier {
+    let mut verifier = Ed25519CheckpointVerifier::new();
+    for (i, s) in signers.iter().enumerate() {
+        verifier = verifier
+            .with_key(format!("k{}", i + 1), s.verifying_key())
+            .unwrap();
+    }
+    verifier
+}
+
+#[test]
+fn art_32_periodic_checkpoints_verify() {
+    let path = tmp_path("periodic");
+    let s = signer("k1", 1);
+    let verifier = verifier_for(&[&s]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 2)
+        .unwrap();
+    for i in 0..5 {
+        logger.record(entry(format!("act{i}"))).unwrap();
+    }
+    drop(logger);
+
+    let latest = verify_checkpoints(&path, &verifier).unwrap().unwrap();
+    assert_eq!(latest.entries, 4);
+    assert_eq!(read_checkpoints(&path).unwrap().len(), 2);
+    cleanup(&path);
+}
+
+#[test]
+fn art_9_truncated_tail_is_detected() {
+    let path = tmp_path("truncate");
+    let verifier = verifier_for(&[&signer("k1", 1)]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 0)
+        .unwrap();
+    for i in 0..4 {
+        logger.record(entry(format!("act{i}"))).unwrap();
+    }
+    logger.checkpoint().unwrap();
+    drop(logger);
+
+    // Drop the last entry: the remaining chain is internally valid.
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let kept: Vec<&str> = contents.lines().take(3).collect();
+    std::fs::write(&path, format!("{}\n", kept.join("\n"))).unwrap();
+    synthonyx_kit_audit::verify_chain(&path).unwrap();
+
+    let err = verify_checkpoints(&path, &verifier).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::Truncated {
+                checkpoint_entries: 4,
+                log_entries: 3
+            }
+        ),
+        "expected Truncated, got {err:?}"
+    );
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_terminal_entry_tamper_is_detected() {
+    let path = tmp_path("terminal");
+    let verifier = verifier_for(&[&signer("k1", 1)]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 0)
+        .unwrap();
+    logger.record(entry("first".into())).unwrap();
+    logger.record(entry("last".into())).unwrap();
+    logger.checkpoint().unwrap();
+    drop(logger);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replacen("\"last\"", "\"LAST\"", 1)).unwrap();
+    synthonyx_kit_audit::verify_chain(&path).unwrap();
+
+    let err = verify_checkpoints(&path, &verifier).unwrap_err();
+    assert!(
+        matches!(err, AuditError::ChainBroken { .. }),
+        "expected ChainBroken, got {err:?}"
+    );
+    cleanup(&path);
+}
+
+#[test]
+fn art_9_trusted_checkpoint_survives_sidecar_deletion() {
+    let path = tmp_path("trusted");
+    let verifier = verifier_for(&[&signer("k1", 1)]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 0)
+        .unwrap();
+    for i in 0..4 {
+        logger.record(entry(format!("act{i}"))).unwrap();
+    }
+    // Shipped off-host when written.
+    let trusted = logger.checkpoint().unwrap().unwrap();
+    logger.record(entry("act4".into())).unwrap();
+    drop(logger);
+    assert_eq!(
+        verify_checkpoints_against(&path, &verifier, &trusted).unwrap(),
+        trusted
+    );
+
+    // Truncate the log and delete the sidecar: the local check is blind.
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let kept: Vec<&str> = contents.lines().take(2).collect();
+    std::fs::write(&path, format!("{}\n", kept.join("\n"))).unwrap();
+    std::fs::remove_file(checkpoint_path(&path)).unwrap();
+    assert_eq!(verify_checkpoints(&path, &verifier).unwrap(), None);
+
+    let err = verify_checkpoints_against(&path, &verifier, &trusted).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::Truncated {
+                checkpoint_entries: 4,
+                log_entries: 2
+            }
+        ),
+        "expected Truncated, got {err:?}"
+    );
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_forged_checkpoint_is_rejected() {
+    let path = tmp_path("forged");
+    let verifier = verifier_for(&[&signer("k1", 1)]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 0)
+        .unwrap();
+    logger.record(entry("only".into())).unwrap();
+    logger.checkpoint().unwrap();
+    drop(logger);
+
+    // An attacker with write access re-signs with their own key under the
+    // trusted key id.
+    std::fs::remove_file(checkpoint_path(&path)).unwrap();
+    let forged = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 99), 0)
+        .unwrap();
+    forged.checkpoint().unwrap();
+    drop(forged);
+
+    let err = verify_checkpoints(&path, &verifier).unwrap_err();
+    assert!(
+        matches!(err, AuditError::Checkpoint(_)),
+        "expected Checkpoint, got {err:?}"
+    );
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_checkpoints_survive_signing_key_rotation() {
+    let path = tmp_path("rotation");
+    let old = signer("k1", 1);
+    let new = signer("k2", 2);
+    let verifier = verifier_for(&[&old, &new]);
+    {
+        let logger = FileAuditLogger::open(&path)
+            .unwrap()
+            .with_checkpoints(signer("k1", 1), 1)
+            .unwrap();
+        logger.record(entry("before".into())).unwrap();
+    }
+    {
+        let logger = FileAuditLogger::open(&path)
+            .unwrap()
+            .with_checkpoints(signer("k2", 2), 1)
+            .unwrap();
+        logger.record(entry("after".into())).unwrap();
+    }
+
+    let checkpoints = read_checkpoints(&path).unwrap();
+    let key_ids: Vec<&str> = checkpoints.iter().map(|c| c.key_id.as_str()).collect();
+    assert_eq!(key_ids, ["k1", "k2"]);
+    verify_checkpoints(&path, &verifier).unwrap();
+    cleanup(&path);
+}
+
+/// Fails to sign until `healthy` is set, like a KMS that is briefly
+/// unreachable.
+struct FlakySigner {
+    inner: Ed25519CheckpointSigner,
+    healthy: &'static AtomicBool,
+}
+
+impl CheckpointSigner for FlakySigner {
+    fn key_id(&self) -> &str {
+        self.inner.key_id()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, AuditError> {
+        if !self.healthy.load(Ordering::Relaxed) {
+            return Err(AuditError::Checkpoint("signer unavailable".into()));
+        }
+        self.inner.sign(message)
+    }
+}
+
+#[test]
+fn art_9_failed_checkpoint_is_retried_without_failing_the_append() {
+    static HEALTHY: AtomicBool = AtomicBool::new(false);
+    let path = tmp_path("retry");
+    let s = signer("k1", 1);
+    let verifier = verifier_for(&[&s]);
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(
+            FlakySigner {
+                inner: signer("k1", 1),
+                healthy: &HEALTHY,
+            },
+            2,
+        )
+        .unwrap();
+    for i in 0..2 {
+        logger.record(entry(format!("act{i}"))).unwrap();
+    }
+    assert_eq!(logger.len(), 2);
+    assert!(read_checkpoints(&path).unwrap().is_empty());
+
+    HEALTHY.store(true, Ordering::Relaxed);
+    logger.record(entry("act2".into())).unwrap();
+    drop(logger);
+
+    let latest = verify_checkpoints(&path, &verifier).unwrap().unwrap();
+    assert_eq!(latest.entries, 3);
+    assert_eq!(read_checkpoints(&path).unwrap().len(), 1);
+    cleanup(&path);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn art_9_unwritable_sidecar_does_not_fail_the_append() {
+    let path = tmp_path("unwritable");
+    std::os::unix::fs::symlink("/dev/full", checkpoint_path(&path)).unwrap();
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_checkpoints(signer("k1", 1), 1)
+        .unwrap();
+    for i in 0..3 {
+        logger.record(entry(format!("act{i}"))).unwrap();
+    }
+    assert_eq!(logger.len(), 3);
+    assert!(logger.checkpoint().is_err());
+    drop(logger);
+
+    synthonyx_kit_audit::verify_chain(&path).unwrap();
+    cleanup(&path);
+}
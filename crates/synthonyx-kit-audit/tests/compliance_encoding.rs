@@ -0,0 +1,168 @@
+//! Compliance contract tests for the versioned entry encoding.
+//!
+//! References:
+//! - DORA Art. 32 (audit-log integrity — chain hashes must not depend on
+//!   a serializer's incidental output, or a dependency upgrade could
+//!   invalidate every stored log).
+//! - DORA Art. 9 (audit-trail retention — logs written in an older format
+//!   stay verifiable after the format moves on).
+//!
+//! Contracts enforced:
+//! - The V2 and current V3 formats are byte-identical to the ADR 0010
+//!   golden vectors.
+//! - A log written entirely in the legacy V1 format still verifies and
+//!   reads back.
+//! - A legacy log reopened by a current logger continues in V3 and the
+//!   mixed chain verifies end to end.
+//! - A canonical line that is not in canonical form fails verification.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    AuditReader, EntryFormat, FileAuditLogger, decode_entry, encode_entry, verify_chain,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-encoding-{pid}-{seq}-{name}"))
+}
+
+fn entry(action: &'static str) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([0x0f; 16]),
+        module: Cow::Borrowed("auth"),
+        action: Cow::Borrowed(action),
+        origin: OriginSnapshot {
+            kind: OriginKind::User,
+            principal: Some("u1".into()),
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([
+            ("attempt".to_string(), AuditValue::UInt(1)),
+            ("mfa".to_string(), AuditValue::Bool(true)),
+        ]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+/// Write `actions` as a correctly chained log in the legacy V1 format.
+fn write_v1_log(path: &Path, actions: &[&'static str]) {
+    let mut file = std::fs::File::create(path).unwrap();
+    let mut prev = [0u8; 32];
+    for action in actions {
+        let mut e = entry(action);
+        e.prev_hash = prev;
+        let line = encode_entry(&e, EntryFormat::V1).unwrap();
+        prev = *blake3::hash(line.as_bytes()).as_bytes();
+        writeln!(file, "{line}").unwrap();
+    }
+}
+
+#[test]
+fn art_32_v2_matches_golden_vector() {
+    let line = encode_entry(&entry("login"), EntryFormat::V2).unwrap();
+    let golden = concat!(
+        r#"{"action":"login","correlation_id":"0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f","#,
+        r#""fields":{"attempt":{"uint":1},"mfa":{"bool":true}},"module":"auth","#,
+        r#""origin":{"kind":"user","principal":"u1","service":null},"outcome":"success","#,
+        r#""prev_hash":"0000000000000000000000000000000000000000000000000000000000000000","#,
+        r#""subject":null,"timestamp":"1700000000000000000","v":2}"#,
+    );
+    assert_eq!(line, golden);
+}
+
+#[test]
+fn art_32_v3_matches_golden_vector() {
+    let mut e = entry("login");
+    e.sequence = Some(7);
+    e.writer = Some("node-a".into());
+    let line = encode_entry(&e, EntryFormat::V3).unwrap();
+    let golden = concat!(
+        r#"{"action":"login","correlation_id":"0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f","#,
+        r#""fields":{"attempt":{"uint":1},"mfa":{"bool":true}},"module":"auth","#,
+        r#""origin":{"kind":"user","principal":"u1","service":null},"outcome":"success","#,
+        r#""prev_hash":"0000000000000000000000000000000000000000000000000000000000000000","#,
+        r#""sequence":7,"subject":null,"timestamp":"1700000000000000000","v":3,"#,
+        r#""writer":"node-a"}"#,
+    );
+    assert_eq!(line, golden);
+    assert_eq!(EntryFormat::CURRENT, EntryFormat::V3);
+}
+
+#[test]
+fn art_9_legacy_v1_log_verifies() {
+    let path = tmp_path("v1.jsonl");
+    write_v1_log(&path, &["a", "b", "c"]);
+
+    verify_chain(&path).unwrap();
+    let actions: Vec<_> = AuditReader::open(&path)
+        .unwrap()
+        .map(|e| e.unwrap().action.into_owned())
+        .collect();
+    assert_eq!(actions, ["a", "b", "c"]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_9_mixed_format_chain_verifies() {
+    let path = tmp_path("mixed.jsonl");
+    write_v1_log(&path, &["a", "b"]);
+    let logger = FileAuditLogger::open(&path).unwrap();
+    logger.record(entry("c")).unwrap();
+    logger.record(entry("d")).unwrap();
+    drop(logger);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let formats: Vec<_> = contents
+        .lines()
+        .map(|l| decode_entry(l).unwrap().1)
+        .collect();
+    let sequences: Vec<_> = contents
+        .lines()
+        .map(|l| decode_entry(l).unwrap().0.sequence)
+        .collect();
+    assert_eq!(sequences, [None, None, Some(2), Some(3)]);
+    assert_eq!(
+        formats,
+        [
+            EntryFormat::V1,
+            EntryFormat::V1,
+            EntryFormat::V3,
+            EntryFormat::V3
+        ]
+    );
+    verify_chain(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_32_non_canonical_v2_line_is_rejected() {
+    let path = tmp_path("noncanonical.jsonl");
+    let logger = FileAuditLogger::open(&path).unwrap();
+    logger.record(entry("a")).unwrap();
+    logger.record(entry("b")).unwrap();
+    drop(logger);
+
+    // Same entry, same JSON value, different bytes.
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replacen("\"module\":", "\"module\": ", 1)).unwrap();
+    let err = verify_chain(&path).unwrap_err();
+    assert!(matches!(err, AuditError::Serialization(_)), "got {err:?}");
+    let _ = std::fs::remove_file(&path);
+}
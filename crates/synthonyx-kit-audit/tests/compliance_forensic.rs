@@ -0,0 +1,167 @@
+//! Compliance contract tests for forensic verification.
+//!
+//! References:
+//! - DORA Art. 17 (incident investigation — after tampering or corruption,
+//!   investigators must know exactly which records remain trustworthy).
+//! - DORA Art. 32 (audit-log integrity — every defect is reported with its
+//!   position, not only the first).
+//!
+//! Contracts enforced:
+//! - A clean log yields no findings and one linked range, with the same
+//!   head as `verify_chain`.
+//! - Every break, gap, malformed line and torn tail is reported with its
+//!   line number and byte offset; the scan never stops early.
+//! - Trusted ranges split exactly at the defects.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    FileAuditLogger, FindingKind, TrustedRange, forensic_scan, verify_chain,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditLogger, CorrelationId, OriginKind, OriginSnapshot, Outcome, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-forensic-{pid}-{seq}-{name}"))
+}
+
+fn entry(i: usize) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Owned(format!("act{i}")),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::new(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+/// Write `n` chained entries and return the stored lines (with newlines).
+fn write_log(path: &Path, n: usize) -> Vec<String> {
+    let logger = FileAuditLogger::open(path).unwrap();
+    for i in 0..n {
+        logger.record(entry(i)).unwrap();
+    }
+    drop(logger);
+    std::fs::read_to_string(path)
+        .unwrap()
+        .split_inclusive('\n')
+        .map(str::to_string)
+        .collect()
+}
+
+fn range(first_line: u64, last_line: u64, linked: bool, lines: &[String]) -> TrustedRange {
+    let offset = |line: u64| -> u64 {
+        lines[..(line - 1) as usize]
+            .iter()
+            .map(|l| l.len() as u64)
+            .sum()
+    };
+    TrustedRange {
+        first_line,
+        last_line,
+        start_offset: offset(first_line),
+        end_offset: offset(last_line + 1),
+        entries: last_line - first_line + 1,
+        linked,
+    }
+}
+
+#[test]
+fn art_32_clean_log_has_one_linked_range() {
+    let path = tmp_path("clean.jsonl");
+    let lines = write_log(&path, 5);
+
+    let report = forensic_scan(&path).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(report.entries, 5);
+    assert_eq!(report.trusted, [range(1, 5, true, &lines)]);
+    assert_eq!(report.head, verify_chain(&path).unwrap());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_17_tampered_entry_is_located() {
+    let path = tmp_path("tamper.jsonl");
+    let mut lines = write_log(&path, 5);
+    lines[1] = lines[1].replacen("\"act1\"", "\"actX\"", 1);
+    std::fs::write(&path, lines.concat()).unwrap();
+
+    let report = forensic_scan(&path).unwrap();
+    assert_eq!(report.findings.len(), 1);
+    let finding = &report.findings[0];
+    assert_eq!(finding.line, 3);
+    assert_eq!(finding.offset, (lines[0].len() + lines[1].len()) as u64);
+    assert!(matches!(finding.kind, FindingKind::ChainBroken { .. }));
+    assert_eq!(
+        report.trusted,
+        [range(1, 2, true, &lines), range(3, 5, false, &lines)]
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_17_every_defect_is_reported() {
+    let path = tmp_path("many.jsonl");
+    let original = write_log(&path, 6);
+    // Delete entry 2, insert garbage after entry 3, replay entry 0, and
+    // leave a torn tail.
+    let lines = vec![
+        original[0].clone(),
+        original[1].clone(),
+        original[3].clone(),
+        original[4].clone(),
+        "not json\n".to_string(),
+        original[5].clone(),
+        original[0].clone(),
+        "{\"act".to_string(),
+    ];
+    std::fs::write(&path, lines.concat()).unwrap();
+
+    let report = forensic_scan(&path).unwrap();
+    let found: Vec<_> = report
+        .findings
+        .iter()
+        .map(|f| (f.line, f.kind.clone()))
+        .collect();
+    assert!(matches!(found[0], (3, FindingKind::ChainBroken { .. })));
+    assert!(matches!(found[1], (5, FindingKind::Malformed { .. })));
+    assert_eq!(found[2], (6, FindingKind::Gap { links_to_line: 4 }));
+    assert_eq!(found[3], (7, FindingKind::Gap { links_to_line: 0 }));
+    assert_eq!(found[4], (8, FindingKind::TornTail { len: 5 }));
+    assert_eq!(found.len(), 5);
+    for f in &report.findings {
+        let expected: usize = lines[..(f.line - 1) as usize].iter().map(String::len).sum();
+        assert_eq!(f.offset, expected as u64, "line {}", f.line);
+    }
+
+    assert_eq!(report.lines, 8);
+    assert_eq!(report.entries, 6);
+    assert_eq!(
+        report.trusted,
+        [
+            range(1, 2, true, &lines),
+            range(3, 4, false, &lines),
+            range(6, 6, false, &lines),
+            range(7, 7, false, &lines),
+        ]
+    );
+    let _ = std::fs::remove_file(&path);
+}
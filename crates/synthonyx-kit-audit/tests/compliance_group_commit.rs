@@ -0,0 +1,143 @@
+//! Compliance contract tests for `GroupCommitAuditLogger`.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — acknowledged entries are durable
+//!   and never silently dropped).
+//! - DORA Art. 32 (audit-log integrity — concurrent writers).
+//!
+//! Contracts enforced:
+//! - Concurrent writers batched by the background thread produce a single,
+//!   valid chain containing every acknowledged entry.
+//! - Under every `SyncPolicy`, a single caller is never left waiting for
+//!   other callers to fill a batch.
+//! - After `shutdown()`, `record()` fails with `AuditError::Closed` rather
+//!   than accepting entries that will never be written.
+//! - The file format is shared with `FileAuditLogger`, which can reopen and
+//!   extend the chain.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use synthonyx_kit_audit::{
+    AuditReader, FileAuditLogger, GroupCommitAuditLogger, SyncPolicy, verify_chain,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-group-{prefix}-{pid}-{seq}.jsonl"))
+}
+
+fn entry(seq: u64) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed("act"),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([("seq".to_string(), AuditValue::UInt(seq))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn count(path: &PathBuf) -> usize {
+    AuditReader::open(path).unwrap().map(Result::unwrap).count()
+}
+
+#[test]
+fn art_32_concurrent_writers_share_one_chain() {
+    let path = tmp_path("concurrent");
+    let logger = Arc::new(GroupCommitAuditLogger::open(&path, SyncPolicy::EveryEntry).unwrap());
+    let handles: Vec<_> = (0..8)
+        .map(|t| {
+            let logger = Arc::clone(&logger);
+            thread::spawn(move || {
+                for i in 0..50 {
+                    logger.record(entry(t * 1000 + i)).unwrap();
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    logger.shutdown().unwrap();
+
+    verify_chain(&path).unwrap();
+    assert_eq!(count(&path), 400);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_9_single_caller_never_waits_for_a_batch() {
+    for policy in [
+        SyncPolicy::EveryEntry,
+        SyncPolicy::EveryN(1_000),
+        SyncPolicy::Interval(Duration::from_secs(3600)),
+    ] {
+        let path = tmp_path("single");
+        let logger = GroupCommitAuditLogger::open(&path, policy).unwrap();
+        for i in 0..3 {
+            logger.record(entry(i)).unwrap();
+        }
+        // Acknowledged entries are visible to readers before any fsync.
+        assert_eq!(count(&path), 3, "{policy:?}");
+        logger.sync().unwrap();
+        drop(logger);
+        verify_chain(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+#[test]
+fn art_9_record_after_shutdown_is_rejected() {
+    let path = tmp_path("shutdown");
+    let logger = GroupCommitAuditLogger::open(&path, SyncPolicy::EveryN(10)).unwrap();
+    logger.record(entry(0)).unwrap();
+    logger.shutdown().unwrap();
+    logger.shutdown().unwrap();
+
+    let err = logger.record(entry(1)).unwrap_err();
+    assert!(matches!(err, AuditError::Closed(_)), "got {err:?}");
+    assert_eq!(count(&path), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_9_file_logger_extends_group_commit_chain() {
+    let path = tmp_path("interop");
+    {
+        let logger = GroupCommitAuditLogger::open(&path, SyncPolicy::EveryEntry).unwrap();
+        logger.record(entry(0)).unwrap();
+    }
+    {
+        let logger = FileAuditLogger::open(&path).unwrap();
+        logger.record(entry(1)).unwrap();
+    }
+    let logger = GroupCommitAuditLogger::open(&path, SyncPolicy::EveryEntry).unwrap();
+    logger.record(entry(2)).unwrap();
+    drop(logger);
+
+    verify_chain(&path).unwrap();
+    assert_eq!(count(&path), 3);
+    let _ = std::fs::remove_file(&path);
+}
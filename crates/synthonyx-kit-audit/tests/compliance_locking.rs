@@ -0,0 +1,194 @@
+//! Compliance contract tests for cross-process write locking.
+//!
+//! References:
+//! - DORA Art. 32 (audit-log integrity — a second writer must not be able
+//!   to interleave lines into a live chain).
+//! - DORA Art. 9 (audit-trail retention — tooling can inspect a live log
+//!   without interrupting it).
+//!
+//! Contracts enforced:
+//! - While one process has a log open for writing, opening it for writing
+//!   from another process fails with `AuditError::Locked`; the lock is
+//!   released when the holder exits.
+//! - Within a process, every appending sink and `recover_torn_tail` honour
+//!   the same lock, and dropping the holder releases it.
+//! - A segment directory is locked as a whole.
+//! - `FileAuditLogger::open_read_only` takes no lock, never creates or
+//!   modifies the log, and refuses to record.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    FileAuditLogger, GroupCommitAuditLogger, RotationPolicy, SegmentedAuditLogger, SyncPolicy,
+    lock_path, quarantine_path, recover_torn_tail, verify_chain,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, MockClock, OriginKind,
+    OriginSnapshot, Outcome, UnixNanos,
+};
+
+/// Set in the child process spawned by
+/// `art_32_second_process_is_locked_out`: the path it should hold open.
+const HOLDER_ENV: &str = "SYNTHONYX_AUDIT_LOCK_HOLDER";
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-lock-{prefix}-{pid}-{seq}.jsonl"))
+}
+
+fn cleanup(path: &PathBuf) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(lock_path(path));
+}
+
+fn entry(action: &'static str) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed(action),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([("n".to_string(), AuditValue::UInt(1))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn assert_locked<T>(result: Result<T, AuditError>) {
+    match result {
+        Err(AuditError::Locked(_)) => {}
+        Err(e) => panic!("expected AuditError::Locked, got {e:?}"),
+        Ok(_) => panic!("expected AuditError::Locked, got Ok"),
+    }
+}
+
+/// Child-process half of `art_32_second_process_is_locked_out`: open the
+/// log, write one entry, report, and hold the lock until stdin closes.
+/// A no-op in a normal test run.
+#[test]
+fn lock_holder() {
+    let Ok(path) = std::env::var(HOLDER_ENV) else {
+        return;
+    };
+    let logger = FileAuditLogger::open(&path).unwrap();
+    logger.record(entry("child")).unwrap();
+    println!("holding");
+    std::io::stdout().flush().unwrap();
+    let mut rest = String::new();
+    let _ = std::io::stdin().read_line(&mut rest);
+}
+
+#[test]
+fn art_32_second_process_is_locked_out() {
+    let path = tmp_path("process");
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "lock_holder", "--nocapture", "--test-threads=1"])
+        .env(HOLDER_ENV, &path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("holding") {
+        line.clear();
+        assert_ne!(stdout.read_line(&mut line).unwrap(), 0, "holder exited");
+    }
+
+    assert_locked(FileAuditLogger::open(&path));
+    let reader = FileAuditLogger::open_read_only(&path).unwrap();
+    assert_eq!(reader.len(), 1);
+
+    drop(child.stdin.take());
+    assert!(child.wait().unwrap().success());
+    let logger = FileAuditLogger::open(&path).unwrap();
+    logger.record(entry("parent")).unwrap();
+    drop(logger);
+    verify_chain(&path).unwrap();
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_every_writer_honours_the_lock() {
+    let path = tmp_path("sinks");
+    let holder = FileAuditLogger::open(&path).unwrap();
+    holder.record(entry("first")).unwrap();
+
+    assert_locked(FileAuditLogger::open(&path));
+    assert_locked(GroupCommitAuditLogger::open(&path, SyncPolicy::EveryEntry));
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(b"{\"partial")
+        .unwrap();
+    assert_locked(recover_torn_tail(&path, &MockClock::new(0, 0)));
+
+    drop(holder);
+    recover_torn_tail(&path, &MockClock::new(0, 0))
+        .unwrap()
+        .unwrap();
+    let group = GroupCommitAuditLogger::open(&path, SyncPolicy::EveryEntry).unwrap();
+    assert_locked(FileAuditLogger::open(&path));
+    group.shutdown().unwrap();
+    drop(group);
+    FileAuditLogger::open(&path).unwrap();
+    let _ = std::fs::remove_file(quarantine_path(&path));
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_segment_directory_is_locked() {
+    let dir = tmp_path("segments");
+    let holder = SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+    assert_locked(SegmentedAuditLogger::open(&dir, RotationPolicy::default()));
+    drop(holder);
+    SegmentedAuditLogger::open(&dir, RotationPolicy::default()).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn art_9_read_only_open_takes_no_lock() {
+    let path = tmp_path("read-only");
+    assert!(matches!(
+        FileAuditLogger::open_read_only(&path),
+        Err(AuditError::Io(_))
+    ));
+    assert!(!path.exists(), "read-only open must not create the log");
+
+    let writer = FileAuditLogger::open(&path).unwrap();
+    writer.record(entry("first")).unwrap();
+    writer.record(entry("second")).unwrap();
+
+    let reader = FileAuditLogger::open_read_only(&path).unwrap();
+    assert!(reader.is_read_only());
+    assert_eq!(reader.len(), 2);
+    assert_eq!(reader.head(), writer.head());
+    assert!(matches!(
+        reader.record(entry("third")),
+        Err(AuditError::Closed(_))
+    ));
+    assert!(matches!(reader.checkpoint(), Err(AuditError::Closed(_))));
+
+    // The writer is unaffected, and a second reader can open alongside.
+    writer.record(entry("third")).unwrap();
+    let later = FileAuditLogger::open_read_only(&path).unwrap();
+    assert_eq!(later.len(), 3);
+    assert_eq!(verify_chain(&path).unwrap(), writer.head());
+    cleanup(&path);
+}
@@ -0,0 +1,204 @@
+//! Compliance contract tests for `PiiGuardAuditLogger`.
+//!
+//! References:
+//! - GDPR Art. 5(1)(c) (data minimisation — audit entries carry no
+//!   personal data in clear beyond what is necessary).
+//! - GDPR Art. 25 (data protection by default — the guard rejects every
+//!   kind unless configured otherwise).
+//! - DORA Art. 10 (detection — a leak attempt raises a Security-severity
+//!   signal).
+//!
+//! Contracts enforced:
+//! - By default an entry containing personal data is rejected with
+//!   `AuditError::PiiDetected`, nothing reaches the sink, and the error and
+//!   signal name the kind and location but never the data.
+//! - `Hash` replaces the value with a keyed hash that is equal wherever the
+//!   same value appears; `Redact` replaces only the matches.
+//! - `Hash` without a key fails closed.
+//! - Allowed fields and `Allow`ed kinds pass through without a signal.
+//! - Composed inside `ShreddingAuditLogger`, shredded subjects pass.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_audit::{
+    MemoryAuditLogger, MemorySubjectKeyStore, PiiAction, PiiDetected, PiiGuardAuditLogger, PiiKind,
+    PiiLocation, ShreddingAuditLogger,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, Event, EventBus, OriginKind,
+    OriginSnapshot, Outcome, Secret, Severity, UnixNanos,
+};
+
+const EMAIL: &str = "alice@example.com";
+
+fn entry(fields: &[(&str, &str)], subject: Option<&str>, principal: Option<&str>) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([4u8; 16]),
+        module: Cow::Borrowed("users"),
+        action: Cow::Borrowed("register"),
+        origin: OriginSnapshot {
+            kind: OriginKind::User,
+            principal: principal.map(str::to_string),
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: subject.map(str::to_string),
+        fields: fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), AuditValue::from(*v)))
+            .collect::<BTreeMap<_, _>>(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+#[derive(Clone, Default)]
+struct Signals(Arc<Mutex<Vec<PiiDetected>>>);
+
+impl EventBus<PiiDetected> for Signals {
+    fn emit(&self, event: PiiDetected) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+impl Signals {
+    fn take(&self) -> Vec<PiiDetected> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+fn guard() -> (
+    PiiGuardAuditLogger<MemoryAuditLogger>,
+    MemoryAuditLogger,
+    Signals,
+) {
+    let memory = MemoryAuditLogger::new();
+    let signals = Signals::default();
+    let guard = PiiGuardAuditLogger::new(memory.clone()).with_signal(signals.clone());
+    (guard, memory, signals)
+}
+
+fn hash_of(value: &AuditValue) -> &str {
+    match value {
+        AuditValue::Hash(h) => h,
+        other => panic!("expected a hash, got {other:?}"),
+    }
+}
+
+#[test]
+fn art_25_personal_data_is_rejected_by_default() {
+    let (guard, memory, signals) = guard();
+    let err = guard
+        .record(entry(&[("contact", EMAIL)], None, None))
+        .unwrap_err();
+    match &err {
+        AuditError::PiiDetected(message) => {
+            assert!(message.contains("email"), "{message}");
+            assert!(message.contains("contact"), "{message}");
+            assert!(!message.contains(EMAIL), "{message}");
+        }
+        other => panic!("expected PiiDetected, got {other:?}"),
+    }
+    assert!(memory.is_empty());
+
+    let raised = signals.take();
+    assert_eq!(raised.len(), 1);
+    assert_eq!(raised[0].severity(), Severity::Security);
+    assert_eq!(raised[0].kind, PiiKind::Email);
+    assert_eq!(raised[0].location, PiiLocation::Field("contact".into()));
+    assert_eq!(raised[0].handling, PiiAction::Reject);
+
+    for (kind, text) in [
+        (PiiKind::Phone, "+49 30 1234567"),
+        (PiiKind::Iban, "DE89 3704 0044 0532 0130 00"),
+        (PiiKind::CardNumber, "4111-1111-1111-1111"),
+        (PiiKind::NationalId, "078-05-1120"),
+    ] {
+        assert!(
+            guard.record(entry(&[("note", text)], None, None)).is_err(),
+            "{kind} not rejected"
+        );
+        assert_eq!(signals.take()[0].kind, kind);
+    }
+    assert!(memory.is_empty());
+}
+
+#[test]
+fn art_5_hash_and_redact_rewrite_the_entry() {
+    let (guard, memory, signals) = guard();
+    let guard = guard
+        .with_hash_key(Secret::new([9; 32]))
+        .with_action(PiiKind::Email, PiiAction::Hash)
+        .with_action(PiiKind::Phone, PiiAction::Redact);
+    guard
+        .record(entry(
+            &[("contact", EMAIL), ("note", "call +33 1 23 45 67 89 today")],
+            Some(EMAIL),
+            Some(EMAIL),
+        ))
+        .unwrap();
+
+    let stored = &memory.entries()[0];
+    let hashed = hash_of(&stored.fields["contact"]);
+    assert_eq!(stored.subject.as_deref(), Some(hashed));
+    assert_eq!(stored.origin.principal.as_deref(), Some(hashed));
+    assert!(matches!(
+        &stored.fields["note"],
+        AuditValue::Str(s) if s == "call [redacted:phone] today"
+    ));
+    memory.assert_not_contains(EMAIL);
+    memory.assert_not_contains("23 45");
+    assert_eq!(signals.take().len(), 4);
+
+    // Under another key the same address hashes differently.
+    let other = MemoryAuditLogger::new();
+    PiiGuardAuditLogger::new(other.clone())
+        .with_hash_key(Secret::new([8; 32]))
+        .with_action(PiiKind::Email, PiiAction::Hash)
+        .record(entry(&[("contact", EMAIL)], None, None))
+        .unwrap();
+    assert_ne!(hash_of(&other.entries()[0].fields["contact"]), hashed);
+}
+
+#[test]
+fn art_25_hash_without_a_key_fails_closed() {
+    let (guard, memory, _) = guard();
+    let guard = guard.with_action(PiiKind::Email, PiiAction::Hash);
+    assert!(matches!(
+        guard.record(entry(&[], Some(EMAIL), None)),
+        Err(AuditError::PiiDetected(_))
+    ));
+    assert!(memory.is_empty());
+}
+
+#[test]
+fn art_5_allowed_fields_and_kinds_pass_silently() {
+    let (guard, memory, signals) = guard();
+    let guard = guard
+        .allow_field("reference")
+        .with_action(PiiKind::Phone, PiiAction::Allow);
+    guard
+        .record(entry(
+            &[("reference", EMAIL), ("hotline", "+44 20 7946 0958")],
+            None,
+            None,
+        ))
+        .unwrap();
+    assert_eq!(memory.len(), 1);
+    assert!(signals.take().is_empty());
+}
+
+#[test]
+fn art_5_shredded_subjects_pass_the_guard() {
+    let memory = MemoryAuditLogger::new();
+    let audit = ShreddingAuditLogger::new(
+        PiiGuardAuditLogger::new(memory.clone()),
+        MemorySubjectKeyStore::new(),
+    );
+    audit.record(entry(&[], Some(EMAIL), None)).unwrap();
+    memory.assert_not_contains(EMAIL);
+}
@@ -0,0 +1,180 @@
+//! Compliance contract tests for `QueuedAuditLogger`.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — accepted entries are never
+//!   dropped silently).
+//! - DORA Art. 11 (response and recovery — a slow audit sink must not hang
+//!   the operations it audits).
+//!
+//! Contracts enforced:
+//! - A full queue refuses the entry at once with
+//!   `AuditError::Backpressure` carrying the depth and capacity.
+//! - Shutdown drains every accepted entry into the inner sink, in order,
+//!   and later entries are refused with `AuditError::Closed`.
+//! - Metrics account for every entry offered.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_audit::{MemoryAuditLogger, QueueMetrics, QueuedAuditLogger};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, UnixNanos,
+};
+
+fn entry(n: u64) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed("queued"),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::from([("n".to_string(), AuditValue::UInt(n))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+/// A sink that announces each `record()` and then blocks until the test
+/// releases it (or drops the release channel), before passing the entry
+/// on to a `MemoryAuditLogger`.
+struct Gate {
+    entered: Mutex<Sender<()>>,
+    release: Mutex<Receiver<()>>,
+    inner: MemoryAuditLogger,
+}
+
+impl AuditLogger for Gate {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let _ = self.entered.lock().unwrap().send(());
+        let _ = self.release.lock().unwrap().recv();
+        self.inner.record(entry)
+    }
+}
+
+fn gated(capacity: usize) -> Gated {
+    let (entered_tx, entered) = mpsc::channel();
+    let (release, release_rx) = mpsc::channel();
+    let memory = MemoryAuditLogger::new();
+    let gate = Gate {
+        entered: Mutex::new(entered_tx),
+        release: Mutex::new(release_rx),
+        inner: memory.clone(),
+    };
+    Gated {
+        logger: QueuedAuditLogger::new(gate, capacity).unwrap(),
+        entered,
+        release,
+        memory,
+    }
+}
+
+struct Gated {
+    logger: QueuedAuditLogger,
+    /// Receives once per entry reaching the sink.
+    entered: Receiver<()>,
+    /// Releases one blocked entry per send; dropping it releases all.
+    release: Sender<()>,
+    memory: MemoryAuditLogger,
+}
+
+/// The `n` field of every entry that reached the sink, in order.
+fn recorded(memory: &MemoryAuditLogger) -> Vec<u64> {
+    memory
+        .entries()
+        .iter()
+        .map(|e| match e.fields["n"] {
+            AuditValue::UInt(n) => n,
+            _ => panic!("unexpected field"),
+        })
+        .collect()
+}
+
+#[test]
+fn art_11_full_queue_returns_backpressure_without_blocking() {
+    let Gated {
+        logger,
+        entered,
+        release,
+        memory,
+    } = gated(2);
+    logger.record(entry(0)).unwrap();
+    entered.recv().unwrap(); // entry 0 is in the sink, the queue is empty
+    logger.record(entry(1)).unwrap();
+    logger.record(entry(2)).unwrap();
+
+    let err = logger.record(entry(3)).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::Backpressure {
+                depth: 2,
+                capacity: 2
+            }
+        ),
+        "got {err:?}"
+    );
+    assert_eq!(
+        logger.metrics(),
+        QueueMetrics {
+            capacity: 2,
+            depth: 2,
+            high_water: 2,
+            enqueued: 3,
+            written: 0,
+            rejected: 1,
+            failed: 0,
+        }
+    );
+
+    drop(release);
+    logger.flush().unwrap();
+    assert_eq!(logger.metrics().written, 3);
+    assert_eq!(recorded(&memory), [0, 1, 2]);
+    memory.assert_chain_valid();
+}
+
+#[test]
+fn art_9_shutdown_drains_every_accepted_entry() {
+    let Gated {
+        logger,
+        entered,
+        release,
+        memory,
+    } = gated(8);
+    let logger = Arc::new(logger);
+    for n in 0..8 {
+        logger.record(entry(n)).unwrap();
+    }
+    entered.recv().unwrap();
+
+    // Shut down while the sink is still blocked on the first entry.
+    let closing = {
+        let logger = Arc::clone(&logger);
+        std::thread::spawn(move || logger.shutdown())
+    };
+    // Entries offered before the shutdown takes effect are either accepted
+    // (and will be drained) or refused for backpressure; wait until they
+    // are refused as closed.
+    while !matches!(logger.record(entry(99)), Err(AuditError::Closed(_))) {
+        std::thread::yield_now();
+    }
+    drop(release);
+    closing.join().unwrap().unwrap();
+
+    let metrics = logger.metrics();
+    assert_eq!(metrics.depth, 0);
+    assert_eq!(metrics.written, metrics.enqueued);
+    assert_eq!(memory.len() as u64, metrics.enqueued);
+    assert_eq!(recorded(&memory)[..8], [0, 1, 2, 3, 4, 5, 6, 7]);
+    assert!(logger.shutdown().is_ok(), "second shutdown is a no-op");
+}
@@ -0,0 +1,122 @@
+//! Compliance contract tests for reading the audit trail back.
+//!
+//! References:
+//! - GDPR Art. 15 (right of access — every record linked to a data subject
+//!   must be retrievable).
+//! - DORA Art. 17 (incident investigation — every record of one operation
+//!   must be retrievable by correlation id).
+//!
+//! Contracts enforced:
+//! - An `AuditQuery` by subject returns exactly that subject's entries, in
+//!   log order.
+//! - A `CorrelationIndex` lookup returns the same entries as a full scan
+//!   filtered by correlation id.
+//! - Reading a tampered log surfaces `AuditError::ChainBroken` instead of
+//!   returning entries past the break.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{AuditQuery, AuditReader, CorrelationIndex, FileAuditLogger, index_path};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-audit-read-{prefix}-{pid}-{seq}.jsonl"))
+}
+
+fn cleanup(path: &PathBuf) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(index_path(path));
+}
+
+fn entry(i: u8, subject: Option<&str>) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000 + u128::from(i)),
+        correlation_id: CorrelationId([i % 3; 16]),
+        module: Cow::Borrowed("accounts"),
+        action: Cow::Owned(format!("act{i}")),
+        origin: OriginSnapshot {
+            kind: OriginKind::User,
+            principal: Some("operator".into()),
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: subject.map(str::to_string),
+        fields: BTreeMap::new(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn write_log(path: &PathBuf) {
+    let logger = FileAuditLogger::open(path).unwrap();
+    for i in 0..12u8 {
+        let subject = if i % 4 == 0 {
+            Some("subj-a")
+        } else {
+            Some("subj-b")
+        };
+        logger.record(entry(i, subject)).unwrap();
+    }
+}
+
+#[test]
+fn art_15_access_request_returns_every_subject_entry() {
+    let path = tmp_path("subject");
+    write_log(&path);
+    let actions: Vec<String> = AuditReader::open(&path)
+        .unwrap()
+        .with_query(AuditQuery::new().subject("subj-a"))
+        .map(|e| e.unwrap().action.into_owned())
+        .collect();
+    assert_eq!(actions, ["act0", "act4", "act8"]);
+    cleanup(&path);
+}
+
+#[test]
+fn art_17_index_lookup_matches_full_scan() {
+    let path = tmp_path("index");
+    write_log(&path);
+    let id = CorrelationId([1; 16]);
+    let scanned: Vec<String> = AuditReader::open(&path)
+        .unwrap()
+        .with_query(AuditQuery::new().correlation_id(id))
+        .map(|e| e.unwrap().action.into_owned())
+        .collect();
+    let indexed: Vec<String> = CorrelationIndex::open(&path)
+        .unwrap()
+        .lookup(&path, id)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.action.into_owned())
+        .collect();
+    assert_eq!(scanned, ["act1", "act4", "act7", "act10"]);
+    assert_eq!(indexed, scanned);
+    cleanup(&path);
+}
+
+#[test]
+fn art_17_reader_surfaces_tampering() {
+    let path = tmp_path("tamper");
+    write_log(&path);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replacen("\"act5\"", "\"actX\"", 1)).unwrap();
+
+    let results: Vec<_> = AuditReader::open(&path).unwrap().collect();
+    assert_eq!(results.len(), 7);
+    assert!(
+        matches!(results.last(), Some(Err(AuditError::ChainBroken { .. }))),
+        "expected ChainBroken at the end"
+    );
+    cleanup(&path);
+}
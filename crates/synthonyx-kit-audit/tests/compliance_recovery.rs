@@ -0,0 +1,172 @@
+//! Compliance contract tests for torn-tail recovery.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — a crash mid-write must not take
+//!   the audit trail, or the service, down).
+//! - DORA Art. 32 (audit-log integrity — recovery never masks tampering).
+//!
+//! Contracts enforced:
+//! - A partial final line is moved to the quarantine sidecar, a chained
+//!   recovery marker is appended, and the log reopens and keeps growing.
+//! - A broken chain before the torn tail is refused and the log is left
+//!   byte-for-byte untouched.
+//! - A log ending on a newline is never modified.
+//! - The recovery marker keeps the log's sequence and writer id, so a
+//!   recovered single-writer log still passes strict verification.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    AuditReader, FileAuditLogger, QuarantinedTail, RECOVERY_ACTION, VerifyOptions, quarantine_path,
+    recover_torn_tail, verify_chain, verify_chain_with,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, CorrelationId, MockClock, OriginKind, OriginSnapshot,
+    Outcome, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(prefix: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "synthonyx-audit-recover-{prefix}-{pid}-{seq}.jsonl"
+    ))
+}
+
+fn cleanup(path: &PathBuf) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(quarantine_path(path));
+}
+
+fn entry(action: &'static str) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed(action),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::new(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn write_with_torn_tail(path: &PathBuf) -> &'static [u8] {
+    let logger = FileAuditLogger::open(path).unwrap();
+    logger.record(entry("first")).unwrap();
+    logger.record(entry("second")).unwrap();
+    drop(logger);
+    let torn: &'static [u8] = b"{\"timestamp\":17000000";
+    let mut f = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+    f.write_all(torn).unwrap();
+    torn
+}
+
+#[test]
+fn art_9_torn_tail_is_quarantined_and_chain_continues() {
+    let path = tmp_path("torn");
+    let torn = write_with_torn_tail(&path);
+    assert!(matches!(
+        FileAuditLogger::open(&path),
+        Err(AuditError::Serialization(_))
+    ));
+
+    let clock = MockClock::new(42, 0);
+    let logger = FileAuditLogger::open_recovering(&path, &clock).unwrap();
+    logger.record(entry("third")).unwrap();
+    drop(logger);
+
+    verify_chain(&path).unwrap();
+    let entries: Vec<AuditEntry> = AuditReader::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let actions: Vec<&str> = entries.iter().map(|e| e.action.as_ref()).collect();
+    assert_eq!(actions, ["first", "second", RECOVERY_ACTION, "third"]);
+    assert_eq!(entries[2].outcome, Outcome::Error);
+    assert_eq!(entries[2].timestamp, UnixNanos(42));
+
+    let sidecar = std::fs::read_to_string(quarantine_path(&path)).unwrap();
+    let tail: QuarantinedTail = serde_json::from_str(sidecar.trim_end()).unwrap();
+    assert_eq!(hex::decode(&tail.bytes).unwrap(), torn);
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_recovered_log_passes_strict_verification() {
+    let path = tmp_path("strict");
+    let logger = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_writer_id("node-a");
+    logger.record(entry("first")).unwrap();
+    logger.record(entry("second")).unwrap();
+    drop(logger);
+    let mut f = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    f.write_all(b"{\"timestamp\":17000000").unwrap();
+    drop(f);
+
+    let clock = MockClock::new(1_700_000_000_000_000_001, 0);
+    let logger = FileAuditLogger::open_recovering(&path, &clock)
+        .unwrap()
+        .with_writer_id("node-a");
+    let mut third = entry("third");
+    third.timestamp = UnixNanos(1_700_000_000_000_000_002);
+    logger.record(third).unwrap();
+    drop(logger);
+
+    verify_chain_with(&path, &VerifyOptions::strict()).unwrap();
+    let marker = AuditReader::open(&path).unwrap().nth(2).unwrap().unwrap();
+    assert_eq!(marker.action, RECOVERY_ACTION);
+    assert_eq!(marker.sequence, Some(2));
+    assert_eq!(marker.writer.as_deref(), Some("node-a"));
+    cleanup(&path);
+}
+
+#[test]
+fn art_32_mid_file_tamper_is_not_repaired() {
+    let path = tmp_path("tamper");
+    write_with_torn_tail(&path);
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replacen("\"first\"", "\"FIRST\"", 1)).unwrap();
+    let before = std::fs::read(&path).unwrap();
+
+    let err = recover_torn_tail(&path, &MockClock::new(0, 0)).unwrap_err();
+    assert!(matches!(err, AuditError::ChainBroken { .. }), "got {err:?}");
+    assert_eq!(std::fs::read(&path).unwrap(), before);
+    assert!(!quarantine_path(&path).exists());
+    cleanup(&path);
+}
+
+#[test]
+fn art_9_clean_log_is_left_alone() {
+    let path = tmp_path("clean");
+    let logger = FileAuditLogger::open(&path).unwrap();
+    logger.record(entry("only")).unwrap();
+    drop(logger);
+    let before = std::fs::read(&path).unwrap();
+
+    assert!(
+        recover_torn_tail(&path, &MockClock::new(0, 0))
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), before);
+    assert!(!quarantine_path(&path).exists());
+    cleanup(&path);
+}
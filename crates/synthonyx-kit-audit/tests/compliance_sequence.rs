@@ -0,0 +1,219 @@
+//! Compliance contract tests for sequence numbers and semantic chain checks.
+//!
+//! References:
+//! - DORA Art. 32 (audit-log integrity — a valid hash chain must also be a
+//!   plausible record: ordered in time and produced by one writer).
+//! - DORA Art. 9 (audit-trail retention — logs written before sequence
+//!   numbers existed still verify).
+//!
+//! Contracts enforced:
+//! - Sinks stamp each entry with its zero-based position in the log,
+//!   continuing across reopen and across segments, and with their writer
+//!   id; `VerifyOptions::strict` accepts such a log.
+//! - A skipped sequence number, a timestamp going backwards, and an entry
+//!   from a second writer each fail with their own `AuditError` variant,
+//!   while plain `verify_chain` still accepts the log.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    AuditReader, EntryFormat, FileAuditLogger, RotationPolicy, SegmentedAuditLogger, VerifyOptions,
+    encode_entry, verify_chain, verify_chain_with,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-sequence-{pid}-{seq}-{name}"))
+}
+
+fn entry(timestamp: u128) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(timestamp),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed("act"),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::new(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+#[test]
+fn art_32_sequence_continues_across_reopen() {
+    let path = tmp_path("reopen.jsonl");
+    for ts in [1, 2] {
+        let logger = FileAuditLogger::open(&path)
+            .unwrap()
+            .with_writer_id("node-a");
+        logger.record(entry(ts)).unwrap();
+        logger.record(entry(ts)).unwrap();
+    }
+
+    let entries: Vec<_> = AuditReader::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    let sequences: Vec<_> = entries.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, [Some(0), Some(1), Some(2), Some(3)]);
+    assert!(
+        entries
+            .iter()
+            .all(|e| e.writer.as_deref() == Some("node-a"))
+    );
+
+    let head = verify_chain(&path).unwrap();
+    assert_eq!(
+        verify_chain_with(&path, &VerifyOptions::strict()).unwrap(),
+        head
+    );
+    verify_chain_with(&path, &VerifyOptions::new().expect_writer(Some("node-a"))).unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_32_skipped_sequence_is_a_gap() {
+    // Correctly chained, but entry 1 claims position 2.
+    let path = tmp_path("gap.jsonl");
+    let mut file = std::fs::File::create(&path).unwrap();
+    let mut prev = [0u8; 32];
+    for sequence in [0, 2, 3] {
+        let mut e = entry(1);
+        e.prev_hash = prev;
+        e.sequence = Some(sequence);
+        let line = encode_entry(&e, EntryFormat::CURRENT).unwrap();
+        prev = *blake3::hash(line.as_bytes()).as_bytes();
+        writeln!(file, "{line}").unwrap();
+    }
+    drop(file);
+
+    verify_chain(&path).unwrap();
+    let err = verify_chain_with(&path, &VerifyOptions::new().check_sequence()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::SequenceGap {
+                expected: 1,
+                actual: 2
+            }
+        ),
+        "got {err:?}"
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_32_timestamp_regression_is_detected() {
+    let path = tmp_path("clock.jsonl");
+    let logger = FileAuditLogger::open(&path).unwrap();
+    for ts in [10, 10, 5, 20] {
+        logger.record(entry(ts)).unwrap();
+    }
+    drop(logger);
+
+    verify_chain_with(&path, &VerifyOptions::new().check_sequence()).unwrap();
+    let err = verify_chain_with(&path, &VerifyOptions::new().check_timestamps()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::TimestampRegression {
+                previous: UnixNanos(10),
+                actual: UnixNanos(5)
+            }
+        ),
+        "got {err:?}"
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_32_second_writer_is_detected() {
+    let path = tmp_path("writers.jsonl");
+    let a = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_writer_id("node-a");
+    a.record(entry(1)).unwrap();
+    a.record(entry(2)).unwrap();
+    drop(a);
+    let b = FileAuditLogger::open(&path)
+        .unwrap()
+        .with_writer_id("node-b");
+    b.record(entry(3)).unwrap();
+    drop(b);
+
+    verify_chain_with(&path, &VerifyOptions::new().check_sequence()).unwrap();
+    let err = verify_chain_with(&path, &VerifyOptions::strict()).unwrap_err();
+    match err {
+        AuditError::WriterMismatch { expected, actual } => {
+            assert_eq!(expected.as_deref(), Some("node-a"));
+            assert_eq!(actual.as_deref(), Some("node-b"));
+        }
+        other => panic!("got {other:?}"),
+    }
+    let err =
+        verify_chain_with(&path, &VerifyOptions::new().expect_writer(None::<String>)).unwrap_err();
+    assert!(
+        matches!(err, AuditError::WriterMismatch { .. }),
+        "got {err:?}"
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_9_segments_continue_the_sequence() {
+    let dir = tmp_path("segments");
+    let policy = RotationPolicy {
+        max_bytes: Some(1),
+        max_age_nanos: None,
+    };
+    let logger = SegmentedAuditLogger::open(&dir, policy)
+        .unwrap()
+        .with_writer_id("node-a");
+    for ts in 0..3 {
+        logger.record(entry(ts)).unwrap();
+    }
+    let manifest = logger.manifest();
+    drop(logger);
+
+    // The active segment alone, starting from the manifest head.
+    let sealed: u64 = manifest.segments.iter().map(|s| s.entries).sum();
+    assert_eq!(sealed, 2);
+    let options = VerifyOptions::strict()
+        .start(manifest.head())
+        .first_sequence(sealed);
+    verify_chain_with(manifest.active_path(&dir), &options).unwrap();
+    let err = verify_chain_with(
+        manifest.active_path(&dir),
+        &VerifyOptions::strict().start(manifest.head()),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AuditError::SequenceGap {
+                expected: 0,
+                actual: 2
+            }
+        ),
+        "got {err:?}"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
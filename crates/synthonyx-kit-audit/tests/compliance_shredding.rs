@@ -0,0 +1,151 @@
+//! Compliance contract tests for crypto-shredding of data subjects.
+//!
+//! References:
+//! - GDPR Art. 17 (right to erasure — destroying a subject's key makes
+//!   their audit entries unlinkable to them).
+//! - GDPR Art. 5(1)(c) (data minimisation — subjects are never written to
+//!   the log in clear).
+//! - DORA Art. 32 (audit-log integrity — erasure does not touch the chain).
+//!
+//! Contracts enforced:
+//! - Neither the subject nor a shredded field value appears in the log
+//!   bytes; the same subject maps to the same token while its key exists.
+//! - Erasing the key leaves the chain verifying, removes the token lookup,
+//!   and appends an attributed erasure entry carrying the old token.
+//! - A subject logged again after erasure gets a new, unrelated token.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_audit::{
+    AuditQuery, AuditReader, ERASURE_ACTION, FileAuditLogger, MemorySubjectKeyStore,
+    ShreddingAuditLogger, verify_chain,
+};
+use synthonyx_kit_core::{
+    AuditEntry, AuditLogger, AuditValue, BaseOrigin, CorrelationId, MockClock, OriginKind,
+    OriginSnapshot, Outcome, UnixNanos,
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-shred-{pid}-{seq}-{name}"))
+}
+
+fn entry(subject: &str, email: &str) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("accounts"),
+        action: Cow::Borrowed("update_profile"),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: Some(subject.to_string()),
+        fields: BTreeMap::from([
+            ("email".to_string(), AuditValue::Str(email.to_string())),
+            ("attempt".to_string(), AuditValue::UInt(1)),
+        ]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn logger(path: &PathBuf) -> ShreddingAuditLogger<FileAuditLogger, MemorySubjectKeyStore> {
+    ShreddingAuditLogger::new(
+        FileAuditLogger::open(path).unwrap(),
+        MemorySubjectKeyStore::new(),
+    )
+    .shred_field("email")
+}
+
+#[test]
+fn art_5_subject_and_shredded_fields_never_in_clear() {
+    let path = tmp_path("clear.jsonl");
+    let audit = logger(&path);
+    audit.record(entry("alice", "alice@example.com")).unwrap();
+    audit.record(entry("alice", "alice@example.com")).unwrap();
+    audit.record(entry("bob", "bob@example.com")).unwrap();
+
+    let bytes = std::fs::read_to_string(&path).unwrap();
+    assert!(!bytes.contains("alice"), "{bytes}");
+    assert!(!bytes.contains("example.com"), "{bytes}");
+
+    let token = audit.subject_token("alice").unwrap().unwrap();
+    let alice: Vec<_> = AuditReader::open(&path)
+        .unwrap()
+        .with_query(AuditQuery::new().subject(token))
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(alice.len(), 2);
+    assert!(matches!(alice[0].fields["email"], AuditValue::Hash(_)));
+    assert!(matches!(alice[0].fields["attempt"], AuditValue::UInt(1)));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_17_erasure_keeps_chain_and_is_recorded() {
+    let path = tmp_path("erase.jsonl");
+    let audit = logger(&path);
+    audit.record(entry("alice", "alice@example.com")).unwrap();
+    audit.record(entry("bob", "bob@example.com")).unwrap();
+    let token = audit.subject_token("alice").unwrap().unwrap();
+
+    let dpo = BaseOrigin::User {
+        principal: "dpo-1".to_string(),
+        correlation: CorrelationId([7u8; 16]),
+    };
+    let clock = MockClock::new(1_700_000_001_000_000_000, 0);
+    assert!(audit.erase_subject("alice", &dpo, &clock).unwrap());
+    assert_eq!(audit.subject_token("alice").unwrap(), None);
+    assert!(!audit.erase_subject("alice", &dpo, &clock).unwrap());
+
+    verify_chain(&path).unwrap();
+    let entries: Vec<_> = AuditReader::open(&path)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(entries.len(), 4);
+    let erasure = &entries[2];
+    assert_eq!(erasure.action, ERASURE_ACTION);
+    assert_eq!(erasure.subject.as_deref(), Some(token.as_str()));
+    assert_eq!(erasure.origin.principal.as_deref(), Some("dpo-1"));
+    assert_eq!(erasure.correlation_id, CorrelationId([7u8; 16]));
+    assert!(matches!(
+        erasure.fields["key_erased"],
+        AuditValue::Bool(true)
+    ));
+    assert_eq!(entries[3].subject, None);
+    assert!(matches!(
+        entries[3].fields["key_erased"],
+        AuditValue::Bool(false)
+    ));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn art_17_subject_logged_after_erasure_gets_new_token() {
+    let path = tmp_path("relog.jsonl");
+    let audit = logger(&path);
+    audit.record(entry("alice", "alice@example.com")).unwrap();
+    let before = audit.subject_token("alice").unwrap().unwrap();
+    let origin = BaseOrigin::<String>::System {
+        correlation: CorrelationId::ZERO,
+    };
+    audit
+        .erase_subject("alice", &origin, &MockClock::default())
+        .unwrap();
+
+    audit.record(entry("alice", "alice@example.com")).unwrap();
+    let after = audit.subject_token("alice").unwrap().unwrap();
+    assert_ne!(before, after);
+    let _ = std::fs::remove_file(&path);
+}
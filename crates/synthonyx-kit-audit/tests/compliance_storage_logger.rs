@@ -0,0 +1,218 @@
+//! Compliance contract tests for `StorageAuditLogger`.
+//!
+//! References:
+//! - DORA Art. 9 (audit-trail retention — the trail survives reopen and
+//!   lives in the replicated data store).
+//! - DORA Art. 32 (audit-log integrity — the storage chain is as tamper
+//!   evident as the file chain).
+//!
+//! Contracts enforced:
+//! - Entries round-trip through any `Backend`, and reopening extends the
+//!   original chain.
+//! - Tampering with a stored non-terminal entry fails verification.
+//! - Deleting entries from the end of the chain fails with
+//!   `AuditError::Truncated`.
+//! - Distinct prefixes hold independent chains in one backend.
+//! - `record` works from async code and plain threads with a backend whose
+//!   futures need the Tokio runtime.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use synthonyx_kit_audit::{StorageAuditLogger, verify_storage_chain};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, CorrelationId, OriginKind, OriginSnapshot, Outcome,
+    UnixNanos,
+};
+use synthonyx_kit_storage::{Backend, StorageError, StorageKey};
+
+#[derive(Clone, Default)]
+struct MemoryBackend(Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>);
+
+impl MemoryBackend {
+    fn keys_with(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        let mut keys: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+impl Backend for MemoryBackend {
+    async fn read(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.0.lock().unwrap().get(key.as_bytes()).cloned())
+    }
+    async fn write(&self, key: &StorageKey, value: &[u8]) -> Result<(), StorageError> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(key.as_bytes().to_vec(), value.to_vec());
+        Ok(())
+    }
+    async fn delete(&self, key: &StorageKey) -> Result<(), StorageError> {
+        self.0.lock().unwrap().remove(key.as_bytes());
+        Ok(())
+    }
+    async fn exists(&self, key: &StorageKey) -> Result<bool, StorageError> {
+        Ok(self.0.lock().unwrap().contains_key(key.as_bytes()))
+    }
+}
+
+/// A backend whose writes wait on a Tokio timer, like a network client:
+/// polling it outside the runtime panics.
+#[derive(Clone, Default)]
+struct TimedBackend(MemoryBackend);
+
+impl Backend for TimedBackend {
+    async fn read(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, StorageError> {
+        self.0.read(key).await
+    }
+    async fn write(&self, key: &StorageKey, value: &[u8]) -> Result<(), StorageError> {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        self.0.write(key, value).await
+    }
+    async fn delete(&self, key: &StorageKey) -> Result<(), StorageError> {
+        self.0.delete(key).await
+    }
+    async fn exists(&self, key: &StorageKey) -> Result<bool, StorageError> {
+        self.0.exists(key).await
+    }
+}
+
+fn entry(action: &'static str) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_000_000_000),
+        correlation_id: CorrelationId([1u8; 16]),
+        module: Cow::Borrowed("test"),
+        action: Cow::Borrowed(action),
+        origin: OriginSnapshot {
+            kind: OriginKind::System,
+            principal: None,
+            service: None,
+        },
+        outcome: Outcome::Success,
+        subject: None,
+        fields: BTreeMap::new(),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+fn prefix() -> StorageKey {
+    StorageKey::new(b"audit/".to_vec())
+}
+
+async fn write_three(backend: &MemoryBackend) -> [u8; 32] {
+    let logger = StorageAuditLogger::open(backend.clone(), prefix())
+        .await
+        .unwrap();
+    for action in ["a", "b", "c"] {
+        logger.record(entry(action)).unwrap();
+    }
+    logger.head().head_hash
+}
+
+#[tokio::test]
+async fn art_9_storage_chain_survives_reopen() {
+    let backend = MemoryBackend::default();
+    let head = write_three(&backend).await;
+    assert_eq!(
+        verify_storage_chain(&backend, &prefix()).await.unwrap(),
+        head
+    );
+
+    let logger = StorageAuditLogger::open(backend.clone(), prefix())
+        .await
+        .unwrap();
+    assert_eq!(logger.head().entries, 3);
+    logger.record(entry("d")).unwrap();
+    assert_eq!(logger.head().entries, 4);
+    verify_storage_chain(&backend, &prefix()).await.unwrap();
+}
+
+#[tokio::test]
+async fn art_32_tampered_entry_is_detected() {
+    let backend = MemoryBackend::default();
+    write_three(&backend).await;
+    let first = backend.keys_with(b"audit/e")[0].clone();
+    {
+        let mut map = backend.0.lock().unwrap();
+        let line = String::from_utf8(map[&first].clone()).unwrap();
+        map.insert(first, line.replacen("\"a\"", "\"A\"", 1).into_bytes());
+    }
+    let err = verify_storage_chain(&backend, &prefix()).await.unwrap_err();
+    assert!(matches!(err, AuditError::ChainBroken { .. }), "got {err:?}");
+}
+
+#[tokio::test]
+async fn art_9_deleted_tail_is_detected() {
+    let backend = MemoryBackend::default();
+    write_three(&backend).await;
+    let last = backend.keys_with(b"audit/e").pop().unwrap();
+    backend.0.lock().unwrap().remove(&last);
+
+    let err = StorageAuditLogger::open(backend.clone(), prefix())
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        matches!(
+            err,
+            AuditError::Truncated {
+                checkpoint_entries: 3,
+                log_entries: 2
+            }
+        ),
+        "got {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn art_32_prefixes_hold_independent_chains() {
+    let backend = MemoryBackend::default();
+    write_three(&backend).await;
+    let other = StorageKey::new(b"audit-b/".to_vec());
+    let logger = StorageAuditLogger::open(backend.clone(), other.clone())
+        .await
+        .unwrap();
+    assert_eq!(logger.head().entries, 0);
+    logger.record(entry("x")).unwrap();
+    verify_storage_chain(&backend, &other).await.unwrap();
+    verify_storage_chain(&backend, &prefix()).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn art_9_record_drives_runtime_bound_backends() {
+    let backend = TimedBackend::default();
+    let logger = Arc::new(
+        StorageAuditLogger::open(backend.clone(), prefix())
+            .await
+            .unwrap(),
+    );
+    logger.record(entry("from-task")).unwrap();
+    let from_thread = Arc::clone(&logger);
+    std::thread::spawn(move || from_thread.record(entry("from-thread")))
+        .join()
+        .unwrap()
+        .unwrap();
+    let from_spawned = Arc::clone(&logger);
+    tokio::spawn(async move { from_spawned.record(entry("from-spawned")) })
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(logger.head().entries, 3);
+    assert_eq!(
+        verify_storage_chain(&backend.0, &prefix()).await.unwrap(),
+        logger.head().head_hash
+    );
+}
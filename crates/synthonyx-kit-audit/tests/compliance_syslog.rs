@@ -0,0 +1,261 @@
+//! Compliance contract tests for the syslog and journald sinks.
+//!
+//! References:
+//! - DORA Art. 10 (detection — audit events reach the SOC's monitoring
+//!   pipeline promptly and in a form it can index).
+//! - DORA Art. 17 (incident investigation — the correlation id travels
+//!   with every forwarded entry).
+//!
+//! Contracts enforced:
+//! - Each entry is one RFC 5424 message whose structured data carries its
+//!   correlation id, module, action, outcome, origin and fields.
+//! - `PRI` combines the facility with a severity mapped from the outcome.
+//! - UDP and Unix datagrams carry one message each; TCP and byte streams
+//!   use RFC 6587 octet-counting framing.
+//! - A TCP collector that stops reading fails entries within the write
+//!   timeout instead of blocking the caller indefinitely.
+//! - The journald sink sends native-protocol fields to the journal socket.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, UdpSocket};
+#[cfg(unix)]
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use synthonyx_kit_audit::{Facility, SyslogAuditLogger, SyslogSeverity};
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditLogger, AuditValue, CorrelationId, OriginKind, OriginSnapshot,
+    Outcome, Severity, UnixNanos,
+};
+
+#[cfg(unix)]
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(unix)]
+fn tmp_path(name: &str) -> PathBuf {
+    let pid = std::process::id();
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("synthonyx-syslog-{pid}-{seq}-{name}"))
+}
+
+fn entry(outcome: Outcome) -> AuditEntry {
+    AuditEntry {
+        timestamp: UnixNanos(1_700_000_000_123_456_789),
+        correlation_id: CorrelationId([0xab; 16]),
+        module: Cow::Borrowed("accounts"),
+        action: Cow::Borrowed("register_user"),
+        origin: OriginSnapshot {
+            kind: OriginKind::User,
+            principal: Some("u\"1]".into()),
+            service: None,
+        },
+        outcome,
+        subject: Some("subject-7".into()),
+        fields: BTreeMap::from([("attempt".to_string(), AuditValue::UInt(2))]),
+        prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
+    }
+}
+
+/// Split an RFC 6587 octet-counted stream into messages.
+fn unframe(mut bytes: &[u8]) -> Vec<String> {
+    let mut out = Vec::new();
+    while !bytes.is_empty() {
+        let space = bytes.iter().position(|&b| b == b' ').unwrap();
+        let len: usize = std::str::from_utf8(&bytes[..space])
+            .unwrap()
+            .parse()
+            .unwrap();
+        let end = space + 1 + len;
+        out.push(String::from_utf8(bytes[space + 1..end].to_vec()).unwrap());
+        bytes = &bytes[end..];
+    }
+    out
+}
+
+#[test]
+fn art_10_udp_message_is_rfc5424() {
+    let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let audit = SyslogAuditLogger::udp(collector.local_addr().unwrap())
+        .unwrap()
+        .with_hostname("host-1")
+        .with_app_name("payments");
+    audit.record(entry(Outcome::Success)).unwrap();
+
+    let mut buf = [0u8; 4096];
+    let n = collector.recv(&mut buf).unwrap();
+    let message = std::str::from_utf8(&buf[..n]).unwrap();
+    let pid = std::process::id();
+    let expected = format!(
+        concat!(
+            "<110>1 2023-11-14T22:13:20.123456Z host-1 payments {pid} register_user ",
+            "[audit@32473 correlation_id=\"abababababababababababababababab\" ",
+            "module=\"accounts\" action=\"register_user\" outcome=\"success\" ",
+            "origin_kind=\"user\" principal=\"u\\\"1\\]\" subject=\"subject-7\" ",
+            "timestamp=\"1700000000123456789\" field.attempt=\"2\"] ",
+            "accounts.register_user success",
+        ),
+        pid = pid
+    );
+    assert_eq!(message, expected);
+}
+
+#[test]
+fn art_10_severity_follows_outcome_and_facility() {
+    let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let audit = SyslogAuditLogger::udp(collector.local_addr().unwrap()).unwrap();
+    let mut buf = [0u8; 4096];
+    for (outcome, pri) in [
+        (Outcome::Success, "<110>"),
+        (Outcome::Denied, "<108>"),
+        (Outcome::Error, "<107>"),
+    ] {
+        audit.record(entry(outcome)).unwrap();
+        let n = collector.recv(&mut buf).unwrap();
+        assert!(buf[..n].starts_with(pri.as_bytes()), "{outcome:?}");
+    }
+
+    let audit = SyslogAuditLogger::udp(collector.local_addr().unwrap())
+        .unwrap()
+        .with_facility(Facility::Local3)
+        .with_severity(|_| Severity::Critical.into());
+    audit.record(entry(Outcome::Success)).unwrap();
+    let n = collector.recv(&mut buf).unwrap();
+    assert!(buf[..n].starts_with(b"<154>"));
+    assert_eq!(
+        SyslogSeverity::from(Severity::Security),
+        SyslogSeverity::Error
+    );
+}
+
+#[test]
+fn art_10_tcp_uses_octet_counting() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let audit = SyslogAuditLogger::tcp(listener.local_addr().unwrap()).unwrap();
+    let (mut conn, _) = listener.accept().unwrap();
+    audit.record(entry(Outcome::Success)).unwrap();
+    audit.record(entry(Outcome::Denied)).unwrap();
+    drop(audit);
+
+    let mut bytes = Vec::new();
+    conn.read_to_end(&mut bytes).unwrap();
+    let messages = unframe(&bytes);
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].starts_with("<110>1 "));
+    assert!(messages[1].starts_with("<108>1 "));
+    assert!(messages[1].ends_with("accounts.register_user denied"));
+}
+
+#[test]
+fn art_10_tcp_write_to_stalled_collector_times_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let timeout = Duration::from_millis(200);
+    let audit =
+        SyslogAuditLogger::tcp_with_timeout(listener.local_addr().unwrap(), timeout).unwrap();
+    // Accepted but never read: the socket buffers fill and writes stall.
+    let (_conn, _) = listener.accept().unwrap();
+    let mut big = entry(Outcome::Success);
+    big.fields
+        .insert("payload".into(), AuditValue::Str("x".repeat(64 * 1024)));
+
+    let mut failed = None;
+    for _ in 0..4096 {
+        let started = Instant::now();
+        if let Err(e) = audit.record(big.clone()) {
+            failed = Some((e, started.elapsed()));
+            break;
+        }
+    }
+    let (err, elapsed) = failed.expect("writes to a stalled collector never failed");
+    assert!(matches!(err, AuditError::Io(_)), "got {err:?}");
+    assert!(elapsed < timeout * 10, "blocked for {elapsed:?}");
+}
+
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn art_10_stream_transport_for_tls() {
+    let buf = SharedBuf::default();
+    let audit = SyslogAuditLogger::stream(buf.clone());
+    audit.record(entry(Outcome::Success)).unwrap();
+    let messages = unframe(&buf.0.lock().unwrap());
+    assert_eq!(messages, [audit.format(&entry(Outcome::Success))]);
+}
+
+#[cfg(unix)]
+#[test]
+fn art_10_unix_datagram() {
+    use std::os::unix::net::UnixDatagram;
+
+    let path = tmp_path("dev-log.sock");
+    let collector = UnixDatagram::bind(&path).unwrap();
+    let audit = SyslogAuditLogger::unix(&path).unwrap();
+    audit.record(entry(Outcome::Error)).unwrap();
+
+    let mut buf = [0u8; 4096];
+    let n = collector.recv(&mut buf).unwrap();
+    let message = std::str::from_utf8(&buf[..n]).unwrap();
+    assert!(message.starts_with("<107>1 "), "{message}");
+    assert!(message.contains("outcome=\"error\""), "{message}");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(unix)]
+#[test]
+fn art_17_journald_native_fields() {
+    use std::os::unix::net::UnixDatagram;
+    use synthonyx_kit_audit::JournaldAuditLogger;
+
+    let path = tmp_path("journal.sock");
+    let journal = UnixDatagram::bind(&path).unwrap();
+    let audit = JournaldAuditLogger::new()
+        .unwrap()
+        .with_socket(&path)
+        .with_identifier("payments");
+    let mut e = entry(Outcome::Denied);
+    e.fields
+        .insert("note".to_string(), AuditValue::Str("line 1\nline 2".into()));
+    audit.record(e).unwrap();
+
+    let mut buf = [0u8; 4096];
+    let n = journal.recv(&mut buf).unwrap();
+    let datagram = &buf[..n];
+    let text = String::from_utf8_lossy(datagram);
+    for field in [
+        "MESSAGE=accounts.register_user denied\n",
+        "PRIORITY=4\n",
+        "SYSLOG_FACILITY=13\n",
+        "SYSLOG_IDENTIFIER=payments\n",
+        "SYNTHONYX_CORRELATION_ID=abababababababababababababababab\n",
+        "SYNTHONYX_OUTCOME=denied\n",
+        "SYNTHONYX_PRINCIPAL=u\"1]\n",
+        "SYNTHONYX_FIELD_ATTEMPT=2\n",
+    ] {
+        assert!(text.contains(field), "missing {field:?} in {text:?}");
+    }
+    let mut binary = b"SYNTHONYX_FIELD_NOTE\n".to_vec();
+    binary.extend_from_slice(&13u64.to_le_bytes());
+    binary.extend_from_slice(b"line 1\nline 2\n");
+    assert!(
+        datagram.windows(binary.len()).any(|w| w == binary),
+        "{text:?}"
+    );
+    let _ = std::fs::remove_file(&path);
+}
@@ -44,6 +44,8 @@ fn entry_for(action: String) -> AuditEntry {
         subject: None,
         fields: BTreeMap::from([("seq".to_string(), AuditValue::UInt(0))]),
         prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
     }
 }
 
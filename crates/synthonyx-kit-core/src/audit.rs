@@ -8,9 +8,11 @@
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
+use crate::config::Config;
 use crate::correlation::CorrelationId;
-use crate::origin::OriginKind;
-use crate::time::UnixNanos;
+use crate::error::DispatchError;
+use crate::origin::{OriginKind, OriginTrait};
+use crate::time::{TimeSource, UnixNanos};
 
 /// Outcome of an audited dispatch.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -24,6 +26,20 @@ pub enum Outcome {
     Error,
 }
 
+impl Outcome {
+    /// The outcome a dispatch result is audited as: `Ok` is
+    /// [`Self::Success`]; [`DispatchError::BadOrigin`] and
+    /// [`DispatchError::Precondition`] are [`Self::Denied`]; any other error
+    /// is [`Self::Error`].
+    pub fn of<O>(result: Result<O, &DispatchError>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(DispatchError::BadOrigin { .. } | DispatchError::Precondition(_)) => Self::Denied,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
 /// Serializable, low-cardinality snapshot of an origin for audit storage.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -37,6 +53,25 @@ pub struct OriginSnapshot {
     pub service: Option<Cow<'static, str>>,
 }
 
+impl OriginSnapshot {
+    /// Snapshot `origin`: its kind, its principal's `Display` form, and its
+    /// service name.
+    ///
+    /// The `Display` form is what gets stored, so it should be a stable,
+    /// non-PII identifier. Origins whose principal has no `Display` impl
+    /// build the snapshot by hand.
+    pub fn of<O: OriginTrait>(origin: &O) -> Self
+    where
+        O::Principal: core::fmt::Display,
+    {
+        Self {
+            kind: origin.kind(),
+            principal: origin.principal().map(ToString::to_string),
+            service: origin.service(),
+        }
+    }
+}
+
 /// Safe-to-log value type for audit entry fields.
 ///
 /// Deliberately restricted to non-PII primitive shapes. To log fields derived
@@ -58,6 +93,36 @@ pub enum AuditValue {
     Hash(String),
 }
 
+impl From<&str> for AuditValue {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+impl From<String> for AuditValue {
+    fn from(s: String) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl From<i64> for AuditValue {
+    fn from(i: i64) -> Self {
+        Self::Int(i)
+    }
+}
+
+impl From<u64> for AuditValue {
+    fn from(u: u64) -> Self {
+        Self::UInt(u)
+    }
+}
+
+impl From<bool> for AuditValue {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
 /// A single audit log entry.
 ///
 /// Entries are appended in order. `prev_hash` carries the BLAKE3 hash of the
@@ -87,6 +152,71 @@ pub struct AuditEntry {
     pub fields: BTreeMap<String, AuditValue>,
     /// BLAKE3-32 hash of the previous entry's canonical encoding.
     pub prev_hash: [u8; 32],
+    /// Zero-based position of the entry in its log, assigned by the sink.
+    /// `None` on entries written before sinks assigned sequence numbers.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub sequence: Option<u64>,
+    /// Identity of the sink instance that wrote the entry, if it was
+    /// configured with one. Assigned by the sink.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub writer: Option<String>,
+}
+
+impl AuditEntry {
+    /// Start an entry for a dispatch of `action` in the RTM configured by
+    /// `T`.
+    ///
+    /// Takes the timestamp from `time`, the module from `T::MODULE`, and the
+    /// correlation id and [`OriginSnapshot`] from `origin`. The outcome
+    /// defaults to [`Outcome::Success`]; there is no subject and no fields;
+    /// `prev_hash`, `sequence` and `writer` are left for the logger to fill
+    /// in.
+    pub fn for_dispatch<T: Config>(
+        time: &T::Time,
+        origin: &T::Origin,
+        action: impl Into<Cow<'static, str>>,
+    ) -> Self
+    where
+        <T::Origin as OriginTrait>::Principal: core::fmt::Display,
+    {
+        Self {
+            timestamp: time.now(),
+            correlation_id: origin.correlation_id(),
+            module: Cow::Borrowed(T::MODULE),
+            action: action.into(),
+            origin: OriginSnapshot::of(origin),
+            outcome: Outcome::Success,
+            subject: None,
+            fields: BTreeMap::new(),
+            prev_hash: [0u8; 32],
+            sequence: None,
+            writer: None,
+        }
+    }
+
+    /// Set the outcome.
+    pub fn with_outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    /// Link the entry to a (pseudonymous) data subject.
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Add a field, replacing any earlier value under `key`.
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<AuditValue>) -> Self {
+        self.fields.insert(key.into(), value.into());
+        self
+    }
 }
 
 /// Errors produced by an [`AuditLogger`] implementation.
@@ -106,6 +236,109 @@ pub enum AuditError {
         /// Actual previous hash on the entry being appended, hex-encoded.
         actual: String,
     },
+    /// A segment manifest is malformed or disagrees with the segments on
+    /// disk.
+    #[error("audit segment manifest error: {0}")]
+    Manifest(String),
+    /// A checkpoint signature is invalid, its key is unknown, or the
+    /// checkpoint sidecar is inconsistent.
+    #[error("audit checkpoint error: {0}")]
+    Checkpoint(String),
+    /// The log holds fewer entries than a signed checkpoint attests to.
+    #[error(
+        "audit log truncated: checkpoint covers {checkpoint_entries} entries, log has {log_entries}"
+    )]
+    Truncated {
+        /// Entry count recorded in the checkpoint.
+        checkpoint_entries: u64,
+        /// Entry count found in the log.
+        log_entries: u64,
+    },
+    /// A sidecar index covers more of the log than the log now holds: the
+    /// log was shortened or replaced after the index was built.
+    #[error(
+        "audit index stale: index covers {index_entries} entries ({index_bytes} bytes), log has {log_entries} ({log_bytes} bytes)"
+    )]
+    StaleIndex {
+        /// Entry count the index covers.
+        index_entries: u64,
+        /// Byte length of the log the index covers.
+        index_bytes: u64,
+        /// Entry count found in the log.
+        log_entries: u64,
+        /// Byte length of the log.
+        log_bytes: u64,
+    },
+    /// A Merkle inclusion or consistency proof is malformed or does not
+    /// verify.
+    #[error("audit proof error: {0}")]
+    Proof(String),
+    /// The logger has been shut down, was opened read-only, or its
+    /// background writer has stopped after an earlier failure, and accepts
+    /// no further entries.
+    #[error("audit logger closed: {0}")]
+    Closed(String),
+    /// A storage backend holding the audit trail failed.
+    #[error("audit storage backend error: {0}")]
+    Backend(String),
+    /// An entry's sequence number is not its position in the log: entries
+    /// were removed, duplicated, or written by two sinks at once.
+    #[error("audit sequence discontinuity: expected {expected}, got {actual}")]
+    SequenceGap {
+        /// The entry's position in the log.
+        expected: u64,
+        /// The sequence number it carries.
+        actual: u64,
+    },
+    /// An entry is timestamped earlier than the entry before it.
+    #[error(
+        "audit timestamp regression: {} ns follows {} ns",
+        actual.0,
+        previous.0
+    )]
+    TimestampRegression {
+        /// Timestamp of the preceding entry.
+        previous: UnixNanos,
+        /// The earlier timestamp on the following entry.
+        actual: UnixNanos,
+    },
+    /// An entry was written by a different sink than the log belongs to.
+    #[error("audit writer mismatch: expected {expected:?}, got {actual:?}")]
+    WriterMismatch {
+        /// The log's writer identity.
+        expected: Option<String>,
+        /// The writer recorded on the entry.
+        actual: Option<String>,
+    },
+    /// Fewer sinks than required accepted the entry.
+    #[error("audit quorum not met: {succeeded} of {required} required sinks succeeded ({})", errors.join("; "))]
+    Quorum {
+        /// Number of sinks that had to accept the entry.
+        required: usize,
+        /// Number of sinks that did.
+        succeeded: usize,
+        /// The error reported by each failing sink, in sink order.
+        errors: Vec<String>,
+    },
+    /// Another process holds the log's write lock.
+    #[error("audit log locked by another writer: {0}")]
+    Locked(String),
+    /// A queued sink is full and did not accept the entry. Nothing was
+    /// recorded; the caller decides whether to retry or refuse the
+    /// operation.
+    #[error("audit queue full: {depth} of {capacity} entries pending")]
+    Backpressure {
+        /// Entries waiting in the queue when this one was refused, not
+        /// counting one the sink is writing.
+        depth: usize,
+        /// The queue's capacity.
+        capacity: usize,
+    },
+    /// An entry carries personal data its sink is configured to refuse.
+    /// The message names the kind of data and where it was found, never
+    /// the data itself.
+    #[error("audit entry rejected: {0}")]
+    PiiDetected(String),
 }
 
 /// Sink for audit entries.
@@ -114,8 +347,21 @@ pub enum AuditError {
 /// - persist or transmit each entry durably enough for the deployment's
 ///   evidence-retention requirements (DORA Article 9);
 /// - never silently drop entries;
-/// - return [`AuditError`] rather than panicking on backpressure.
+/// - return [`AuditError`] rather than panicking or blocking indefinitely
+///   on backpressure ([`AuditError::Backpressure`] when a queue is full).
 pub trait AuditLogger: Send + Sync + 'static {
     /// Append `entry` to the log.
     fn record(&self, entry: AuditEntry) -> Result<(), AuditError>;
 }
+
+impl<L: AuditLogger + ?Sized> AuditLogger for Box<L> {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        (**self).record(entry)
+    }
+}
+
+impl<L: AuditLogger + ?Sized> AuditLogger for std::sync::Arc<L> {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        (**self).record(entry)
+    }
+}
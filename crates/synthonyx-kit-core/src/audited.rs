@@ -0,0 +1,121 @@
+//! Automatic audit of every dispatch, failures included.
+
+use crate::audit::{AuditEntry, AuditLogger, Outcome};
+use crate::config::Config;
+use crate::dispatch::{Dispatch, DispatchAsyncSend};
+use crate::error::DispatchError;
+use crate::origin::OriginTrait;
+
+/// What [`Audited`] does when the audit sink refuses an entry.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AuditFailurePolicy {
+    /// Fail the dispatch with [`DispatchError::Audit`]. The default: an
+    /// operation that cannot be evidenced is reported as failed.
+    #[default]
+    FailClosed,
+    /// Return the dispatch's own result and discard the audit error. Only
+    /// for sinks that report their own failures.
+    FailOpen,
+}
+
+/// Wraps a dispatch so every call — successful, denied or failed — records
+/// one [`AuditEntry`] through `T::Audit`, timestamped by `T::Time`.
+///
+/// The entry is built with [`AuditEntry::for_dispatch`] under the
+/// dispatch's [`Dispatch::action`] name, then passed through
+/// [`Dispatch::annotate_audit`] so the call can add its subject and fields.
+/// The outcome is derived with [`Outcome::of`]; a failed call also records
+/// its [`DispatchError::kind`] under the `error` field (never the message,
+/// which may carry input data).
+///
+/// Under [`AuditFailurePolicy::FailClosed`], a call that succeeded but
+/// could not be audited returns [`DispatchError::Audit`] and its output is
+/// dropped; a call that had already failed keeps its own error. The
+/// operation's effects are not undone, so pair this with storage that
+/// commits only once the dispatch returns `Ok`.
+pub struct Audited<'a, T: Config, D> {
+    dispatch: D,
+    audit: &'a T::Audit,
+    time: &'a T::Time,
+    on_failure: AuditFailurePolicy,
+}
+
+impl<'a, T: Config, D> Audited<'a, T, D> {
+    /// Audit each call of `dispatch` into `audit`, timestamped by `time`.
+    pub fn new(dispatch: D, audit: &'a T::Audit, time: &'a T::Time) -> Self {
+        Self {
+            dispatch,
+            audit,
+            time,
+            on_failure: AuditFailurePolicy::default(),
+        }
+    }
+
+    /// Choose what happens when the sink refuses an entry.
+    pub fn with_failure_policy(mut self, policy: AuditFailurePolicy) -> Self {
+        self.on_failure = policy;
+        self
+    }
+
+    /// The wrapped dispatch.
+    pub fn inner(&self) -> &D {
+        &self.dispatch
+    }
+
+    /// Complete `entry` from `result`, record it, and apply the failure
+    /// policy.
+    fn finish<O>(
+        &self,
+        entry: AuditEntry,
+        result: Result<O, DispatchError>,
+    ) -> Result<O, DispatchError> {
+        let mut entry = entry.with_outcome(Outcome::of(result.as_ref()));
+        if let Err(e) = &result {
+            entry = entry.with_field("error", e.kind());
+        }
+        match self.audit.record(entry) {
+            Ok(()) => result,
+            Err(e) => match (self.on_failure, result) {
+                (AuditFailurePolicy::FailClosed, Ok(_)) => Err(DispatchError::Audit(e)),
+                (_, result) => result,
+            },
+        }
+    }
+}
+
+impl<T: Config, D: Dispatch<T>> Dispatch<T> for Audited<'_, T, D>
+where
+    <T::Origin as OriginTrait>::Principal: core::fmt::Display,
+{
+    type Output = D::Output;
+
+    fn call(&self, origin: T::Origin) -> Result<D::Output, DispatchError> {
+        let entry = AuditEntry::for_dispatch::<T>(self.time, &origin, self.dispatch.action());
+        let entry = self.dispatch.annotate_audit(entry);
+        let result = self.dispatch.call(origin);
+        self.finish(entry, result)
+    }
+
+    fn action(&self) -> &'static str {
+        self.dispatch.action()
+    }
+}
+
+impl<T: Config, D: DispatchAsyncSend<T> + Sync> DispatchAsyncSend<T> for Audited<'_, T, D>
+where
+    <T::Origin as OriginTrait>::Principal: core::fmt::Display,
+{
+    type Output = D::Output;
+
+    async fn call(&self, origin: T::Origin) -> Result<D::Output, DispatchError> {
+        let action = DispatchAsyncSend::action(&self.dispatch);
+        let entry = AuditEntry::for_dispatch::<T>(self.time, &origin, action);
+        let entry = DispatchAsyncSend::annotate_audit(&self.dispatch, entry);
+        let result = DispatchAsyncSend::call(&self.dispatch, origin).await;
+        self.finish(entry, result)
+    }
+
+    fn action(&self) -> &'static str {
+        DispatchAsyncSend::action(&self.dispatch)
+    }
+}
@@ -0,0 +1,413 @@
+//! Authorization — who may perform which dispatch.
+//!
+//! A [`Policy`] looks at an [`AccessRequest`] (the origin's principal and
+//! kind, the module and action, and any attributes the call adds) and
+//! either decides or abstains. [`PolicySet`] combines policies deny-first
+//! and denies whatever no policy allows. Every outcome is a [`Decision`]
+//! naming the policy that made it, which [`Decision::annotate`] attaches
+//! to an audit entry.
+//!
+//! Two policies ship here: [`Rbac`] (roles granting permissions, assigned
+//! to principals or origin kinds) and [`Rule`] (attribute-based predicates
+//! over the request).
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use crate::audit::{AuditEntry, AuditLogger, AuditValue, Outcome};
+use crate::config::Config;
+use crate::dispatch::{DispatchContext, Interceptor};
+use crate::error::DispatchError;
+use crate::origin::{OriginKind, OriginTrait};
+
+/// One dispatch to authorize.
+pub struct AccessRequest<'a, T: Config> {
+    origin: &'a T::Origin,
+    action: &'static str,
+    attributes: BTreeMap<String, AuditValue>,
+}
+
+impl<'a, T: Config> AccessRequest<'a, T> {
+    /// A request by `origin` to perform `action` in [`Config::MODULE`].
+    pub fn new(origin: &'a T::Origin, action: &'static str) -> Self {
+        Self {
+            origin,
+            action,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Add an attribute of the call (a tenant, an amount, a resource
+    /// owner) for attribute-based rules, replacing any earlier value.
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<AuditValue>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// The requesting origin.
+    pub fn origin(&self) -> &'a T::Origin {
+        self.origin
+    }
+
+    /// The origin's principal, if authenticated.
+    pub fn principal(&self) -> Option<&'a <T::Origin as OriginTrait>::Principal> {
+        self.origin.principal()
+    }
+
+    /// The origin's kind.
+    pub fn kind(&self) -> OriginKind {
+        self.origin.kind()
+    }
+
+    /// The module being called, [`Config::MODULE`].
+    pub fn module(&self) -> &'static str {
+        T::MODULE
+    }
+
+    /// The action being called.
+    pub fn action(&self) -> &'static str {
+        self.action
+    }
+
+    /// An attribute added with [`Self::with_attribute`].
+    pub fn attribute(&self, key: &str) -> Option<&AuditValue> {
+        self.attributes.get(key)
+    }
+}
+
+/// Whether a [`Decision`] allows or denies.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Effect {
+    /// The request may proceed.
+    Allow,
+    /// The request is refused.
+    Deny,
+}
+
+impl Effect {
+    /// `"allow"` or `"deny"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+/// The outcome of authorizing one request, and which policy produced it.
+///
+/// `policy` and `reason` are recorded in audit entries, so policies must
+/// keep them free of personal data: name roles and permissions, not
+/// principals.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Decision {
+    /// Allow or deny.
+    pub effect: Effect,
+    /// The policy (or rule) that decided; `"default"` when none applied.
+    pub policy: Cow<'static, str>,
+    /// Why it decided.
+    pub reason: Cow<'static, str>,
+}
+
+impl Decision {
+    /// An allowing decision.
+    pub fn allow(
+        policy: impl Into<Cow<'static, str>>,
+        reason: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            effect: Effect::Allow,
+            policy: policy.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// A denying decision.
+    pub fn deny(
+        policy: impl Into<Cow<'static, str>>,
+        reason: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            effect: Effect::Deny,
+            policy: policy.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Whether the request may proceed.
+    pub fn is_allowed(&self) -> bool {
+        self.effect == Effect::Allow
+    }
+
+    /// `Ok` if allowed, otherwise [`DispatchError::BadOrigin`] for
+    /// `action`.
+    pub fn into_result(self, action: &'static str) -> Result<(), DispatchError> {
+        match self.effect {
+            Effect::Allow => Ok(()),
+            Effect::Deny => Err(DispatchError::BadOrigin { action }),
+        }
+    }
+
+    /// Record this decision on `entry` as the `authz_effect`,
+    /// `authz_policy` and `authz_reason` fields; a denial also sets the
+    /// outcome to [`Outcome::Denied`].
+    pub fn annotate(&self, entry: AuditEntry) -> AuditEntry {
+        let entry = entry
+            .with_field("authz_effect", self.effect.name())
+            .with_field("authz_policy", self.policy.to_string())
+            .with_field("authz_reason", self.reason.to_string());
+        match self.effect {
+            Effect::Allow => entry,
+            Effect::Deny => entry.with_outcome(Outcome::Denied),
+        }
+    }
+}
+
+/// An authorization rule set evaluated against each [`AccessRequest`].
+pub trait Policy<T: Config>: Send + Sync + 'static {
+    /// Decide the request, or return `None` to abstain when the policy
+    /// does not apply to it.
+    fn evaluate(&self, request: &AccessRequest<'_, T>) -> Option<Decision>;
+}
+
+impl<T: Config, P: Policy<T> + ?Sized> Policy<T> for Arc<P> {
+    fn evaluate(&self, request: &AccessRequest<'_, T>) -> Option<Decision> {
+        (**self).evaluate(request)
+    }
+}
+
+/// Role-based access control.
+///
+/// A role grants permissions, each of the form `module.action`,
+/// `module.*` or `*`. Roles are assigned to principals (by the `Display`
+/// form of [`OriginTrait::Principal`]) or to every origin of an
+/// [`OriginKind`]. `Rbac` allows a request when one of the origin's roles
+/// grants it and abstains otherwise; it never denies on its own.
+#[derive(Clone, Debug, Default)]
+pub struct Rbac {
+    roles: BTreeMap<String, BTreeSet<String>>,
+    principals: BTreeMap<String, BTreeSet<String>>,
+    kinds: Vec<(OriginKind, String)>,
+}
+
+impl Rbac {
+    /// An empty role table, granting nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define `role` as granting `permissions`, adding to any it already
+    /// grants.
+    pub fn with_role<P: Into<String>>(
+        mut self,
+        role: impl Into<String>,
+        permissions: impl IntoIterator<Item = P>,
+    ) -> Self {
+        self.roles
+            .entry(role.into())
+            .or_default()
+            .extend(permissions.into_iter().map(Into::into));
+        self
+    }
+
+    /// Give `principal` the `role`.
+    pub fn assign(mut self, principal: impl Into<String>, role: impl Into<String>) -> Self {
+        self.principals
+            .entry(principal.into())
+            .or_default()
+            .insert(role.into());
+        self
+    }
+
+    /// Give every origin of `kind` the `role`.
+    pub fn assign_kind(mut self, kind: OriginKind, role: impl Into<String>) -> Self {
+        self.kinds.push((kind, role.into()));
+        self
+    }
+
+    /// Whether `role` grants `action` in `module`.
+    pub fn grants(&self, role: &str, module: &str, action: &str) -> bool {
+        self.roles
+            .get(role)
+            .is_some_and(|permissions| permissions.iter().any(|p| permits(p, module, action)))
+    }
+}
+
+fn permits(permission: &str, module: &str, action: &str) -> bool {
+    if permission == "*" {
+        return true;
+    }
+    match permission.split_once('.') {
+        Some((m, a)) => m == module && (a == "*" || a == action),
+        None => false,
+    }
+}
+
+impl<T: Config> Policy<T> for Rbac
+where
+    <T::Origin as OriginTrait>::Principal: core::fmt::Display,
+{
+    fn evaluate(&self, request: &AccessRequest<'_, T>) -> Option<Decision> {
+        let principal = request.principal().map(ToString::to_string);
+        let assigned = principal
+            .as_ref()
+            .and_then(|p| self.principals.get(p))
+            .into_iter()
+            .flatten();
+        let by_kind = self
+            .kinds
+            .iter()
+            .filter(|(kind, _)| *kind == request.kind())
+            .map(|(_, role)| role);
+        let role = assigned
+            .chain(by_kind)
+            .find(|role| self.grants(role, request.module(), request.action()))?;
+        Some(Decision::allow(
+            "rbac",
+            format!(
+                "role `{role}` grants `{}.{}`",
+                request.module(),
+                request.action()
+            ),
+        ))
+    }
+}
+
+type Predicate<T> = dyn Fn(&AccessRequest<'_, T>) -> bool + Send + Sync;
+
+/// An attribute-based rule: a named predicate over the request that
+/// allows or denies when it holds, and abstains otherwise.
+pub struct Rule<T: Config> {
+    name: Cow<'static, str>,
+    effect: Effect,
+    predicate: Box<Predicate<T>>,
+}
+
+impl<T: Config> Rule<T> {
+    /// Allow requests for which `predicate` holds.
+    pub fn allow(
+        name: impl Into<Cow<'static, str>>,
+        predicate: impl Fn(&AccessRequest<'_, T>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(name, Effect::Allow, predicate)
+    }
+
+    /// Deny requests for which `predicate` holds, whatever else allows
+    /// them.
+    pub fn deny(
+        name: impl Into<Cow<'static, str>>,
+        predicate: impl Fn(&AccessRequest<'_, T>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self::new(name, Effect::Deny, predicate)
+    }
+
+    fn new(
+        name: impl Into<Cow<'static, str>>,
+        effect: Effect,
+        predicate: impl Fn(&AccessRequest<'_, T>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            effect,
+            predicate: Box::new(predicate),
+        }
+    }
+}
+
+impl<T: Config> Policy<T> for Rule<T> {
+    fn evaluate(&self, request: &AccessRequest<'_, T>) -> Option<Decision> {
+        (self.predicate)(request).then(|| Decision {
+            effect: self.effect,
+            policy: self.name.clone(),
+            reason: Cow::Borrowed("rule matched"),
+        })
+    }
+}
+
+/// Policies combined deny-by-default.
+///
+/// Every policy is evaluated in order: the first `Deny` wins outright;
+/// otherwise the first `Allow` is returned; if every policy abstains the
+/// request is denied by the `"default"` policy.
+///
+/// A `PolicySet` is also an [`Interceptor`]: in a [`crate::Pipeline`] it
+/// authorizes each dispatch by origin and action before it runs and
+/// refuses it with [`DispatchError::BadOrigin`]. With
+/// [`Self::with_audit`], each denial is recorded there too, so refused
+/// calls leave a trace even though the dispatch never runs. Calls whose
+/// authorization depends on their arguments build an [`AccessRequest`]
+/// with attributes and call [`Self::authorize`] themselves.
+pub struct PolicySet<T: Config> {
+    policies: Vec<Arc<dyn Policy<T>>>,
+    audit: Option<Arc<dyn AuditLogger>>,
+}
+
+impl<T: Config> Default for PolicySet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config> PolicySet<T> {
+    /// An empty set, denying everything.
+    pub fn new() -> Self {
+        Self {
+            policies: Vec::new(),
+            audit: None,
+        }
+    }
+
+    /// Add `policy` after those added so far.
+    pub fn with_policy(mut self, policy: impl Policy<T>) -> Self {
+        self.policies.push(Arc::new(policy));
+        self
+    }
+
+    /// As an interceptor, record every denial into `audit`. If recording
+    /// fails the call is refused with [`DispatchError::Audit`] instead.
+    pub fn with_audit(mut self, audit: impl AuditLogger) -> Self {
+        self.audit = Some(Arc::new(audit));
+        self
+    }
+
+    /// Decide `request`.
+    pub fn authorize(&self, request: &AccessRequest<'_, T>) -> Decision {
+        let mut allowed = None;
+        for policy in &self.policies {
+            match policy.evaluate(request) {
+                Some(decision) if decision.effect == Effect::Deny => return decision,
+                Some(decision) => {
+                    allowed.get_or_insert(decision);
+                }
+                None => {}
+            }
+        }
+        allowed.unwrap_or_else(|| {
+            Decision::deny(
+                "default",
+                format!(
+                    "no policy allows `{}.{}`",
+                    request.module(),
+                    request.action()
+                ),
+            )
+        })
+    }
+}
+
+impl<T: Config> Interceptor<T> for PolicySet<T>
+where
+    <T::Origin as OriginTrait>::Principal: core::fmt::Display,
+{
+    fn before(&self, ctx: &DispatchContext<'_, T>) -> Result<(), DispatchError> {
+        let decision = self.authorize(&AccessRequest::new(ctx.origin(), ctx.action()));
+        if !decision.is_allowed() {
+            if let Some(audit) = &self.audit {
+                let entry = AuditEntry::for_dispatch::<T>(ctx.time(), ctx.origin(), ctx.action());
+                audit.record(decision.annotate(entry))?;
+            }
+        }
+        decision.into_result(ctx.action())
+    }
+}
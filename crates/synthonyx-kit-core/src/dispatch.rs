@@ -1,7 +1,12 @@
-//! Dispatch traits — entry points for RTM operations.
+//! Dispatch traits — entry points for RTM operations — and the interceptor
+//! pipeline that wraps them.
 
+use std::sync::Arc;
+
+use crate::audit::AuditEntry;
 use crate::config::Config;
 use crate::error::DispatchError;
+use crate::time::{MonotonicNanos, TimeSource};
 
 /// Synchronous dispatch entry point.
 ///
@@ -14,6 +19,24 @@ pub trait Dispatch<T: Config> {
 
     /// Execute this dispatch on behalf of `origin`.
     fn call(&self, origin: T::Origin) -> Result<Self::Output, DispatchError>;
+
+    /// A short, stable name for the operation, seen by [`Interceptor`]s
+    /// (and typically recorded as the audit `action`). Call enums return
+    /// one name per variant.
+    ///
+    /// Defaults to the implementing type's name, so policies and audit
+    /// entries can still tell dispatches apart. That name is not
+    /// guaranteed stable across compiler versions; override this for
+    /// anything that is matched on or retained.
+    fn action(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Contribute a subject and fields to the entry [`crate::Audited`]
+    /// records for this call. The default adds nothing.
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        entry
+    }
 }
 
 /// Asynchronous dispatch entry point.
@@ -28,4 +51,507 @@ pub trait DispatchAsync<T: Config> {
 
     /// Execute this dispatch on behalf of `origin`.
     async fn call(&self, origin: T::Origin) -> Result<Self::Output, DispatchError>;
+
+    /// A short, stable name for the operation; see [`Dispatch::action`].
+    fn action(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Contribute to the audit entry; see [`Dispatch::annotate_audit`].
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        entry
+    }
+}
+
+/// What an [`Interceptor`] sees of one dispatch.
+pub struct DispatchContext<'a, T: Config> {
+    origin: &'a T::Origin,
+    action: &'static str,
+    time: &'a T::Time,
+    started: MonotonicNanos,
+}
+
+impl<'a, T: Config> DispatchContext<'a, T> {
+    /// The origin the dispatch runs on behalf of.
+    pub fn origin(&self) -> &'a T::Origin {
+        self.origin
+    }
+
+    /// The dispatch's [`Dispatch::action`] name.
+    pub fn action(&self) -> &'static str {
+        self.action
+    }
+
+    /// The pipeline's time source, for audit timestamps.
+    pub fn time(&self) -> &'a T::Time {
+        self.time
+    }
+
+    /// Monotonic time at which the pipeline was entered.
+    pub fn started(&self) -> MonotonicNanos {
+        self.started
+    }
+
+    /// Nanoseconds since the pipeline was entered.
+    pub fn elapsed_nanos(&self) -> u128 {
+        self.time.monotonic().0.saturating_sub(self.started.0)
+    }
+}
+
+/// A cross-cutting concern — audit, timing, authorization, rate limiting,
+/// tracing — applied around every dispatch of a [`Pipeline`].
+///
+/// Both methods default to no-ops, so an interceptor overrides only the
+/// side it needs. Interceptors are not generic over the dispatch output:
+/// `after` sees whether the call succeeded, not what it returned.
+pub trait Interceptor<T: Config>: Send + Sync + 'static {
+    /// Called before the dispatch runs. Returning an error short-circuits:
+    /// neither the dispatch nor any inner interceptor runs, and the error
+    /// is returned to the caller.
+    fn before(&self, ctx: &DispatchContext<'_, T>) -> Result<(), DispatchError> {
+        let _ = ctx;
+        Ok(())
+    }
+
+    /// Called once the dispatch (or an inner interceptor's short-circuit)
+    /// has produced `result`. Returning an error turns a success into that
+    /// error and discards the output; if the dispatch already failed, its
+    /// error is kept.
+    fn after(
+        &self,
+        ctx: &DispatchContext<'_, T>,
+        result: Result<(), &DispatchError>,
+    ) -> Result<(), DispatchError> {
+        let _ = (ctx, result);
+        Ok(())
+    }
+}
+
+impl<T: Config, I: Interceptor<T> + ?Sized> Interceptor<T> for Arc<I> {
+    fn before(&self, ctx: &DispatchContext<'_, T>) -> Result<(), DispatchError> {
+        (**self).before(ctx)
+    }
+
+    fn after(
+        &self,
+        ctx: &DispatchContext<'_, T>,
+        result: Result<(), &DispatchError>,
+    ) -> Result<(), DispatchError> {
+        (**self).after(ctx, result)
+    }
+}
+
+/// An ordered stack of [`Interceptor`]s wrapped around [`Dispatch`] and
+/// [`DispatchAsync`] calls.
+///
+/// The first layer added is the outermost: `before` runs in the order the
+/// layers were added and `after` in reverse, and a layer whose `before`
+/// ran always gets its `after`, even when an inner layer short-circuits.
+///
+/// The runtime composer builds one pipeline per RTM `Config`, usually from
+/// a single function generic over `T` so every RTM gets the same stack.
+/// Stateful interceptors that must be shared across RTMs (a rate limiter,
+/// say) are added as an `Arc`.
+pub struct Pipeline<T: Config> {
+    time: T::Time,
+    layers: Vec<Arc<dyn Interceptor<T>>>,
+}
+
+impl<T: Config> Pipeline<T> {
+    /// An empty pipeline timing dispatches with `time`.
+    pub fn new(time: T::Time) -> Self {
+        Self {
+            time,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Add `layer` inside the layers added so far.
+    pub fn with_layer(mut self, layer: impl Interceptor<T>) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Number of layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Whether the pipeline has no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Run `dispatch` through every layer.
+    pub fn call<D: Dispatch<T>>(
+        &self,
+        dispatch: &D,
+        origin: T::Origin,
+    ) -> Result<D::Output, DispatchError> {
+        let ctx = self.context(&origin, dispatch.action());
+        self.enter(&ctx)?;
+        let result = dispatch.call(origin.clone());
+        self.exit(&ctx, self.layers.len(), result)
+    }
+
+    /// Run `dispatch` through every layer, awaiting it in the middle.
+    pub async fn call_async<D: DispatchAsync<T>>(
+        &self,
+        dispatch: &D,
+        origin: T::Origin,
+    ) -> Result<D::Output, DispatchError> {
+        let ctx = self.context(&origin, dispatch.action());
+        self.enter(&ctx)?;
+        let result = dispatch.call(origin.clone()).await;
+        self.exit(&ctx, self.layers.len(), result)
+    }
+
+    /// Wrap `dispatch` so that calling it goes through this pipeline. The
+    /// result implements [`Dispatch`] and [`DispatchAsyncSend`] whenever
+    /// `dispatch` does, so it can stand in wherever a dispatch is expected.
+    pub fn wrap<D>(&self, dispatch: D) -> Layered<'_, T, D> {
+        Layered {
+            pipeline: self,
+            dispatch,
+        }
+    }
+
+    fn context<'a>(
+        &'a self,
+        origin: &'a T::Origin,
+        action: &'static str,
+    ) -> DispatchContext<'a, T> {
+        DispatchContext {
+            origin,
+            action,
+            time: &self.time,
+            started: self.time.monotonic(),
+        }
+    }
+
+    /// Run every `before`, unwinding through the layers already entered if
+    /// one short-circuits.
+    fn enter(&self, ctx: &DispatchContext<'_, T>) -> Result<(), DispatchError> {
+        for (depth, layer) in self.layers.iter().enumerate() {
+            if let Err(e) = layer.before(ctx) {
+                return self.exit(ctx, depth, Err(e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `after` on the outermost `depth` layers, innermost first.
+    fn exit<O>(
+        &self,
+        ctx: &DispatchContext<'_, T>,
+        depth: usize,
+        mut result: Result<O, DispatchError>,
+    ) -> Result<O, DispatchError> {
+        for layer in self.layers[..depth].iter().rev() {
+            if let Err(e) = layer.after(ctx, result.as_ref().map(|_| ())) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// A dispatch wrapped by [`Pipeline::wrap`].
+pub struct Layered<'a, T: Config, D> {
+    pipeline: &'a Pipeline<T>,
+    dispatch: D,
+}
+
+impl<T: Config, D> Layered<'_, T, D> {
+    /// The wrapped dispatch.
+    pub fn inner(&self) -> &D {
+        &self.dispatch
+    }
+}
+
+impl<T: Config, D: Dispatch<T>> Dispatch<T> for Layered<'_, T, D> {
+    type Output = D::Output;
+
+    fn call(&self, origin: T::Origin) -> Result<D::Output, DispatchError> {
+        self.pipeline.call(&self.dispatch, origin)
+    }
+
+    fn action(&self) -> &'static str {
+        self.dispatch.action()
+    }
+
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        self.dispatch.annotate_audit(entry)
+    }
+}
+
+impl<T: Config, D: DispatchAsyncSend<T> + Sync> DispatchAsyncSend<T> for Layered<'_, T, D> {
+    type Output = D::Output;
+
+    async fn call(&self, origin: T::Origin) -> Result<D::Output, DispatchError> {
+        let pipeline = self.pipeline;
+        let ctx = pipeline.context(&origin, DispatchAsyncSend::action(&self.dispatch));
+        pipeline.enter(&ctx)?;
+        let result = DispatchAsyncSend::call(&self.dispatch, origin.clone()).await;
+        pipeline.exit(&ctx, pipeline.layers.len(), result)
+    }
+
+    fn action(&self) -> &'static str {
+        DispatchAsyncSend::action(&self.dispatch)
+    }
+
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        DispatchAsyncSend::annotate_audit(&self.dispatch, entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditEntry, AuditError, AuditLogger};
+    use crate::correlation::CorrelationId;
+    use crate::event::{Event, Severity};
+    use crate::origin::BaseOrigin;
+    use crate::time::MockClock;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+
+    #[derive(Debug)]
+    struct Noop;
+
+    impl Event for Noop {
+        fn module(&self) -> &'static str {
+            "test"
+        }
+        fn name(&self) -> &'static str {
+            "noop"
+        }
+        fn severity(&self) -> Severity {
+            Severity::Info
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("test error")]
+    struct TestError;
+
+    impl From<TestError> for DispatchError {
+        fn from(e: TestError) -> Self {
+            DispatchError::module("test", e)
+        }
+    }
+
+    struct NullAudit;
+
+    impl AuditLogger for NullAudit {
+        fn record(&self, _: AuditEntry) -> Result<(), AuditError> {
+            Ok(())
+        }
+    }
+
+    struct Runtime;
+
+    impl Config for Runtime {
+        const MODULE: &'static str = "test";
+        type RuntimeEvent = Noop;
+        type Event = Noop;
+        type Error = TestError;
+        type Origin = BaseOrigin<String>;
+        type Time = MockClock;
+        type Audit = NullAudit;
+    }
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    /// Records `before`/`after` calls into a shared log, and optionally
+    /// fails one side.
+    struct Probe {
+        name: &'static str,
+        log: Log,
+        deny: bool,
+        fail_after: bool,
+    }
+
+    impl Probe {
+        fn new(name: &'static str, log: &Log) -> Self {
+            Self {
+                name,
+                log: Arc::clone(log),
+                deny: false,
+                fail_after: false,
+            }
+        }
+    }
+
+    impl Interceptor<Runtime> for Probe {
+        fn before(&self, ctx: &DispatchContext<'_, Runtime>) -> Result<(), DispatchError> {
+            let entry = format!("{}.before({})", self.name, ctx.action());
+            self.log.lock().unwrap().push(entry);
+            if self.deny {
+                return Err(DispatchError::BadOrigin {
+                    action: ctx.action(),
+                });
+            }
+            Ok(())
+        }
+
+        fn after(
+            &self,
+            _: &DispatchContext<'_, Runtime>,
+            result: Result<(), &DispatchError>,
+        ) -> Result<(), DispatchError> {
+            let entry = format!("{}.after(ok={})", self.name, result.is_ok());
+            self.log.lock().unwrap().push(entry);
+            if self.fail_after {
+                return Err(DispatchError::Internal("after failed".into()));
+            }
+            Ok(())
+        }
+    }
+
+    struct Echo(Log);
+
+    impl Dispatch<Runtime> for Echo {
+        type Output = u32;
+
+        fn call(&self, _: BaseOrigin<String>) -> Result<u32, DispatchError> {
+            self.0.lock().unwrap().push("call".into());
+            Ok(7)
+        }
+
+        fn action(&self) -> &'static str {
+            "echo"
+        }
+    }
+
+    impl DispatchAsyncSend<Runtime> for Echo {
+        type Output = u32;
+
+        async fn call(&self, origin: BaseOrigin<String>) -> Result<u32, DispatchError> {
+            Dispatch::call(self, origin)
+        }
+
+        fn action(&self) -> &'static str {
+            "echo"
+        }
+    }
+
+    fn origin() -> BaseOrigin<String> {
+        BaseOrigin::System {
+            correlation: CorrelationId([1; 16]),
+        }
+    }
+
+    fn taken(log: &Log) -> Vec<String> {
+        std::mem::take(&mut log.lock().unwrap())
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn layers_wrap_in_order() {
+        let log = Log::default();
+        let pipeline = Pipeline::<Runtime>::new(MockClock::default())
+            .with_layer(Probe::new("outer", &log))
+            .with_layer(Probe::new("inner", &log));
+        assert_eq!(pipeline.call(&Echo(Arc::clone(&log)), origin()).unwrap(), 7);
+        assert_eq!(
+            taken(&log),
+            [
+                "outer.before(echo)",
+                "inner.before(echo)",
+                "call",
+                "inner.after(ok=true)",
+                "outer.after(ok=true)",
+            ]
+        );
+
+        let wrapped = pipeline.wrap(Echo(Arc::clone(&log)));
+        let output = block_on(DispatchAsyncSend::call(&wrapped, origin())).unwrap();
+        assert_eq!(output, 7);
+        assert_eq!(taken(&log).len(), 5);
+    }
+
+    #[test]
+    fn short_circuit_skips_the_call_and_unwinds() {
+        let log = Log::default();
+        let deny = Probe {
+            deny: true,
+            ..Probe::new("deny", &log)
+        };
+        let pipeline = Pipeline::<Runtime>::new(MockClock::default())
+            .with_layer(Probe::new("outer", &log))
+            .with_layer(deny)
+            .with_layer(Probe::new("inner", &log));
+        let err = Dispatch::call(&pipeline.wrap(Echo(Arc::clone(&log))), origin()).unwrap_err();
+        assert!(matches!(err, DispatchError::BadOrigin { action: "echo" }));
+        assert_eq!(
+            taken(&log),
+            [
+                "outer.before(echo)",
+                "deny.before(echo)",
+                "outer.after(ok=false)",
+            ]
+        );
+    }
+
+    #[test]
+    fn after_can_fail_a_success_but_keeps_the_first_error() {
+        let log = Log::default();
+        let failing = || Probe {
+            fail_after: true,
+            ..Probe::new("fail", &log)
+        };
+        let pipeline = Pipeline::<Runtime>::new(MockClock::default())
+            .with_layer(Arc::new(Probe::new("outer", &log)))
+            .with_layer(failing())
+            .with_layer(failing());
+        let err = pipeline
+            .call(&Echo(Arc::clone(&log)), origin())
+            .unwrap_err();
+        assert!(matches!(err, DispatchError::Internal(_)));
+        assert_eq!(
+            taken(&log)[4..],
+            [
+                "fail.after(ok=true)",
+                "fail.after(ok=false)",
+                "outer.after(ok=false)"
+            ]
+        );
+    }
+
+    struct Unnamed;
+
+    impl Dispatch<Runtime> for Unnamed {
+        type Output = ();
+
+        fn call(&self, _: BaseOrigin<String>) -> Result<(), DispatchError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn default_action_names_the_dispatch_type() {
+        let log = Log::default();
+        let pipeline =
+            Pipeline::<Runtime>::new(MockClock::default()).with_layer(Probe::new("p", &log));
+        pipeline.call(&Unnamed, origin()).unwrap();
+        assert_eq!(
+            taken(&log)[0],
+            format!("p.before({})", core::any::type_name::<Unnamed>())
+        );
+        assert!(Dispatch::action(&Unnamed).ends_with("::Unnamed"));
+    }
 }
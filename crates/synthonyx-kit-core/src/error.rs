@@ -1,5 +1,7 @@
 //! The kit-wide dispatch error type.
 
+use crate::audit::AuditError;
+
 /// The kit-wide error returned by [`crate::Dispatch::call`] and
 /// [`crate::DispatchAsync::call`].
 ///
@@ -32,6 +34,11 @@ pub enum DispatchError {
     /// An unexpected internal condition that should not occur in correct code.
     #[error("internal error: {0}")]
     Internal(String),
+
+    /// The dispatch could not be audited, and audit failures fail closed
+    /// (see [`crate::Audited`]).
+    #[error("audit trail unavailable: {0}")]
+    Audit(#[from] AuditError),
 }
 
 impl DispatchError {
@@ -46,4 +53,16 @@ impl DispatchError {
             source: Box::new(source),
         }
     }
+
+    /// A stable, low-cardinality name for the variant, safe to record in
+    /// audit entries and metrics where the message might not be.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Module { .. } => "module",
+            Self::BadOrigin { .. } => "bad_origin",
+            Self::Precondition(_) => "precondition",
+            Self::Internal(_) => "internal",
+            Self::Audit(_) => "audit",
+        }
+    }
 }
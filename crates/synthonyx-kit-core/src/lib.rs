@@ -9,7 +9,8 @@
 //! - [`get`]: the `Get<T>` type-parameter pattern.
 //! - [`config`]: the per-RTM `Config` trait that wires events, errors, origin,
 //!   time, and audit.
-//! - [`dispatch`]: synchronous and asynchronous dispatch entry points.
+//! - [`dispatch`]: synchronous and asynchronous dispatch entry points, and the
+//!   `Pipeline` of `Interceptor` layers wrapped around them.
 //! - [`origin`]: `OriginTrait`, `OriginKind`, and the generic `BaseOrigin<P>`.
 //! - [`event`]: the `Event` trait and `Severity` tag.
 //! - [`hooks`]: service-lifecycle hooks (`on_boot`, `on_shutdown`, etc.).
@@ -20,9 +21,15 @@
 //! - [`pii`]: `Pii<T, C>` — type-tagged personal data.
 //! - [`audit`]: the `AuditLogger` trait and `AuditEntry` type. Concrete sinks
 //!   live in `synthonyx-kit-audit`.
+//! - [`audited`]: `Audited<D>`, which records an audit entry for every call
+//!   of a dispatch, failures included.
+//! - [`authz`]: `Policy`, `PolicySet`, `Rbac` and attribute `Rule`s —
+//!   deny-by-default authorization with auditable decisions.
 #![deny(missing_docs, unsafe_code, rust_2018_idioms)]
 
 pub mod audit;
+pub mod audited;
+pub mod authz;
 pub mod config;
 pub mod correlation;
 pub mod dispatch;
@@ -36,9 +43,13 @@ pub mod secret;
 pub mod time;
 
 pub use audit::{AuditEntry, AuditError, AuditLogger, AuditValue, OriginSnapshot, Outcome};
+pub use audited::{AuditFailurePolicy, Audited};
+pub use authz::{AccessRequest, Decision, Effect, Policy, PolicySet, Rbac, Rule};
 pub use config::Config;
 pub use correlation::{CorrelationId, SpanId};
-pub use dispatch::{Dispatch, DispatchAsync, DispatchAsyncSend};
+pub use dispatch::{
+    Dispatch, DispatchAsync, DispatchAsyncSend, DispatchContext, Interceptor, Layered, Pipeline,
+};
 pub use error::DispatchError;
 pub use event::{Event, EventBus, Severity};
 pub use get::Get;
@@ -1,5 +1,7 @@
 //! Origin types — who triggered a dispatch.
 
+use std::borrow::Cow;
+
 use crate::correlation::CorrelationId;
 
 /// The kind of origin — a low-cardinality tag used by audit log entries and
@@ -34,6 +36,11 @@ pub trait OriginTrait: Clone + Send + Sync + 'static {
 
     /// The correlation id propagated with this dispatch.
     fn correlation_id(&self) -> CorrelationId;
+
+    /// The calling service's name, for service-to-service origins.
+    fn service(&self) -> Option<Cow<'static, str>> {
+        None
+    }
 }
 
 /// A generic origin enum sufficient for most RTMs.
@@ -104,4 +111,11 @@ where
             | Self::Anonymous { correlation } => *correlation,
         }
     }
+
+    fn service(&self) -> Option<Cow<'static, str>> {
+        match self {
+            Self::Service { name, .. } => Some(Cow::Borrowed(name)),
+            _ => None,
+        }
+    }
 }
@@ -5,15 +5,62 @@
 //!   and re-readable verbatim).
 //! - DORA Art. 32 (audit log integrity — entries must be deterministically
 //!   encoded so a hash chain is reproducible).
-//! - GDPR Art. 30 (records of processing).
+//! - GDPR Art. 30 (records of processing — `for_dispatch` attributes
+//!   every entry to the dispatch's principal).
 
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
 use synthonyx_kit_core::{
-    AuditEntry, AuditValue, CorrelationId, OriginKind, OriginSnapshot, Outcome, UnixNanos,
+    AuditEntry, AuditError, AuditLogger, AuditValue, BaseOrigin, Config, CorrelationId,
+    DispatchError, Event, MockClock, OriginKind, OriginSnapshot, Outcome, Severity, UnixNanos,
 };
 
+#[derive(Debug)]
+struct Registered;
+
+impl Event for Registered {
+    fn module(&self) -> &'static str {
+        "users"
+    }
+    fn name(&self) -> &'static str {
+        "registered"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("users error")]
+struct UsersError;
+
+impl From<UsersError> for DispatchError {
+    fn from(e: UsersError) -> Self {
+        DispatchError::module("users", e)
+    }
+}
+
+struct NullAudit;
+
+impl AuditLogger for NullAudit {
+    fn record(&self, _: AuditEntry) -> Result<(), AuditError> {
+        Ok(())
+    }
+}
+
+struct Runtime;
+
+impl Config for Runtime {
+    const MODULE: &'static str = "users";
+    type RuntimeEvent = Registered;
+    type Event = Registered;
+    type Error = UsersError;
+    type Origin = BaseOrigin<String>;
+    type Time = MockClock;
+    type Audit = NullAudit;
+}
+
 fn sample() -> AuditEntry {
     AuditEntry {
         timestamp: UnixNanos(1_700_000_000_000_000_000),
@@ -32,6 +79,8 @@ fn sample() -> AuditEntry {
             ("a".to_string(), AuditValue::UInt(1)),
         ]),
         prev_hash: [0u8; 32],
+        sequence: None,
+        writer: None,
     }
 }
 
@@ -88,3 +137,45 @@ fn audit_value_untagged_serialises_compactly() {
     let json = serde_json::to_string(&AuditValue::Str("ok".to_string())).unwrap();
     assert_eq!(json, "\"ok\"");
 }
+
+#[test]
+fn art_30_for_dispatch_snapshots_origin_and_clock() {
+    let clock = MockClock::new(1_700_000_000_000_000_000, 0);
+    let origin = BaseOrigin::Service {
+        name: "billing",
+        principal: "spiffe://prod/billing".to_string(),
+        correlation: CorrelationId([9u8; 16]),
+    };
+    let e = AuditEntry::for_dispatch::<Runtime>(&clock, &origin, "register_user");
+
+    assert_eq!(e.timestamp, UnixNanos(1_700_000_000_000_000_000));
+    assert_eq!(e.correlation_id, CorrelationId([9u8; 16]));
+    assert_eq!(e.module, "users");
+    assert_eq!(e.action, "register_user");
+    assert_eq!(e.origin.kind, OriginKind::Service);
+    assert_eq!(e.origin.principal.as_deref(), Some("spiffe://prod/billing"));
+    assert_eq!(e.origin.service.as_deref(), Some("billing"));
+    assert_eq!(e.outcome, Outcome::Success);
+    assert_eq!(e.prev_hash, [0u8; 32]);
+}
+
+#[test]
+fn art_30_for_dispatch_setters() {
+    let origin = BaseOrigin::<String>::Anonymous {
+        correlation: CorrelationId::ZERO,
+    };
+    let e = AuditEntry::for_dispatch::<Runtime>(&MockClock::default(), &origin, "login")
+        .with_outcome(Outcome::Denied)
+        .with_subject("subj-1")
+        .with_field("attempt", 3u64)
+        .with_field("locked", true)
+        .with_field("reason", "bad_password");
+
+    assert_eq!(e.origin.principal, None);
+    assert_eq!(e.origin.service, None);
+    assert_eq!(e.outcome, Outcome::Denied);
+    assert_eq!(e.subject.as_deref(), Some("subj-1"));
+    assert!(matches!(e.fields["attempt"], AuditValue::UInt(3)));
+    assert!(matches!(e.fields["locked"], AuditValue::Bool(true)));
+    assert!(matches!(&e.fields["reason"], AuditValue::Str(s) if s == "bad_password"));
+}
@@ -0,0 +1,250 @@
+//! Compliance contract tests for `Audited`.
+//!
+//! References:
+//! - GDPR Art. 30 (records of processing — every dispatch is recorded,
+//!   not only the successful ones).
+//! - DORA Art. 9 (audit-trail retention — an operation that cannot be
+//!   evidenced is reported as failed by default).
+//! - DORA Art. 17 (incident attribution — denied and failed calls carry
+//!   their outcome and error kind).
+//!
+//! Contracts enforced:
+//! - Success, `BadOrigin`/`Precondition` and every other error are
+//!   recorded as `Success`, `Denied` and `Error`, with the error kind but
+//!   not the error message.
+//! - `Dispatch::annotate_audit` contributes the subject and fields.
+//! - A sink failure fails a successful call closed by default, keeps a
+//!   failed call's own error, and is ignored under `FailOpen`.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use synthonyx_kit_core::{
+    AuditEntry, AuditError, AuditFailurePolicy, AuditLogger, AuditValue, Audited, BaseOrigin,
+    Config, CorrelationId, Dispatch, DispatchAsyncSend, DispatchError, Event, MockClock, Outcome,
+    Severity,
+};
+
+#[derive(Debug)]
+struct Registered;
+
+impl Event for Registered {
+    fn module(&self) -> &'static str {
+        "users"
+    }
+    fn name(&self) -> &'static str {
+        "registered"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("name {0:?} is taken")]
+struct NameTaken(String);
+
+impl From<NameTaken> for DispatchError {
+    fn from(e: NameTaken) -> Self {
+        DispatchError::module("users", e)
+    }
+}
+
+/// Collects entries, or refuses them all once `broken` is set.
+#[derive(Clone, Default)]
+struct Sink {
+    entries: Arc<Mutex<Vec<AuditEntry>>>,
+    broken: bool,
+}
+
+impl AuditLogger for Sink {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        if self.broken {
+            return Err(AuditError::Backend("sink offline".into()));
+        }
+        self.entries.lock().unwrap().push(entry);
+        Ok(())
+    }
+}
+
+impl Sink {
+    fn taken(&self) -> Vec<AuditEntry> {
+        std::mem::take(&mut self.entries.lock().unwrap())
+    }
+}
+
+struct Runtime;
+
+impl Config for Runtime {
+    const MODULE: &'static str = "users";
+    type RuntimeEvent = Registered;
+    type Event = Registered;
+    type Error = NameTaken;
+    type Origin = BaseOrigin<String>;
+    type Time = MockClock;
+    type Audit = Sink;
+}
+
+type Failure = fn() -> DispatchError;
+
+/// Registers `name`; fails according to `fail`.
+struct Register {
+    name: &'static str,
+    fail: Option<Failure>,
+}
+
+impl Dispatch<Runtime> for Register {
+    type Output = Registered;
+
+    fn call(&self, _: BaseOrigin<String>) -> Result<Registered, DispatchError> {
+        match self.fail {
+            Some(fail) => Err(fail()),
+            None => Ok(Registered),
+        }
+    }
+
+    fn action(&self) -> &'static str {
+        "register_user"
+    }
+
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        entry
+            .with_subject("user-7")
+            .with_field("name_len", self.name.len() as u64)
+    }
+}
+
+impl DispatchAsyncSend<Runtime> for Register {
+    type Output = Registered;
+
+    async fn call(&self, origin: BaseOrigin<String>) -> Result<Registered, DispatchError> {
+        Dispatch::call(self, origin)
+    }
+
+    fn action(&self) -> &'static str {
+        "register_user"
+    }
+
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        Dispatch::annotate_audit(self, entry)
+    }
+}
+
+fn origin() -> BaseOrigin<String> {
+    BaseOrigin::User {
+        principal: "user-42".to_string(),
+        correlation: CorrelationId([42; 16]),
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn art_30_every_outcome_is_recorded() {
+    let sink = Sink::default();
+    let clock = MockClock::new(1_700_000_000_000_000_000, 0);
+    let cases: [(Option<Failure>, Outcome, Option<&str>); 4] = [
+        (None, Outcome::Success, None),
+        (
+            Some(|| DispatchError::BadOrigin {
+                action: "register_user",
+            }),
+            Outcome::Denied,
+            Some("bad_origin"),
+        ),
+        (
+            Some(|| DispatchError::Precondition("name must not be empty")),
+            Outcome::Denied,
+            Some("precondition"),
+        ),
+        (
+            Some(|| NameTaken("alice".into()).into()),
+            Outcome::Error,
+            Some("module"),
+        ),
+    ];
+    for (fail, outcome, kind) in cases {
+        let call = Audited::<Runtime, _>::new(
+            Register {
+                name: "alice",
+                fail,
+            },
+            &sink,
+            &clock,
+        );
+        assert_eq!(Dispatch::call(&call, origin()).is_ok(), fail.is_none());
+
+        let entries = sink.taken();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.module, "users");
+        assert_eq!(entry.action, "register_user");
+        assert_eq!(entry.outcome, outcome);
+        assert_eq!(entry.correlation_id, CorrelationId([42; 16]));
+        assert_eq!(entry.origin.principal.as_deref(), Some("user-42"));
+        assert_eq!(entry.subject.as_deref(), Some("user-7"));
+        assert!(matches!(entry.fields["name_len"], AuditValue::UInt(5)));
+        match kind {
+            Some(kind) => {
+                assert!(matches!(&entry.fields["error"], AuditValue::Str(k) if k == kind))
+            }
+            None => assert!(!entry.fields.contains_key("error")),
+        }
+        assert!(!format!("{entry:?}").contains("alice"));
+    }
+
+    let call = Audited::<Runtime, _>::new(
+        Register {
+            name: "alice",
+            fail: Some(|| DispatchError::Precondition("name must not be empty")),
+        },
+        &sink,
+        &clock,
+    );
+    assert!(block_on(DispatchAsyncSend::call(&call, origin())).is_err());
+    assert_eq!(sink.taken()[0].outcome, Outcome::Denied);
+}
+
+#[test]
+fn art_9_sink_failure_fails_closed_by_default() {
+    let sink = Sink {
+        broken: true,
+        ..Sink::default()
+    };
+    let clock = MockClock::default();
+    let ok = || Register {
+        name: "alice",
+        fail: None,
+    };
+
+    let call = Audited::<Runtime, _>::new(ok(), &sink, &clock);
+    let err = Dispatch::call(&call, origin()).unwrap_err();
+    assert!(matches!(err, DispatchError::Audit(AuditError::Backend(_))));
+    assert_eq!(err.kind(), "audit");
+
+    let failing = Register {
+        name: "alice",
+        fail: Some(|| DispatchError::BadOrigin {
+            action: "register_user",
+        }),
+    };
+    let call = Audited::<Runtime, _>::new(failing, &sink, &clock);
+    assert!(matches!(
+        Dispatch::call(&call, origin()),
+        Err(DispatchError::BadOrigin { .. })
+    ));
+
+    let call = Audited::<Runtime, _>::new(ok(), &sink, &clock)
+        .with_failure_policy(AuditFailurePolicy::FailOpen);
+    assert!(Dispatch::call(&call, origin()).is_ok());
+}
@@ -0,0 +1,235 @@
+//! Compliance contract tests for the authorization policies.
+//!
+//! References:
+//! - GDPR Art. 25 (data protection by default — access not granted by a
+//!   policy is denied).
+//! - GDPR Art. 32 (security of processing — access to operations is
+//!   restricted by role and attribute).
+//! - DORA Art. 9(4)(c) (access control policies limiting access to what
+//!   each function requires).
+//!
+//! Contracts enforced:
+//! - An empty `PolicySet`, and one whose policies all abstain, denies.
+//! - `Rbac` allows exactly the permissions its roles grant, to principals
+//!   and origin kinds it assigns them to.
+//! - A `Rule::deny` overrides any allow.
+//! - The decision names the deciding policy and is recorded in audit
+//!   entries without the principal; as an interceptor, denials are
+//!   recorded even though the dispatch never runs.
+
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_core::{
+    AccessRequest, AuditEntry, AuditError, AuditLogger, AuditValue, BaseOrigin, Config,
+    CorrelationId, Decision, Dispatch, DispatchError, Effect, Event, MockClock, OriginKind,
+    Outcome, Pipeline, PolicySet, Rbac, Rule, Severity,
+};
+
+#[derive(Debug)]
+struct Paid;
+
+impl Event for Paid {
+    fn module(&self) -> &'static str {
+        "payments"
+    }
+    fn name(&self) -> &'static str {
+        "paid"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("payments error")]
+struct PaymentsError;
+
+impl From<PaymentsError> for DispatchError {
+    fn from(e: PaymentsError) -> Self {
+        DispatchError::module("payments", e)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Sink(Arc<Mutex<Vec<AuditEntry>>>);
+
+impl AuditLogger for Sink {
+    fn record(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.0.lock().unwrap().push(entry);
+        Ok(())
+    }
+}
+
+struct Runtime;
+
+impl Config for Runtime {
+    const MODULE: &'static str = "payments";
+    type RuntimeEvent = Paid;
+    type Event = Paid;
+    type Error = PaymentsError;
+    type Origin = BaseOrigin<String>;
+    type Time = MockClock;
+    type Audit = Sink;
+}
+
+fn user(principal: &str) -> BaseOrigin<String> {
+    BaseOrigin::User {
+        principal: principal.to_string(),
+        correlation: CorrelationId([3; 16]),
+    }
+}
+
+fn system() -> BaseOrigin<String> {
+    BaseOrigin::System {
+        correlation: CorrelationId([3; 16]),
+    }
+}
+
+fn rbac() -> Rbac {
+    Rbac::new()
+        .with_role("clerk", ["payments.view"])
+        .with_role("treasurer", ["payments.*"])
+        .with_role("operator", ["*"])
+        .assign("alice", "clerk")
+        .assign("bob", "treasurer")
+        .assign_kind(OriginKind::System, "operator")
+}
+
+fn decide(
+    policies: &PolicySet<Runtime>,
+    origin: &BaseOrigin<String>,
+    action: &'static str,
+) -> Decision {
+    policies.authorize(&AccessRequest::new(origin, action))
+}
+
+#[test]
+fn art_25_nothing_is_allowed_by_default() {
+    let empty = PolicySet::<Runtime>::new();
+    let decision = decide(&empty, &system(), "view");
+    assert_eq!(decision.effect, Effect::Deny);
+    assert_eq!(decision.policy, "default");
+
+    let policies = PolicySet::<Runtime>::new().with_policy(rbac());
+    assert!(!decide(&policies, &user("mallory"), "view").is_allowed());
+    let anonymous = BaseOrigin::Anonymous {
+        correlation: CorrelationId([3; 16]),
+    };
+    assert!(!decide(&policies, &anonymous, "view").is_allowed());
+}
+
+#[test]
+fn art_32_roles_grant_exactly_their_permissions() {
+    let policies = PolicySet::<Runtime>::new().with_policy(rbac());
+    assert!(decide(&policies, &user("alice"), "view").is_allowed());
+    assert!(!decide(&policies, &user("alice"), "refund").is_allowed());
+    assert!(decide(&policies, &user("bob"), "refund").is_allowed());
+    assert!(decide(&policies, &system(), "refund").is_allowed());
+
+    let decision = decide(&policies, &user("bob"), "refund");
+    assert_eq!(decision.policy, "rbac");
+    assert_eq!(decision.reason, "role `treasurer` grants `payments.refund`");
+
+    let rbac = rbac();
+    assert!(rbac.grants("treasurer", "payments", "refund"));
+    assert!(!rbac.grants("treasurer", "users", "refund"));
+    assert!(!rbac.grants("unknown", "payments", "view"));
+}
+
+#[test]
+fn art_32_attribute_rules_allow_and_deny_overrides() {
+    let policies = PolicySet::<Runtime>::new()
+        .with_policy(rbac())
+        .with_policy(Rule::allow(
+            "small-refunds",
+            |r: &AccessRequest<'_, Runtime>| {
+                r.kind() == OriginKind::User
+                    && r.action() == "refund"
+                    && matches!(r.attribute("amount"), Some(AuditValue::UInt(n)) if *n <= 50)
+            },
+        ))
+        .with_policy(Rule::deny(
+            "frozen-accounts",
+            |r: &AccessRequest<'_, Runtime>| {
+                matches!(r.attribute("frozen"), Some(AuditValue::Bool(true)))
+            },
+        ));
+
+    let origin = user("alice");
+    let refund = |amount: u64, frozen: bool| {
+        AccessRequest::<Runtime>::new(&origin, "refund")
+            .with_attribute("amount", amount)
+            .with_attribute("frozen", frozen)
+    };
+    let decision = policies.authorize(&refund(20, false));
+    assert!(decision.is_allowed());
+    assert_eq!(decision.policy, "small-refunds");
+    assert!(!policies.authorize(&refund(500, false)).is_allowed());
+
+    let frozen = policies.authorize(&refund(20, true));
+    assert_eq!(frozen.effect, Effect::Deny);
+    assert_eq!(frozen.policy, "frozen-accounts");
+
+    let bob = user("bob");
+    let request = AccessRequest::<Runtime>::new(&bob, "refund").with_attribute("frozen", true);
+    assert!(
+        !policies.authorize(&request).is_allowed(),
+        "deny overrides a role"
+    );
+    assert!(matches!(
+        policies.authorize(&request).into_result("refund"),
+        Err(DispatchError::BadOrigin { action: "refund" })
+    ));
+}
+
+struct Refund;
+
+impl Dispatch<Runtime> for Refund {
+    type Output = ();
+
+    fn call(&self, _: BaseOrigin<String>) -> Result<(), DispatchError> {
+        Ok(())
+    }
+
+    fn action(&self) -> &'static str {
+        "refund"
+    }
+}
+
+#[test]
+fn art_32_denials_are_recorded_as_decisions() {
+    let sink = Sink::default();
+    let pipeline = Pipeline::<Runtime>::new(MockClock::default()).with_layer(
+        PolicySet::new()
+            .with_policy(rbac())
+            .with_audit(sink.clone()),
+    );
+
+    assert!(pipeline.call(&Refund, user("bob")).is_ok());
+    assert!(
+        sink.0.lock().unwrap().is_empty(),
+        "allowed calls are audited by the call"
+    );
+
+    let err = pipeline.call(&Refund, user("alice")).unwrap_err();
+    assert!(matches!(err, DispatchError::BadOrigin { action: "refund" }));
+    let entries = sink.0.lock().unwrap();
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.outcome, Outcome::Denied);
+    assert_eq!(entry.action, "refund");
+    assert!(matches!(&entry.fields["authz_effect"], AuditValue::Str(s) if s == "deny"));
+    assert!(matches!(&entry.fields["authz_policy"], AuditValue::Str(s) if s == "default"));
+    assert!(
+        matches!(&entry.fields["authz_reason"], AuditValue::Str(s) if s == "no policy allows `payments.refund`")
+    );
+
+    let allowed = Decision::allow("rbac", "role `clerk` grants `payments.view`");
+    let annotated = allowed.annotate(AuditEntry::for_dispatch::<Runtime>(
+        &MockClock::default(),
+        &user("alice"),
+        "view",
+    ));
+    assert_eq!(annotated.outcome, Outcome::Success);
+    assert!(!format!("{:?}", annotated.fields).contains("alice"));
+}
@@ -83,6 +83,33 @@ fn art_30_principal_present_only_for_service_and_user() {
     }
 }
 
+/// A structured principal without a `Display` impl; only the audit
+/// builders need one.
+#[derive(Clone, Debug, PartialEq)]
+struct AccountRef {
+    tenant: u32,
+    account: u64,
+}
+
+#[test]
+fn art_24a_principal_need_not_implement_display() {
+    let origin = BaseOrigin::User {
+        principal: AccountRef {
+            tenant: 7,
+            account: 42,
+        },
+        correlation: cid(5),
+    };
+    assert_eq!(
+        origin.principal(),
+        Some(&AccountRef {
+            tenant: 7,
+            account: 42
+        })
+    );
+    assert_eq!(origin.kind(), OriginKind::User);
+}
+
 #[cfg(feature = "serde")]
 #[test]
 fn origin_kind_serde_roundtrips() {
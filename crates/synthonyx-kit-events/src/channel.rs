@@ -0,0 +1,204 @@
+//! Bounded, Tokio-backed delivery: one channel and task per subscriber.
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use synthonyx_kit_core::{Event, EventBus};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{self, Receiver, Sender, error::TrySendError};
+use tokio::task::JoinHandle;
+
+use crate::dead_letter::{DeadLetter, DeadLetterSink, Failure, LogDeadLetters};
+use crate::filter::EventFilter;
+use crate::{BoxError, BusMetrics, Counters, Subscriber, SubscriptionId};
+
+/// Handles events delivered by a [`ChannelBus`], asynchronously.
+///
+/// Every [`Subscriber`] is also an `AsyncSubscriber`.
+pub trait AsyncSubscriber<E: Event>: Send + Sync + 'static {
+    /// Handle one event.
+    fn handle(&self, event: Arc<E>) -> impl Future<Output = Result<(), BoxError>> + Send;
+}
+
+impl<E: Event, S: Subscriber<E>> AsyncSubscriber<E> for S {
+    fn handle(&self, event: Arc<E>) -> impl Future<Output = Result<(), BoxError>> + Send {
+        std::future::ready(Subscriber::handle(self, &event))
+    }
+}
+
+struct Subscription<E> {
+    id: SubscriptionId,
+    name: Cow<'static, str>,
+    filter: EventFilter,
+    tx: Sender<Arc<E>>,
+    task: JoinHandle<()>,
+}
+
+/// Delivers events through a bounded queue per subscriber, each drained in
+/// order by its own Tokio task.
+///
+/// `emit` only enqueues, so it never waits for a subscriber and may be
+/// called from synchronous code or outside the runtime. When a
+/// subscriber's queue is full the event is not delivered to it: it becomes
+/// a [`DeadLetter`] with [`Failure::QueueFull`] and counts as dropped.
+/// Errors and panics in a subscriber dead-letter that one event; the
+/// subscriber keeps receiving the rest.
+///
+/// Dead letters are logged by default; [`Self::with_dead_letters`] must be
+/// called before subscribing. The sink is called with no bus lock held, so
+/// it may itself emit, subscribe or read [`Self::metrics`].
+/// [`Self::shutdown`] delivers everything already queued before returning.
+pub struct ChannelBus<E: Event> {
+    runtime: Handle,
+    capacity: usize,
+    subscriptions: Mutex<Vec<Subscription<E>>>,
+    counters: Arc<Counters>,
+    dead_letters: Arc<dyn DeadLetterSink<E>>,
+}
+
+impl<E: Event> ChannelBus<E> {
+    /// A bus giving each subscriber a queue of `capacity` events (at least
+    /// one), running subscribers on the current Tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Outside a Tokio runtime, like `tokio::spawn`.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_runtime(Handle::current(), capacity)
+    }
+
+    /// As [`Self::new`], running subscribers on `runtime`.
+    pub fn with_runtime(runtime: Handle, capacity: usize) -> Self {
+        Self {
+            runtime,
+            capacity: capacity.max(1),
+            subscriptions: Mutex::new(Vec::new()),
+            counters: Arc::new(Counters::default()),
+            dead_letters: Arc::new(LogDeadLetters),
+        }
+    }
+
+    /// Send undeliverable events to `sink` instead of the log.
+    pub fn with_dead_letters(mut self, sink: impl DeadLetterSink<E>) -> Self {
+        self.dead_letters = Arc::new(sink);
+        self
+    }
+
+    /// Deliver events matching `filter` to `subscriber` on a new task.
+    /// `name` identifies it in dead letters and logs.
+    pub fn subscribe(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        filter: EventFilter,
+        subscriber: impl AsyncSubscriber<E>,
+    ) -> SubscriptionId {
+        let id = self.counters.next_id();
+        let name = name.into();
+        let (tx, rx) = mpsc::channel(self.capacity);
+        let task = self.runtime.spawn(drain(
+            Arc::new(subscriber),
+            rx,
+            self.runtime.clone(),
+            name.clone(),
+            Arc::clone(&self.counters),
+            Arc::clone(&self.dead_letters),
+        ));
+        self.lock().push(Subscription {
+            id,
+            name,
+            filter,
+            tx,
+            task,
+        });
+        id
+    }
+
+    /// Remove a subscription. Events already queued for it are still
+    /// delivered. Returns whether it existed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscriptions = self.lock();
+        let before = subscriptions.len();
+        subscriptions.retain(|s| s.id != id);
+        subscriptions.len() != before
+    }
+
+    /// Current counters.
+    pub fn metrics(&self) -> BusMetrics {
+        self.counters.snapshot(self.lock().len())
+    }
+
+    /// Remove every subscription and wait until each has handled the
+    /// events already queued for it.
+    pub async fn shutdown(&self) {
+        let subscriptions = std::mem::take(&mut *self.lock());
+        for subscription in subscriptions {
+            drop(subscription.tx);
+            let _ = subscription.task.await;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Subscription<E>>> {
+        self.subscriptions.lock().expect("event bus mutex poisoned")
+    }
+}
+
+impl<E: Event> EventBus<E> for ChannelBus<E> {
+    fn emit(&self, event: E) {
+        self.counters.published.fetch_add(1, Ordering::Relaxed);
+        let event = Arc::new(event);
+        let mut refused = Vec::new();
+        for subscription in self.lock().iter().filter(|s| s.filter.matches(&*event)) {
+            let failure = match subscription.tx.try_send(Arc::clone(&event)) {
+                Ok(()) => continue,
+                Err(TrySendError::Full(_)) => Failure::QueueFull,
+                Err(TrySendError::Closed(_)) => Failure::Closed,
+            };
+            refused.push((subscription.name.clone(), failure));
+        }
+        // Outside the lock: the sink may emit, subscribe or read metrics.
+        for (subscriber, failure) in refused {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+            self.dead_letters.dead_letter(DeadLetter {
+                subscriber,
+                event: Arc::clone(&event),
+                failure,
+            });
+        }
+    }
+}
+
+/// One subscriber's task: handle each queued event in order until the
+/// queue closes. Each call runs as its own task so a panic is caught
+/// without killing the subscription.
+async fn drain<E: Event, S: AsyncSubscriber<E>>(
+    subscriber: Arc<S>,
+    mut rx: Receiver<Arc<E>>,
+    runtime: Handle,
+    name: Cow<'static, str>,
+    counters: Arc<Counters>,
+    dead_letters: Arc<dyn DeadLetterSink<E>>,
+) {
+    while let Some(event) = rx.recv().await {
+        let call = {
+            let subscriber = Arc::clone(&subscriber);
+            let event = Arc::clone(&event);
+            runtime.spawn(async move { subscriber.handle(event).await })
+        };
+        let failure = match call.await {
+            Ok(Ok(())) => {
+                counters.delivered.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            Ok(Err(e)) => Failure::Error(e.to_string()),
+            Err(_) => Failure::Panicked,
+        };
+        counters.failed.fetch_add(1, Ordering::Relaxed);
+        dead_letters.dead_letter(DeadLetter {
+            subscriber: name.clone(),
+            event,
+            failure,
+        });
+    }
+}
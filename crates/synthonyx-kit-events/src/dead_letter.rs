@@ -0,0 +1,148 @@
+//! Where events go when a subscriber cannot take them.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_core::Event;
+
+/// Why an event was not delivered to a subscriber.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Failure {
+    /// The subscriber returned an error.
+    Error(String),
+    /// The subscriber panicked.
+    Panicked,
+    /// The subscriber's queue was full.
+    QueueFull,
+    /// The subscriber's queue had closed.
+    Closed,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(e) => write!(f, "subscriber failed: {e}"),
+            Self::Panicked => f.write_str("subscriber panicked"),
+            Self::QueueFull => f.write_str("subscriber queue full"),
+            Self::Closed => f.write_str("subscriber queue closed"),
+        }
+    }
+}
+
+/// An event one subscriber did not handle, and why.
+#[derive(Debug)]
+pub struct DeadLetter<E> {
+    /// The name the subscriber was registered under.
+    pub subscriber: Cow<'static, str>,
+    /// The undelivered event, shared with the other subscribers.
+    pub event: Arc<E>,
+    /// Why it was not delivered.
+    pub failure: Failure,
+}
+
+/// Receives the events subscribers could not handle.
+///
+/// Called on the delivering thread or task, so implementations must not
+/// block.
+pub trait DeadLetterSink<E: Event>: Send + Sync + 'static {
+    /// Take one dead letter.
+    fn dead_letter(&self, letter: DeadLetter<E>);
+}
+
+impl<E: Event, S: DeadLetterSink<E> + ?Sized> DeadLetterSink<E> for Arc<S> {
+    fn dead_letter(&self, letter: DeadLetter<E>) {
+        (**self).dead_letter(letter)
+    }
+}
+
+/// The default sink: log each dead letter as a `tracing` warning naming
+/// the subscriber, the event's module and name, and the failure. The
+/// event itself is not logged.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogDeadLetters;
+
+impl<E: Event> DeadLetterSink<E> for LogDeadLetters {
+    fn dead_letter(&self, letter: DeadLetter<E>) {
+        ::tracing::warn!(
+            target: "synthonyx::events",
+            subscriber = %letter.subscriber,
+            module = letter.event.module(),
+            event = letter.event.name(),
+            failure = %letter.failure,
+            "event not delivered",
+        );
+    }
+}
+
+/// Keeps up to `capacity` dead letters in memory for inspection or
+/// redelivery, discarding the oldest beyond that. Clones share one queue.
+pub struct DeadLetterQueue<E> {
+    inner: Arc<Mutex<Queue<E>>>,
+}
+
+struct Queue<E> {
+    letters: std::collections::VecDeque<DeadLetter<E>>,
+    capacity: usize,
+    discarded: u64,
+}
+
+impl<E> Clone for DeadLetterQueue<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<E> DeadLetterQueue<E> {
+    /// A queue holding at most `capacity` letters.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Queue {
+                letters: std::collections::VecDeque::new(),
+                capacity,
+                discarded: 0,
+            })),
+        }
+    }
+
+    /// Remove and return every queued letter, oldest first.
+    pub fn drain(&self) -> Vec<DeadLetter<E>> {
+        self.lock().letters.drain(..).collect()
+    }
+
+    /// Number of queued letters.
+    pub fn len(&self) -> usize {
+        self.lock().letters.len()
+    }
+
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Letters discarded because the queue was full.
+    pub fn discarded(&self) -> u64 {
+        self.lock().discarded
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Queue<E>> {
+        self.inner.lock().expect("dead-letter queue mutex poisoned")
+    }
+}
+
+impl<E: Event> DeadLetterSink<E> for DeadLetterQueue<E> {
+    fn dead_letter(&self, letter: DeadLetter<E>) {
+        let mut queue = self.lock();
+        if queue.capacity == 0 {
+            queue.discarded += 1;
+            return;
+        }
+        if queue.letters.len() == queue.capacity {
+            queue.letters.pop_front();
+            queue.discarded += 1;
+        }
+        queue.letters.push_back(letter);
+    }
+}
@@ -0,0 +1,132 @@
+//! Synchronous, in-process fan-out.
+
+use std::borrow::Cow;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use synthonyx_kit_core::{Event, EventBus};
+
+use crate::dead_letter::{DeadLetter, DeadLetterSink, Failure, LogDeadLetters};
+use crate::filter::EventFilter;
+use crate::{BusMetrics, Counters, Subscriber, SubscriptionId};
+
+struct Subscription<E> {
+    id: SubscriptionId,
+    name: Cow<'static, str>,
+    filter: EventFilter,
+    subscriber: Arc<dyn Subscriber<E>>,
+}
+
+/// Delivers each event to every matching subscriber, in subscription
+/// order, on the thread that emits it.
+///
+/// `emit` returns once every subscriber has handled the event, so it is
+/// only as non-blocking as the subscribers: keep them to in-memory work,
+/// and use `ChannelBus` (feature `tokio`) for anything that does I/O. A
+/// subscriber that errors or panics gets a [`DeadLetter`] (logged by
+/// default, see [`Self::with_dead_letters`]); the panic does not reach the
+/// emitter.
+///
+/// Subscribers may subscribe, unsubscribe or emit from inside `handle`.
+pub struct FanOutBus<E: Event> {
+    subscriptions: RwLock<Vec<Subscription<E>>>,
+    counters: Counters,
+    dead_letters: Arc<dyn DeadLetterSink<E>>,
+}
+
+impl<E: Event> Default for FanOutBus<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Event> FanOutBus<E> {
+    /// A bus with no subscribers, logging dead letters.
+    pub fn new() -> Self {
+        Self {
+            subscriptions: RwLock::new(Vec::new()),
+            counters: Counters::default(),
+            dead_letters: Arc::new(LogDeadLetters),
+        }
+    }
+
+    /// Send undeliverable events to `sink` instead of the log.
+    pub fn with_dead_letters(mut self, sink: impl DeadLetterSink<E>) -> Self {
+        self.dead_letters = Arc::new(sink);
+        self
+    }
+
+    /// Deliver events matching `filter` to `subscriber`. `name` identifies
+    /// it in dead letters and logs.
+    pub fn subscribe(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        filter: EventFilter,
+        subscriber: impl Subscriber<E>,
+    ) -> SubscriptionId {
+        let id = self.counters.next_id();
+        self.write().push(Subscription {
+            id,
+            name: name.into(),
+            filter,
+            subscriber: Arc::new(subscriber),
+        });
+        id
+    }
+
+    /// Remove a subscription. Returns whether it existed.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscriptions = self.write();
+        let before = subscriptions.len();
+        subscriptions.retain(|s| s.id != id);
+        subscriptions.len() != before
+    }
+
+    /// Current counters.
+    pub fn metrics(&self) -> BusMetrics {
+        self.counters.snapshot(self.read().len())
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Vec<Subscription<E>>> {
+        self.subscriptions.read().expect("event bus lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<Subscription<E>>> {
+        self.subscriptions.write().expect("event bus lock poisoned")
+    }
+}
+
+impl<E: Event> EventBus<E> for FanOutBus<E> {
+    fn emit(&self, event: E) {
+        self.counters.published.fetch_add(1, Ordering::Relaxed);
+        // Snapshot the matching subscribers so none of them runs under
+        // the lock.
+        let targets: Vec<_> = self
+            .read()
+            .iter()
+            .filter(|s| s.filter.matches(&event))
+            .map(|s| (s.name.clone(), Arc::clone(&s.subscriber)))
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let event = Arc::new(event);
+        for (name, subscriber) in targets {
+            let failure = match catch_unwind(AssertUnwindSafe(|| subscriber.handle(&event))) {
+                Ok(Ok(())) => {
+                    self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                Ok(Err(e)) => Failure::Error(e.to_string()),
+                Err(_) => Failure::Panicked,
+            };
+            self.counters.failed.fetch_add(1, Ordering::Relaxed);
+            self.dead_letters.dead_letter(DeadLetter {
+                subscriber: name,
+                event: Arc::clone(&event),
+                failure,
+            });
+        }
+    }
+}
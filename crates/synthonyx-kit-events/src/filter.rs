@@ -0,0 +1,48 @@
+//! Subscriber filters over an event's module, name and severity.
+
+use std::borrow::Cow;
+
+use synthonyx_kit_core::{Event, Severity};
+
+/// Which events a subscriber receives.
+///
+/// Each criterion left empty matches everything; [`Self::all`] matches
+/// every event. Adding several modules (or names) matches any of them.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    modules: Vec<Cow<'static, str>>,
+    names: Vec<Cow<'static, str>>,
+    min_severity: Option<Severity>,
+}
+
+impl EventFilter {
+    /// A filter matching every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Also match events whose [`Event::module`] is `module`.
+    pub fn module(mut self, module: impl Into<Cow<'static, str>>) -> Self {
+        self.modules.push(module.into());
+        self
+    }
+
+    /// Also match events whose [`Event::name`] is `name`.
+    pub fn name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Only match events at least as severe as `severity`.
+    pub fn min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = Some(severity);
+        self
+    }
+
+    /// Whether `event` passes the filter.
+    pub fn matches<E: Event + ?Sized>(&self, event: &E) -> bool {
+        (self.modules.is_empty() || self.modules.iter().any(|m| m == event.module()))
+            && (self.names.is_empty() || self.names.iter().any(|n| n == event.name()))
+            && self.min_severity.is_none_or(|min| event.severity() >= min)
+    }
+}
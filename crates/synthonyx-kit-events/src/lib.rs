@@ -0,0 +1,108 @@
+//! Event buses for the Synthonyx Kit.
+//!
+//! [`synthonyx_kit_core::EventBus`] is the trait RTMs emit through; this
+//! crate delivers what they emit:
+//!
+//! - [`FanOutBus`]: synchronous, in-process fan-out to typed
+//!   [`Subscriber`]s on the emitting thread.
+//! - `ChannelBus` (feature `tokio`): each subscriber drains its own
+//!   bounded channel on a Tokio task, so `emit` never waits for a
+//!   subscriber.
+//!
+//! Both take an [`EventFilter`] per subscriber (module, name, minimum
+//! [`synthonyx_kit_core::Severity`]), hand undeliverable events to a
+//! [`DeadLetterSink`] instead of dropping them silently, and report
+//! [`BusMetrics`].
+//!
+//! For event sourcing, `EventStore` (feature `storage`) keeps an
+//! append-only log of events with sequence numbers, timestamps and
+//! correlation ids in a `synthonyx-kit-storage` `Backend`, and replays it
+//! into `Projection`s from any offset.
+#![deny(missing_docs, unsafe_code, rust_2018_idioms)]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use synthonyx_kit_core::Event;
+
+#[cfg(feature = "tokio")]
+mod channel;
+mod dead_letter;
+mod fanout;
+mod filter;
+#[cfg(feature = "storage")]
+mod store;
+
+#[cfg(feature = "tokio")]
+pub use channel::{AsyncSubscriber, ChannelBus};
+pub use dead_letter::{DeadLetter, DeadLetterQueue, DeadLetterSink, Failure, LogDeadLetters};
+pub use fanout::FanOutBus;
+pub use filter::EventFilter;
+#[cfg(feature = "storage")]
+pub use store::{EventStore, Projection, StoreError, StoredEvent};
+
+/// The error a subscriber returns when it cannot handle an event.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Handles events of type `E` delivered by a bus.
+///
+/// Closures `Fn(&E) -> Result<(), BoxError>` are subscribers. An error or
+/// a panic turns the event into a [`DeadLetter`] for this subscriber
+/// only; the others still receive it.
+pub trait Subscriber<E: Event>: Send + Sync + 'static {
+    /// Handle one event.
+    fn handle(&self, event: &E) -> Result<(), BoxError>;
+}
+
+impl<E: Event, F> Subscriber<E> for F
+where
+    F: Fn(&E) -> Result<(), BoxError> + Send + Sync + 'static,
+{
+    fn handle(&self, event: &E) -> Result<(), BoxError> {
+        self(event)
+    }
+}
+
+/// Identifies a subscription, for unsubscribing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Point-in-time counters of a bus.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BusMetrics {
+    /// Current number of subscriptions.
+    pub subscribers: usize,
+    /// Events emitted.
+    pub published: u64,
+    /// Deliveries a subscriber handled successfully.
+    pub delivered: u64,
+    /// Deliveries a subscriber failed or panicked on.
+    pub failed: u64,
+    /// Deliveries refused because the subscriber's queue was full or
+    /// closed.
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    next_id: AtomicU64,
+    published: AtomicU64,
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl Counters {
+    fn next_id(&self) -> SubscriptionId {
+        SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn snapshot(&self, subscribers: usize) -> BusMetrics {
+        BusMetrics {
+            subscribers,
+            published: self.published.load(Ordering::Relaxed),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
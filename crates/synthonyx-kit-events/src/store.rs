@@ -0,0 +1,301 @@
+//! Append-only event log over a [`synthonyx_kit_storage::Backend`], for
+//! event sourcing.
+//!
+//! Events are stored under sequential keys, like the audit crate's storage
+//! chain:
+//!
+//! - `prefix ++ b"e" ++ seq.to_be_bytes()` — the event with sequence
+//!   number `seq` (from 0), as a [`StoredEvent`] envelope;
+//! - `prefix ++ b"h"` — the number of events appended, as a big-endian
+//!   `u64`, written after each event.
+//!
+//! The envelope is version byte `1`, the sequence number (`u64`), the
+//! timestamp (`u128` nanoseconds), the 16-byte correlation id, all
+//! big-endian, then the event's [`Codec`] bytes.
+
+use std::marker::PhantomData;
+
+use synthonyx_kit_core::{CorrelationId, Event, OriginTrait, TimeSource, UnixNanos};
+use synthonyx_kit_storage::{Backend, Codec, CodecError, StorageError, StorageKey};
+
+use crate::BoxError;
+
+const ENVELOPE_VERSION: u8 = 1;
+const HEADER_LEN: usize = 1 + 8 + 16 + 16;
+
+/// Errors produced by an [`EventStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    /// The backend failed.
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    /// An event could not be encoded or decoded.
+    #[error(transparent)]
+    Codec(#[from] CodecError),
+    /// A stored envelope is malformed or sits under the wrong key.
+    #[error("stored event {sequence} is corrupt: {reason}")]
+    Corrupt {
+        /// The sequence number whose key held it.
+        sequence: u64,
+        /// What was wrong.
+        reason: String,
+    },
+    /// Fewer events are stored than the head record attests to.
+    #[error("event log truncated: head records {expected} events, event {missing} is missing")]
+    Truncated {
+        /// Events the head records.
+        expected: u64,
+        /// The first missing sequence number.
+        missing: u64,
+    },
+    /// A projection refused an event during replay.
+    #[error("projection failed at event {sequence}: {reason}")]
+    Projection {
+        /// The event it refused.
+        sequence: u64,
+        /// Its error.
+        reason: String,
+    },
+}
+
+/// One event as stored, with the metadata the log assigned it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StoredEvent<E> {
+    /// Position in the log, from 0, without gaps.
+    pub sequence: u64,
+    /// Wall-clock time of the append, from the store's `TimeSource`.
+    pub timestamp: UnixNanos,
+    /// Correlation id of the dispatch that produced the event.
+    pub correlation_id: CorrelationId,
+    /// The event.
+    pub event: E,
+}
+
+/// A read model rebuilt by replaying the log.
+///
+/// Replays are deterministic: the same log always yields the same events
+/// in the same order with the same metadata. A projection that derives
+/// its state only from what it is given — the stored `timestamp`, never
+/// the clock — rebuilds identically every time. Closures
+/// `FnMut(&StoredEvent<E>) -> Result<(), BoxError>` are projections.
+pub trait Projection<E> {
+    /// Fold one event into the read model.
+    fn apply(&mut self, event: &StoredEvent<E>) -> Result<(), BoxError>;
+}
+
+impl<E, F: FnMut(&StoredEvent<E>) -> Result<(), BoxError>> Projection<E> for F {
+    fn apply(&mut self, event: &StoredEvent<E>) -> Result<(), BoxError> {
+        self(event)
+    }
+}
+
+/// Append-only log of `E` events in a [`Backend`].
+///
+/// Appending takes `&mut self`, so one store instance is the single writer
+/// for its prefix; share it behind an async mutex if several tasks append.
+/// Reads and replays take `&self` and see the events appended before they
+/// started.
+pub struct EventStore<B, E, T> {
+    backend: B,
+    prefix: StorageKey,
+    time: T,
+    len: u64,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<B: Backend, E: Event + Codec, T: TimeSource> EventStore<B, E, T> {
+    /// Open the log stored under `prefix` in `backend`, timestamping new
+    /// events with `time`.
+    ///
+    /// Checks that the last event the head records is present (failing
+    /// with [`StoreError::Truncated`] otherwise) and picks up an event an
+    /// interrupted append stored without updating the head.
+    pub async fn open(backend: B, prefix: StorageKey, time: T) -> Result<Self, StoreError> {
+        let recorded = match backend.read(&head_key(&prefix)).await? {
+            Some(bytes) => {
+                let bytes: [u8; 8] =
+                    bytes
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| StoreError::Corrupt {
+                            sequence: 0,
+                            reason: format!("head record is {} bytes, not 8", bytes.len()),
+                        })?;
+                u64::from_be_bytes(bytes)
+            }
+            None => 0,
+        };
+        if recorded > 0 && !backend.exists(&event_key(&prefix, recorded - 1)).await? {
+            return Err(StoreError::Truncated {
+                expected: recorded,
+                missing: recorded - 1,
+            });
+        }
+        let mut len = recorded;
+        while backend.exists(&event_key(&prefix, len)).await? {
+            len += 1;
+        }
+        if len != recorded {
+            backend
+                .write(&head_key(&prefix), &len.to_be_bytes())
+                .await?;
+        }
+        Ok(Self {
+            backend,
+            prefix,
+            time,
+            len,
+            _event: PhantomData,
+        })
+    }
+
+    /// Number of events in the log; also the next sequence number.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the log is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The backend events are stored in.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Append `event` under `correlation_id`, returning its sequence
+    /// number.
+    pub async fn append(
+        &mut self,
+        correlation_id: CorrelationId,
+        event: &E,
+    ) -> Result<u64, StoreError> {
+        let sequence = self.len;
+        let stored = StoredEvent {
+            sequence,
+            timestamp: self.time.now(),
+            correlation_id,
+            event,
+        };
+        let bytes = encode(&stored)?;
+        self.backend
+            .write(&event_key(&self.prefix, sequence), &bytes)
+            .await?;
+        self.backend
+            .write(&head_key(&self.prefix), &(sequence + 1).to_be_bytes())
+            .await?;
+        self.len = sequence + 1;
+        Ok(sequence)
+    }
+
+    /// Append `event` under the correlation id of the dispatch `origin`.
+    pub async fn append_for<O: OriginTrait>(
+        &mut self,
+        origin: &O,
+        event: &E,
+    ) -> Result<u64, StoreError> {
+        self.append(origin.correlation_id(), event).await
+    }
+
+    /// Read the event with `sequence`, or `None` past the end of the log.
+    pub async fn get(&self, sequence: u64) -> Result<Option<StoredEvent<E>>, StoreError> {
+        if sequence >= self.len {
+            return Ok(None);
+        }
+        match self
+            .backend
+            .read(&event_key(&self.prefix, sequence))
+            .await?
+        {
+            Some(bytes) => decode(sequence, &bytes).map(Some),
+            None => Err(StoreError::Truncated {
+                expected: self.len,
+                missing: sequence,
+            }),
+        }
+    }
+
+    /// Read up to `limit` events starting at sequence `from`.
+    pub async fn load(&self, from: u64, limit: usize) -> Result<Vec<StoredEvent<E>>, StoreError> {
+        let end = self.len.min(from.saturating_add(limit as u64));
+        let mut events = Vec::new();
+        for sequence in from..end {
+            events.extend(self.get(sequence).await?);
+        }
+        Ok(events)
+    }
+
+    /// Feed every event from sequence `from` to the current end of the log
+    /// into `projection`, in order. Returns the offset to resume from
+    /// next time, i.e. the log's length when the replay started.
+    ///
+    /// Rebuilding a read model after a bug fix is `replay(0, &mut fresh)`.
+    pub async fn replay<P: Projection<E>>(
+        &self,
+        from: u64,
+        projection: &mut P,
+    ) -> Result<u64, StoreError> {
+        let end = self.len;
+        for sequence in from..end {
+            if let Some(event) = self.get(sequence).await? {
+                projection
+                    .apply(&event)
+                    .map_err(|e| StoreError::Projection {
+                        sequence,
+                        reason: e.to_string(),
+                    })?;
+            }
+        }
+        Ok(end.max(from))
+    }
+}
+
+fn encode<E: Codec>(stored: &StoredEvent<&E>) -> Result<Vec<u8>, StoreError> {
+    let payload = stored.event.encode()?;
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.push(ENVELOPE_VERSION);
+    bytes.extend_from_slice(&stored.sequence.to_be_bytes());
+    bytes.extend_from_slice(&stored.timestamp.0.to_be_bytes());
+    bytes.extend_from_slice(&stored.correlation_id.0);
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+fn decode<E: Codec>(sequence: u64, bytes: &[u8]) -> Result<StoredEvent<E>, StoreError> {
+    let corrupt = |reason: String| StoreError::Corrupt { sequence, reason };
+    if bytes.len() < HEADER_LEN {
+        return Err(corrupt(format!("envelope is only {} bytes", bytes.len())));
+    }
+    let (header, payload) = bytes.split_at(HEADER_LEN);
+    if header[0] != ENVELOPE_VERSION {
+        return Err(corrupt(format!("unknown envelope version {}", header[0])));
+    }
+    let stored_sequence = u64::from_be_bytes(header[1..9].try_into().expect("8 bytes"));
+    if stored_sequence != sequence {
+        return Err(corrupt(format!(
+            "envelope records sequence {stored_sequence}"
+        )));
+    }
+    Ok(StoredEvent {
+        sequence,
+        timestamp: UnixNanos(u128::from_be_bytes(
+            header[9..25].try_into().expect("16 bytes"),
+        )),
+        correlation_id: CorrelationId(header[25..41].try_into().expect("16 bytes")),
+        event: E::decode(payload)?,
+    })
+}
+
+fn event_key(prefix: &StorageKey, sequence: u64) -> StorageKey {
+    let mut key = prefix.0.clone();
+    key.push(b'e');
+    key.extend_from_slice(&sequence.to_be_bytes());
+    StorageKey(key)
+}
+
+fn head_key(prefix: &StorageKey) -> StorageKey {
+    let mut key = prefix.0.clone();
+    key.push(b'h');
+    StorageKey(key)
+}
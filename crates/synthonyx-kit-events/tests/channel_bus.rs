@@ -0,0 +1,172 @@
+//! `ChannelBus`: non-blocking emit, backpressure into dead letters,
+//! per-subscriber ordering, and draining on shutdown.
+
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+
+use synthonyx_kit_core::{Event, EventBus, Severity};
+use synthonyx_kit_events::{
+    AsyncSubscriber, BoxError, ChannelBus, DeadLetter, DeadLetterQueue, DeadLetterSink,
+    EventFilter, Failure,
+};
+use tokio::sync::Semaphore;
+
+#[derive(Debug)]
+struct Tick(u32, Severity);
+
+impl Event for Tick {
+    fn module(&self) -> &'static str {
+        "clock"
+    }
+    fn name(&self) -> &'static str {
+        "tick"
+    }
+    fn severity(&self) -> Severity {
+        self.1
+    }
+}
+
+type Seen = Arc<Mutex<Vec<u32>>>;
+
+/// Waits for a permit before handling each event, so the test controls
+/// when the subscriber makes progress.
+struct Gated {
+    permits: Arc<Semaphore>,
+    seen: Seen,
+}
+
+impl AsyncSubscriber<Tick> for Gated {
+    async fn handle(&self, event: Arc<Tick>) -> Result<(), BoxError> {
+        self.permits.acquire().await?.forget();
+        self.seen.lock().unwrap().push(event.0);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn full_queue_dead_letters_instead_of_blocking() {
+    let dead = DeadLetterQueue::new(16);
+    let bus = ChannelBus::new(2).with_dead_letters(dead.clone());
+    let permits = Arc::new(Semaphore::new(0));
+    let seen = Seen::default();
+    bus.subscribe(
+        "slow",
+        EventFilter::all(),
+        Gated {
+            permits: Arc::clone(&permits),
+            seen: Arc::clone(&seen),
+        },
+    );
+
+    // The subscriber takes the first event and blocks on it; two more
+    // fill its queue and the rest are refused.
+    bus.emit(Tick(0, Severity::Info));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    for n in 1..5 {
+        bus.emit(Tick(n, Severity::Info));
+    }
+    let metrics = bus.metrics();
+    assert_eq!((metrics.published, metrics.dropped), (5, 2));
+    let letters = dead.drain();
+    assert!(letters.iter().all(|l| l.failure == Failure::QueueFull));
+    assert_eq!(
+        letters.iter().map(|l| l.event.0).collect::<Vec<_>>(),
+        [3, 4]
+    );
+
+    permits.add_permits(10);
+    bus.shutdown().await;
+    assert_eq!(*seen.lock().unwrap(), [0, 1, 2]);
+    assert_eq!(bus.metrics().delivered, 3);
+    assert_eq!(bus.metrics().subscribers, 0);
+}
+
+#[tokio::test]
+async fn failures_are_isolated_and_filters_apply() {
+    let dead = DeadLetterQueue::new(16);
+    let bus = ChannelBus::new(16).with_dead_letters(dead.clone());
+    let seen = Seen::default();
+    let recorded = Arc::clone(&seen);
+    bus.subscribe(
+        "soc",
+        EventFilter::all()
+            .module("clock")
+            .min_severity(Severity::Warning),
+        move |tick: &Tick| -> Result<(), BoxError> {
+            if tick.0 == 2 {
+                panic!("subscriber bug");
+            }
+            if tick.0 == 3 {
+                return Err("siem offline".into());
+            }
+            recorded.lock().unwrap().push(tick.0);
+            Ok(())
+        },
+    );
+    bus.emit(Tick(0, Severity::Info));
+    for n in 1..5 {
+        bus.emit(Tick(n, Severity::Security));
+    }
+    bus.shutdown().await;
+
+    assert_eq!(*seen.lock().unwrap(), [1, 4]);
+    let failures: Vec<_> = dead
+        .drain()
+        .into_iter()
+        .map(|l| (l.event.0, l.failure))
+        .collect();
+    assert_eq!(
+        failures,
+        [
+            (2, Failure::Panicked),
+            (3, Failure::Error("siem offline".into()))
+        ]
+    );
+    let metrics = bus.metrics();
+    assert_eq!(
+        (metrics.published, metrics.delivered, metrics.failed),
+        (5, 2, 2)
+    );
+}
+
+/// A dead-letter sink that reads the metrics of the bus it serves.
+#[derive(Clone, Default)]
+struct Reentrant {
+    bus: Arc<OnceLock<Weak<ChannelBus<Tick>>>>,
+    dropped_seen: Arc<Mutex<Vec<u64>>>,
+}
+
+impl DeadLetterSink<Tick> for Reentrant {
+    fn dead_letter(&self, _: DeadLetter<Tick>) {
+        let bus = self.bus.get().and_then(Weak::upgrade).unwrap();
+        self.dropped_seen
+            .lock()
+            .unwrap()
+            .push(bus.metrics().dropped);
+    }
+}
+
+#[tokio::test]
+async fn dead_letter_sink_may_call_back_into_the_bus() {
+    let sink = Reentrant::default();
+    let bus = Arc::new(ChannelBus::new(1).with_dead_letters(sink.clone()));
+    sink.bus.set(Arc::downgrade(&bus)).unwrap();
+    let permits = Arc::new(Semaphore::new(0));
+    bus.subscribe(
+        "slow",
+        EventFilter::all(),
+        Gated {
+            permits: Arc::clone(&permits),
+            seen: Seen::default(),
+        },
+    );
+
+    bus.emit(Tick(0, Severity::Info));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    bus.emit(Tick(1, Severity::Info));
+    bus.emit(Tick(2, Severity::Info));
+    assert_eq!(*sink.dropped_seen.lock().unwrap(), [1]);
+
+    permits.add_permits(10);
+    bus.shutdown().await;
+}
@@ -0,0 +1,259 @@
+//! `EventStore`: sequencing, deterministic replay from an offset, and
+//! detection of damaged logs.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_core::{BaseOrigin, CorrelationId, Event, MockClock, Severity, UnixNanos};
+use synthonyx_kit_events::{BoxError, EventStore, StoreError, StoredEvent};
+use synthonyx_kit_storage::{Backend, Codec, CodecError, StorageError, StorageKey};
+
+#[derive(Clone, Default)]
+struct MemoryBackend(Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>);
+
+impl MemoryBackend {
+    fn tamper(&self, key: &[u8], f: impl FnOnce(&mut Vec<u8>)) {
+        f(self.0.lock().unwrap().get_mut(key).expect("key present"));
+    }
+
+    fn remove(&self, key: &[u8]) {
+        self.0.lock().unwrap().remove(key);
+    }
+}
+
+impl Backend for MemoryBackend {
+    async fn read(&self, key: &StorageKey) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.0.lock().unwrap().get(key.as_bytes()).cloned())
+    }
+    async fn write(&self, key: &StorageKey, value: &[u8]) -> Result<(), StorageError> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(key.as_bytes().to_vec(), value.to_vec());
+        Ok(())
+    }
+    async fn delete(&self, key: &StorageKey) -> Result<(), StorageError> {
+        self.0.lock().unwrap().remove(key.as_bytes());
+        Ok(())
+    }
+    async fn exists(&self, key: &StorageKey) -> Result<bool, StorageError> {
+        Ok(self.0.lock().unwrap().contains_key(key.as_bytes()))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Ledger {
+    Deposited(u32),
+    Withdrew(u32),
+}
+
+impl Event for Ledger {
+    fn module(&self) -> &'static str {
+        "ledger"
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Deposited(_) => "deposited",
+            Self::Withdrew(_) => "withdrew",
+        }
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+}
+
+impl Codec for Ledger {
+    fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        let (tag, amount) = match self {
+            Self::Deposited(n) => (b'd', n),
+            Self::Withdrew(n) => (b'w', n),
+        };
+        let mut bytes = vec![tag];
+        bytes.extend_from_slice(&amount.to_be_bytes());
+        Ok(bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, CodecError> {
+        let amount = bytes
+            .get(1..5)
+            .and_then(|b| b.try_into().ok())
+            .map(u32::from_be_bytes)
+            .ok_or_else(|| CodecError::Decode("short ledger event".into()))?;
+        match bytes[0] {
+            b'd' => Ok(Self::Deposited(amount)),
+            b'w' => Ok(Self::Withdrew(amount)),
+            other => Err(CodecError::Decode(format!("unknown tag {other}"))),
+        }
+    }
+}
+
+/// Balance and last-change time per correlation id (as bytes).
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Balances(BTreeMap<[u8; 16], (i64, UnixNanos)>);
+
+impl Balances {
+    fn apply(&mut self, stored: &StoredEvent<Ledger>) -> Result<(), BoxError> {
+        let delta = match stored.event {
+            Ledger::Deposited(n) => i64::from(n),
+            Ledger::Withdrew(n) => -i64::from(n),
+        };
+        let entry = self
+            .0
+            .entry(stored.correlation_id.0)
+            .or_insert((0, UnixNanos(0)));
+        entry.0 += delta;
+        entry.1 = stored.timestamp;
+        Ok(())
+    }
+}
+
+const PREFIX: &[u8] = b"ledger/";
+
+fn event_key(seq: u64) -> Vec<u8> {
+    let mut key = PREFIX.to_vec();
+    key.push(b'e');
+    key.extend_from_slice(&seq.to_be_bytes());
+    key
+}
+
+async fn open(
+    backend: &MemoryBackend,
+) -> Result<EventStore<MemoryBackend, Ledger, MockClock>, StoreError> {
+    EventStore::open(
+        backend.clone(),
+        StorageKey::new(PREFIX),
+        MockClock::new(1_700_000_000_000_000_000, 0),
+    )
+    .await
+}
+
+async fn seeded(backend: &MemoryBackend) -> EventStore<MemoryBackend, Ledger, MockClock> {
+    let mut store = open(backend).await.unwrap();
+    let alice = CorrelationId([1; 16]);
+    let bob = BaseOrigin::<String>::System {
+        correlation: CorrelationId([2; 16]),
+    };
+    assert_eq!(
+        store.append(alice, &Ledger::Deposited(100)).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        store
+            .append_for(&bob, &Ledger::Deposited(40))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(store.append(alice, &Ledger::Withdrew(30)).await.unwrap(), 2);
+    store
+}
+
+#[tokio::test]
+async fn events_are_sequenced_and_replay_deterministically() {
+    let backend = MemoryBackend::default();
+    let store = seeded(&backend).await;
+    assert_eq!(store.len(), 3);
+
+    let first = store.get(0).await.unwrap().unwrap();
+    assert_eq!(
+        first,
+        StoredEvent {
+            sequence: 0,
+            timestamp: UnixNanos(1_700_000_000_000_000_000),
+            correlation_id: CorrelationId([1; 16]),
+            event: Ledger::Deposited(100),
+        }
+    );
+    assert!(store.get(3).await.unwrap().is_none());
+
+    let mut live = Balances::default();
+    let mut apply = |e: &StoredEvent<Ledger>| live.apply(e);
+    assert_eq!(store.replay(0, &mut apply).await.unwrap(), 3);
+
+    // Rebuilding from a reopened store gives the same read model.
+    drop(store);
+    let reopened = open(&backend).await.unwrap();
+    assert_eq!(reopened.len(), 3);
+    let mut rebuilt = Balances::default();
+    let mut apply = |e: &StoredEvent<Ledger>| rebuilt.apply(e);
+    reopened.replay(0, &mut apply).await.unwrap();
+    assert_eq!(rebuilt, live);
+    assert_eq!(rebuilt.0[&[1; 16]].0, 70);
+    assert_eq!(rebuilt.0[&[2; 16]].0, 40);
+}
+
+#[tokio::test]
+async fn replay_resumes_from_an_offset() {
+    let backend = MemoryBackend::default();
+    let mut store = seeded(&backend).await;
+
+    let mut seen = Vec::new();
+    let mut collect = |e: &StoredEvent<Ledger>| -> Result<(), BoxError> {
+        seen.push(e.sequence);
+        Ok(())
+    };
+    let offset = store.replay(1, &mut collect).await.unwrap();
+    assert_eq!(offset, 3);
+
+    store
+        .append(CorrelationId([1; 16]), &Ledger::Deposited(5))
+        .await
+        .unwrap();
+    assert_eq!(store.replay(offset, &mut collect).await.unwrap(), 4);
+    assert_eq!(seen, [1, 2, 3]);
+
+    let page = store.load(2, 10).await.unwrap();
+    assert_eq!(page.iter().map(|e| e.sequence).collect::<Vec<_>>(), [2, 3]);
+
+    let mut refuse = |e: &StoredEvent<Ledger>| -> Result<(), BoxError> {
+        if e.sequence == 2 {
+            return Err("bad projection".into());
+        }
+        Ok(())
+    };
+    assert!(matches!(
+        store.replay(0, &mut refuse).await,
+        Err(StoreError::Projection { sequence: 2, .. })
+    ));
+}
+
+#[tokio::test]
+async fn damaged_logs_are_detected() {
+    let backend = MemoryBackend::default();
+    drop(seeded(&backend).await);
+
+    // A damaged sequence field no longer matches the key it is stored under.
+    backend.tamper(&event_key(1), |bytes| bytes[8] ^= 1);
+    let store = open(&backend).await.unwrap();
+    assert!(matches!(
+        store.get(1).await,
+        Err(StoreError::Corrupt { sequence: 1, .. })
+    ));
+
+    // Deleting the newest event is caught on open.
+    backend.remove(&event_key(2));
+    assert!(matches!(
+        open(&backend).await,
+        Err(StoreError::Truncated {
+            expected: 3,
+            missing: 2
+        })
+    ));
+}
+
+#[tokio::test]
+async fn an_event_written_without_its_head_is_recovered() {
+    let backend = MemoryBackend::default();
+    drop(seeded(&backend).await);
+    // Simulate a crash after the event write but before the head write.
+    let mut head = PREFIX.to_vec();
+    head.push(b'h');
+    backend.tamper(&head, |bytes| *bytes = 2u64.to_be_bytes().to_vec());
+
+    let store = open(&backend).await.unwrap();
+    assert_eq!(store.len(), 3);
+    assert_eq!(
+        store.get(2).await.unwrap().unwrap().event,
+        Ledger::Withdrew(30)
+    );
+}
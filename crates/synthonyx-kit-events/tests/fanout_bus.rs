@@ -0,0 +1,147 @@
+//! `FanOutBus`: filtering, isolation of failing subscribers, metrics.
+
+use std::sync::{Arc, Mutex};
+
+use synthonyx_kit_core::{Event, EventBus, Severity};
+use synthonyx_kit_events::{
+    BoxError, BusMetrics, DeadLetterQueue, EventFilter, Failure, FanOutBus,
+};
+
+#[derive(Debug)]
+enum UsersEvent {
+    Registered(u32),
+    LoginFailed(u32),
+}
+
+impl Event for UsersEvent {
+    fn module(&self) -> &'static str {
+        "users"
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Registered(_) => "registered",
+            Self::LoginFailed(_) => "login_failed",
+        }
+    }
+    fn severity(&self) -> Severity {
+        match self {
+            Self::Registered(_) => Severity::Info,
+            Self::LoginFailed(_) => Severity::Security,
+        }
+    }
+}
+
+fn id(event: &UsersEvent) -> u32 {
+    match event {
+        UsersEvent::Registered(n) | UsersEvent::LoginFailed(n) => *n,
+    }
+}
+
+type Seen = Arc<Mutex<Vec<u32>>>;
+
+fn recorder(seen: &Seen) -> impl Fn(&UsersEvent) -> Result<(), BoxError> + Send + Sync + 'static {
+    let seen = Arc::clone(seen);
+    move |event| {
+        seen.lock().unwrap().push(id(event));
+        Ok(())
+    }
+}
+
+#[test]
+fn filters_select_by_module_name_and_severity() {
+    let bus = FanOutBus::new();
+    let (all, security, registered, other) = (
+        Seen::default(),
+        Seen::default(),
+        Seen::default(),
+        Seen::default(),
+    );
+    bus.subscribe("all", EventFilter::all(), recorder(&all));
+    bus.subscribe(
+        "soc",
+        EventFilter::all().min_severity(Severity::Security),
+        recorder(&security),
+    );
+    bus.subscribe(
+        "onboarding",
+        EventFilter::all().module("users").name("registered"),
+        recorder(&registered),
+    );
+    bus.subscribe(
+        "billing",
+        EventFilter::all().module("billing"),
+        recorder(&other),
+    );
+
+    bus.emit(UsersEvent::Registered(1));
+    bus.emit(UsersEvent::LoginFailed(2));
+
+    assert_eq!(*all.lock().unwrap(), [1, 2]);
+    assert_eq!(*security.lock().unwrap(), [2]);
+    assert_eq!(*registered.lock().unwrap(), [1]);
+    assert!(other.lock().unwrap().is_empty());
+    assert_eq!(
+        bus.metrics(),
+        BusMetrics {
+            subscribers: 4,
+            published: 2,
+            delivered: 4,
+            failed: 0,
+            dropped: 0,
+        }
+    );
+}
+
+#[test]
+fn failing_subscribers_are_dead_lettered_without_affecting_others() {
+    let dead = DeadLetterQueue::new(8);
+    let bus = FanOutBus::new().with_dead_letters(dead.clone());
+    let seen = Seen::default();
+    bus.subscribe(
+        "erroring",
+        EventFilter::all(),
+        |_: &UsersEvent| -> Result<(), BoxError> { Err("mailer offline".into()) },
+    );
+    bus.subscribe(
+        "panicking",
+        EventFilter::all(),
+        |_: &UsersEvent| -> Result<(), BoxError> { panic!("subscriber bug") },
+    );
+    let healthy = bus.subscribe("healthy", EventFilter::all(), recorder(&seen));
+
+    bus.emit(UsersEvent::Registered(7));
+
+    assert_eq!(*seen.lock().unwrap(), [7]);
+    let letters = dead.drain();
+    assert_eq!(letters.len(), 2);
+    assert_eq!(letters[0].subscriber, "erroring");
+    assert_eq!(letters[0].failure, Failure::Error("mailer offline".into()));
+    assert_eq!(id(&letters[0].event), 7);
+    assert_eq!(letters[1].subscriber, "panicking");
+    assert_eq!(letters[1].failure, Failure::Panicked);
+    let metrics = bus.metrics();
+    assert_eq!((metrics.delivered, metrics.failed), (1, 2));
+
+    assert!(bus.unsubscribe(healthy));
+    assert!(!bus.unsubscribe(healthy));
+    bus.emit(UsersEvent::Registered(8));
+    assert_eq!(*seen.lock().unwrap(), [7]);
+    assert_eq!(bus.metrics().subscribers, 2);
+}
+
+#[test]
+fn dead_letter_queue_keeps_the_newest() {
+    let dead = DeadLetterQueue::new(1);
+    let bus = FanOutBus::new().with_dead_letters(dead.clone());
+    bus.subscribe(
+        "erroring",
+        EventFilter::all(),
+        |_: &UsersEvent| -> Result<(), BoxError> { Err("no".into()) },
+    );
+    bus.emit(UsersEvent::Registered(1));
+    bus.emit(UsersEvent::Registered(2));
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead.discarded(), 1);
+    assert_eq!(id(&dead.drain()[0].event), 2);
+    assert!(dead.is_empty());
+}
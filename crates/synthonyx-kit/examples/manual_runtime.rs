@@ -6,13 +6,10 @@
 //!
 //! Run with `cargo run --example manual_runtime -p synthonyx-kit`.
 
-use std::borrow::Cow;
-use std::collections::BTreeMap;
-
 use synthonyx_kit::audit::TracingAuditLogger;
 use synthonyx_kit::core::{
-    AuditEntry, AuditLogger, BaseOrigin, Config, CorrelationId, DispatchError, Event,
-    OriginSnapshot, OriginTrait, Outcome, Severity, SystemClock, TimeSource,
+    AuditEntry, Audited, BaseOrigin, Config, CorrelationId, Dispatch, DispatchError, Event,
+    OriginTrait, Severity, SystemClock,
 };
 
 // ---------------------------------------------------------------------------
@@ -74,35 +71,43 @@ impl<T: UsersConfig> UsersRtm<T> {
         Self { audit, time }
     }
 
+    /// Every call is audited by `Audited` — failures included — so the
+    /// method only has to describe the call.
     pub fn register_user(
         &self,
         origin: T::Origin,
         name: String,
-    ) -> Result<UsersEvent, DispatchError> {
-        if name.is_empty() {
+    ) -> Result<UsersEvent, DispatchError>
+    where
+        <T::Origin as OriginTrait>::Principal: std::fmt::Display,
+    {
+        Audited::<T, _>::new(RegisterUser { name }, &self.audit, &self.time).call(origin)
+    }
+}
+
+/// The `register_user` call.
+pub struct RegisterUser {
+    pub name: String,
+}
+
+impl<T: UsersConfig> Dispatch<T> for RegisterUser {
+    type Output = UsersEvent;
+
+    fn call(&self, _origin: T::Origin) -> Result<UsersEvent, DispatchError> {
+        if self.name.is_empty() {
             return Err(UsersError::EmptyName.into());
         }
+        Ok(UsersEvent::UserRegistered {
+            name: self.name.clone(),
+        })
+    }
+
+    fn action(&self) -> &'static str {
+        "register_user"
+    }
 
-        let entry = AuditEntry {
-            timestamp: self.time.now(),
-            correlation_id: origin.correlation_id(),
-            module: Cow::Borrowed(<T as Config>::MODULE),
-            action: Cow::Borrowed("register_user"),
-            origin: OriginSnapshot {
-                kind: origin.kind(),
-                principal: None,
-                service: None,
-            },
-            outcome: Outcome::Success,
-            subject: Some(name.clone()),
-            fields: BTreeMap::new(),
-            prev_hash: [0u8; 32],
-        };
-        self.audit
-            .record(entry)
-            .expect("audit recording must not fail in this example");
-
-        Ok(UsersEvent::UserRegistered { name })
+    fn annotate_audit(&self, entry: AuditEntry) -> AuditEntry {
+        entry.with_subject(self.name.clone())
     }
 }
 
@@ -21,10 +21,11 @@
 
 pub use synthonyx_kit_core as core;
 pub use synthonyx_kit_core::{
-    AuditEntry, AuditError, AuditLogger, BaseOrigin, Config, CorrelationId, Dispatch,
-    DispatchAsync, DispatchError, Event, EventBus, Get, Hooks, MockClock, OriginKind, OriginTrait,
-    Personal, Pii, PiiCategory, Pseudonymous, Secret, Sensitive, Severity, SpanId, SystemClock,
-    TimeSource, UnixNanos,
+    AuditEntry, AuditError, AuditFailurePolicy, AuditLogger, Audited, BaseOrigin, Config,
+    CorrelationId, Dispatch, DispatchAsync, DispatchError, Event, EventBus, Get, Hooks,
+    Interceptor, MockClock, OriginKind, OriginTrait, Personal, Pii, PiiCategory, Pipeline, Policy,
+    PolicySet, Pseudonymous, Secret, Sensitive, Severity, SpanId, SystemClock, TimeSource,
+    UnixNanos,
 };
 pub use synthonyx_kit_primitives as primitives;
 pub use synthonyx_kit_primitives::{env_param, param};
@@ -43,3 +44,6 @@ pub use synthonyx_kit_tracing as tracing;
 
 #[cfg(feature = "password")]
 pub use synthonyx_kit_password as password;
+
+#[cfg(feature = "events")]
+pub use synthonyx_kit_events as events;